@@ -0,0 +1,219 @@
+use std::{fs, io::ErrorKind};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    battlefield::Charge,
+    stats::{MatchFinishedEvent, MatchStats},
+    utils::Participant,
+};
+
+// Constants {{{
+
+const ACHIEVEMENTS_PATH: &str = "achievements.ron";
+/// The number of matches in a row the same color has to win for the win streak achievement.
+const WIN_STREAK_LENGTH: u32 = 10;
+/// Matches won in less than this many seconds earn the quick match achievement.
+const QUICK_MATCH_DURATION: f32 = 120.0;
+
+const TOAST_DURATION: f32 = 5.0;
+const TOAST_TITLE_FONT_SIZE: f32 = 28.0;
+const TOAST_BODY_FONT_SIZE: f32 = 20.0;
+const TOAST_BACKGROUND: Color = Color::srgba(0.05, 0.05, 0.05, 0.9);
+const TOAST_BORDER_COLOR: Color = Color::srgb(1.0, 0.84, 0.0);
+/// How far apart toasts shown at the same time are stacked.
+const TOAST_SPACING: f32 = 90.0;
+
+// }}}
+
+/// Milestones that carry over from match to match, saved to disk and announced with a toast when
+/// they're first reached.
+pub struct AchievementsPlugin;
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<AchievementUnlockedEvent>()
+            .add_systems(Startup, load_achievements)
+            .add_systems(
+                Update,
+                (
+                    check_saturation,
+                    check_match_result.run_if(on_event::<MatchFinishedEvent>()),
+                    save_achievements.run_if(
+                        on_event::<AchievementUnlockedEvent>()
+                            .or_else(on_event::<MatchFinishedEvent>()),
+                    ),
+                    show_toasts.run_if(on_event::<AchievementUnlockedEvent>()),
+                    remove_toasts.run_if(any_with_component::<Toast>),
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Achievement {
+    /// A charge hit the largest number it can hold.
+    Saturation,
+    /// The same color won `WIN_STREAK_LENGTH` matches in a row.
+    WinStreak,
+    /// A match was won in under `QUICK_MATCH_DURATION`.
+    QuickMatch,
+}
+impl Achievement {
+    fn title(self) -> &'static str {
+        match self {
+            Self::Saturation => "To Infinity",
+            Self::WinStreak => "Dynasty",
+            Self::QuickMatch => "Blitz",
+        }
+    }
+    fn description(self) -> String {
+        match self {
+            Self::Saturation => format!("A charge maxed out at {}", u64::MAX),
+            Self::WinStreak => format!("One color won {} matches in a row", WIN_STREAK_LENGTH),
+            Self::QuickMatch => format!(
+                "A match was won in under {} minutes",
+                QUICK_MATCH_DURATION / 60.0
+            ),
+        }
+    }
+}
+#[derive(Debug, Event, Clone, Copy)]
+pub struct AchievementUnlockedEvent(pub Achievement);
+/// Everything that gets saved to disk.
+#[derive(Debug, Resource, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct AchievementProgress {
+    unlocked: Vec<Achievement>,
+    /// The color on a winning streak, and how many matches in a row it has won.
+    streak: Option<(Participant, u32)>,
+}
+impl AchievementProgress {
+    fn unlock(
+        &mut self,
+        achievement: Achievement,
+        events: &mut EventWriter<AchievementUnlockedEvent>,
+    ) {
+        if !self.unlocked.contains(&achievement) {
+            self.unlocked.push(achievement);
+            events.send(AchievementUnlockedEvent(achievement));
+        }
+    }
+}
+#[derive(Component)]
+struct Toast(Timer);
+
+fn load_achievements(mut commands: Commands) {
+    let progress = match fs::read_to_string(ACHIEVEMENTS_PATH) {
+        Ok(text) => ron::from_str(&text).unwrap_or_else(|error| {
+            warn!("Failed to parse {}: {}", ACHIEVEMENTS_PATH, error);
+            AchievementProgress::default()
+        }),
+        Err(error) => {
+            if error.kind() != ErrorKind::NotFound {
+                warn!("Failed to read {}: {}", ACHIEVEMENTS_PATH, error);
+            }
+            AchievementProgress::default()
+        }
+    };
+    commands.insert_resource(progress);
+}
+fn check_saturation(
+    charges: Query<&Charge, Changed<Charge>>,
+    mut progress: ResMut<AchievementProgress>,
+    mut events: EventWriter<AchievementUnlockedEvent>,
+) {
+    if charges.iter().any(|charge| charge.value() == u64::MAX) {
+        progress.unlock(Achievement::Saturation, &mut events);
+    }
+}
+fn check_match_result(
+    mut finished_events: EventReader<MatchFinishedEvent>,
+    stats: Res<MatchStats>,
+    mut progress: ResMut<AchievementProgress>,
+    mut events: EventWriter<AchievementUnlockedEvent>,
+) {
+    for event in finished_events.read() {
+        let Some(winner) = event.winner else {
+            progress.streak = None;
+            continue;
+        };
+        let wins = match progress.streak {
+            Some((participant, wins)) if participant == winner => wins + 1,
+            _ => 1,
+        };
+        progress.streak = Some((winner, wins));
+        if wins >= WIN_STREAK_LENGTH {
+            progress.unlock(Achievement::WinStreak, &mut events);
+        }
+        if stats.elapsed < QUICK_MATCH_DURATION {
+            progress.unlock(Achievement::QuickMatch, &mut events);
+        }
+    }
+}
+fn save_achievements(progress: Res<AchievementProgress>) {
+    let result = ron::to_string(&*progress)
+        .map_err(|error| error.to_string())
+        .and_then(|text| fs::write(ACHIEVEMENTS_PATH, text).map_err(|error| error.to_string()));
+    if let Err(error) = result {
+        warn!(
+            "Failed to save achievements to {}: {}",
+            ACHIEVEMENTS_PATH, error
+        );
+    }
+}
+fn show_toasts(mut commands: Commands, mut events: EventReader<AchievementUnlockedEvent>) {
+    for (i, &AchievementUnlockedEvent(achievement)) in events.read().enumerate() {
+        commands
+            .spawn((
+                Name::new(format!("Achievement Toast: {:?}", achievement)),
+                Toast(Timer::from_seconds(TOAST_DURATION, TimerMode::Once)),
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        top: Val::Px(20.0 + i as f32 * TOAST_SPACING),
+                        left: Val::Px(20.0),
+                        flex_direction: FlexDirection::Column,
+                        padding: UiRect::all(Val::Px(10.0)),
+                        border: UiRect::all(Val::Px(2.0)),
+                        ..default()
+                    },
+                    background_color: TOAST_BACKGROUND.into(),
+                    border_color: TOAST_BORDER_COLOR.into(),
+                    z_index: ZIndex::Global(2),
+                    ..default()
+                },
+            ))
+            .with_children(|parent| {
+                parent.spawn(TextBundle::from_section(
+                    format!("Achievement: {}", achievement.title()),
+                    TextStyle {
+                        font: default(),
+                        font_size: TOAST_TITLE_FONT_SIZE,
+                        color: TOAST_BORDER_COLOR,
+                    },
+                ));
+                parent.spawn(TextBundle::from_section(
+                    achievement.description(),
+                    TextStyle {
+                        font: default(),
+                        font_size: TOAST_BODY_FONT_SIZE,
+                        color: Color::WHITE,
+                    },
+                ));
+            });
+    }
+}
+/// Toasts time out in real time, so they don't hang around while the game is paused.
+fn remove_toasts(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut toasts: Query<(Entity, &mut Toast)>,
+) {
+    for (entity, mut toast) in &mut toasts {
+        if toast.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
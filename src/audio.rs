@@ -0,0 +1,344 @@
+use std::{str::FromStr, time::Duration};
+
+use bevy::{audio::Volume, prelude::*};
+
+use crate::{
+    battlefield::{BulletImpactEvent, EliminationEvent, ImpactKind, MatchOutcome},
+    panel_plugin::{TriggerEvent, TriggerType},
+};
+
+// Constants {{{
+
+/// The maximum number of impact sounds that can start playing in a single frame. Burst shots can
+/// hit dozens of tiles at once, so only the loudest impacts get a voice.
+const IMPACT_VOICES_PER_FRAME: usize = 6;
+const IMPACT_SOUND_DURATION: f32 = 0.08;
+const WALL_IMPACT_FREQUENCY: f32 = 220.0;
+const TILE_IMPACT_FREQUENCY: f32 = 660.0;
+const TURRET_IMPACT_FREQUENCY: f32 = 110.0;
+/// The number of charge levels it takes for the pitch of an impact to drop by an octave.
+const IMPACT_LEVELS_PER_OCTAVE: f32 = 8.0;
+const IMPACT_MIN_PLAYBACK_SPEED: f32 = 0.25;
+/// The speed at which an impact plays at its full volume.
+const IMPACT_REFERENCE_SPEED: f32 = 500.0;
+const IMPACT_BASE_VOLUME: f32 = 0.1;
+const IMPACT_VOLUME_PER_LEVEL: f32 = 0.02;
+const IMPACT_MAX_VOLUME: f32 = 0.5;
+/// Impacts quieter than this are dropped instead of being played.
+const IMPACT_MIN_VOLUME: f32 = 0.01;
+
+/// The most trigger sounds that start playing in a single frame.
+const TRIGGER_VOICES_PER_FRAME: usize = 4;
+const TRIGGER_SOUND_DURATION: f32 = 0.12;
+const TRIGGER_VOLUME: f32 = 0.2;
+/// A Multiply zone goes up by this many octaves for every doubling of its factor.
+const MULTIPLY_OCTAVES_PER_DOUBLING: f32 = 0.25;
+const MULTIPLY_FREQUENCY: f32 = 440.0;
+const BURST_SHOT_FREQUENCY: f32 = 330.0;
+const CHARGED_SHOT_FREQUENCY: f32 = 165.0;
+/// A low, drawn out tone for a worker ball landing in a penalty zone.
+const PENALTY_STING_FREQUENCY: f32 = 82.0;
+const PENALTY_STING_DURATION: f32 = 0.35;
+const PENALTY_STING_VOLUME: f32 = 0.4;
+
+const ELIMINATION_FREQUENCY: f32 = 55.0;
+const ELIMINATION_DURATION: f32 = 0.8;
+const ELIMINATION_VOLUME: f32 = 0.5;
+
+/// Played together as a chord when the match ends: major for a winner, minor for a draw.
+const VICTORY_CHORD: [f32; 3] = [523.25, 659.25, 783.99];
+const DRAW_CHORD: [f32; 3] = [440.0, 523.25, 659.25];
+const GAME_OVER_DURATION: f32 = 1.2;
+const GAME_OVER_VOLUME: f32 = 0.15;
+
+// }}}
+
+pub struct GameAudioPlugin;
+impl Plugin for GameAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SoundVolumes>()
+            .add_systems(Startup, setup)
+            .add_systems(
+                Update,
+                (
+                    play_impact_sounds.run_if(on_event::<BulletImpactEvent>()),
+                    play_trigger_sounds.run_if(on_event::<TriggerEvent>()),
+                    play_elimination_sound.run_if(on_event::<EliminationEvent>()),
+                    play_game_over_sound.run_if(resource_changed::<MatchOutcome>),
+                ),
+            );
+    }
+}
+
+/// How loud each kind of sound plays, where 1 is the normal volume and 0 mutes it. Read from a
+/// list like `triggers=0.5,tile_hits=0`, where the kinds left out stay at 1.
+#[derive(Debug, Clone, Copy, PartialEq, Resource)]
+pub struct SoundVolumes {
+    /// Bullets hitting tiles and walls.
+    pub tile_hits: f32,
+    pub turret_hits: f32,
+    /// Worker balls landing in trigger zones.
+    pub triggers: f32,
+    pub eliminations: f32,
+    pub game_over: f32,
+    /// The background music.
+    pub music: f32,
+}
+impl Default for SoundVolumes {
+    fn default() -> Self {
+        Self {
+            tile_hits: 1.0,
+            turret_hits: 1.0,
+            triggers: 1.0,
+            eliminations: 1.0,
+            game_over: 1.0,
+            music: 1.0,
+        }
+    }
+}
+impl FromStr for SoundVolumes {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut volumes = Self::default();
+        for entry in s
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+        {
+            let Some((kind, volume)) = entry.split_once('=') else {
+                return Err(format!("`{}` should look like `triggers=0.5`", entry));
+            };
+            let volume = volume
+                .trim()
+                .parse::<f32>()
+                .ok()
+                .filter(|volume| volume.is_finite() && *volume >= 0.0)
+                .ok_or_else(|| format!("`{}` isn't a volume of 0 or more", volume.trim()))?;
+            *match kind.trim() {
+                "tile_hits" => &mut volumes.tile_hits,
+                "turret_hits" => &mut volumes.turret_hits,
+                "triggers" => &mut volumes.triggers,
+                "eliminations" => &mut volumes.eliminations,
+                "game_over" => &mut volumes.game_over,
+                "music" => &mut volumes.music,
+                other => {
+                    return Err(format!(
+                        "there's no `{}` sound, only tile_hits, turret_hits, triggers, \
+                         eliminations, game_over and music",
+                        other
+                    ))
+                }
+            } = volume;
+        }
+        Ok(volumes)
+    }
+}
+impl SoundVolumes {
+    fn impacts(&self, kind: ImpactKind) -> f32 {
+        match kind {
+            ImpactKind::Wall | ImpactKind::Tile => self.tile_hits,
+            ImpactKind::Turret => self.turret_hits,
+        }
+    }
+}
+#[derive(Resource)]
+struct ImpactSounds {
+    wall: Handle<Pitch>,
+    tile: Handle<Pitch>,
+    turret: Handle<Pitch>,
+}
+impl ImpactSounds {
+    fn get(&self, kind: ImpactKind) -> Handle<Pitch> {
+        match kind {
+            ImpactKind::Wall => self.wall.clone(),
+            ImpactKind::Tile => self.tile.clone(),
+            ImpactKind::Turret => self.turret.clone(),
+        }
+    }
+}
+#[derive(Resource)]
+struct EventSounds {
+    multiply: Handle<Pitch>,
+    burst_shot: Handle<Pitch>,
+    charged_shot: Handle<Pitch>,
+    penalty: Handle<Pitch>,
+    elimination: Handle<Pitch>,
+    victory: Vec<Handle<Pitch>>,
+    draw: Vec<Handle<Pitch>>,
+}
+impl EventSounds {
+    /// The sound for the trigger, with the speed to play it at and how loud it is.
+    fn trigger(&self, trigger_type: TriggerType) -> (Handle<Pitch>, f32, f32) {
+        match trigger_type {
+            TriggerType::Multiply(factor) => {
+                let octaves = (factor.max(1) as f32).log2() * MULTIPLY_OCTAVES_PER_DOUBLING;
+                (self.multiply.clone(), octaves.exp2(), TRIGGER_VOLUME)
+            }
+            TriggerType::BurstShot => (self.burst_shot.clone(), 1.0, TRIGGER_VOLUME),
+            TriggerType::ChargedShot => (self.charged_shot.clone(), 1.0, TRIGGER_VOLUME),
+            TriggerType::Penalty(_) => (self.penalty.clone(), 1.0, PENALTY_STING_VOLUME),
+        }
+    }
+}
+struct ImpactVoice {
+    kind: ImpactKind,
+    volume: f32,
+    speed: f32,
+}
+impl ImpactVoice {
+    fn from_event(event: &BulletImpactEvent, volumes: &SoundVolumes) -> Self {
+        let level = event.level.saturating_sub(1) as f32;
+        let volume = (IMPACT_BASE_VOLUME + level * IMPACT_VOLUME_PER_LEVEL).min(IMPACT_MAX_VOLUME)
+            * (event.speed / IMPACT_REFERENCE_SPEED).min(1.0)
+            * volumes.impacts(event.kind);
+        // Bigger bullets make deeper sounds.
+        let speed = (-level / IMPACT_LEVELS_PER_OCTAVE)
+            .exp2()
+            .max(IMPACT_MIN_PLAYBACK_SPEED);
+        Self {
+            kind: event.kind,
+            volume,
+            speed,
+        }
+    }
+}
+
+fn setup(mut commands: Commands, mut pitches: ResMut<Assets<Pitch>>) {
+    let duration = Duration::from_secs_f32(IMPACT_SOUND_DURATION);
+    commands.insert_resource(ImpactSounds {
+        wall: pitches.add(Pitch::new(WALL_IMPACT_FREQUENCY, duration)),
+        tile: pitches.add(Pitch::new(TILE_IMPACT_FREQUENCY, duration)),
+        turret: pitches.add(Pitch::new(TURRET_IMPACT_FREQUENCY, duration)),
+    });
+    let trigger_duration = Duration::from_secs_f32(TRIGGER_SOUND_DURATION);
+    let game_over_duration = Duration::from_secs_f32(GAME_OVER_DURATION);
+    let mut chord = |frequencies: [f32; 3]| {
+        frequencies
+            .map(|frequency| pitches.add(Pitch::new(frequency, game_over_duration)))
+            .to_vec()
+    };
+    let victory = chord(VICTORY_CHORD);
+    let draw = chord(DRAW_CHORD);
+    commands.insert_resource(EventSounds {
+        multiply: pitches.add(Pitch::new(MULTIPLY_FREQUENCY, trigger_duration)),
+        burst_shot: pitches.add(Pitch::new(BURST_SHOT_FREQUENCY, trigger_duration)),
+        charged_shot: pitches.add(Pitch::new(CHARGED_SHOT_FREQUENCY, trigger_duration)),
+        penalty: pitches.add(Pitch::new(
+            PENALTY_STING_FREQUENCY,
+            Duration::from_secs_f32(PENALTY_STING_DURATION),
+        )),
+        elimination: pitches.add(Pitch::new(
+            ELIMINATION_FREQUENCY,
+            Duration::from_secs_f32(ELIMINATION_DURATION),
+        )),
+        victory,
+        draw,
+    });
+}
+fn play(
+    commands: &mut Commands,
+    name: &'static str,
+    source: Handle<Pitch>,
+    volume: f32,
+    speed: f32,
+) {
+    commands.spawn((
+        Name::new(name),
+        PitchBundle {
+            source,
+            settings: PlaybackSettings::DESPAWN
+                .with_volume(Volume::new(volume))
+                .with_speed(speed),
+        },
+    ));
+}
+fn play_impact_sounds(
+    mut commands: Commands,
+    mut events: EventReader<BulletImpactEvent>,
+    sounds: Res<ImpactSounds>,
+    volumes: Res<SoundVolumes>,
+) {
+    let mut voices: Vec<_> = events
+        .read()
+        .map(|event| ImpactVoice::from_event(event, &volumes))
+        .filter(|voice| voice.volume >= IMPACT_MIN_VOLUME)
+        .collect();
+    voices.sort_unstable_by(|a, b| b.volume.total_cmp(&a.volume));
+    for voice in voices.into_iter().take(IMPACT_VOICES_PER_FRAME) {
+        play(
+            &mut commands,
+            "Impact Sound",
+            sounds.get(voice.kind),
+            voice.volume,
+            voice.speed,
+        );
+    }
+}
+/// Penalties go first, so they're never crowded out by the Multiplies landing at the same time.
+fn play_trigger_sounds(
+    mut commands: Commands,
+    mut events: EventReader<TriggerEvent>,
+    sounds: Res<EventSounds>,
+    volumes: Res<SoundVolumes>,
+) {
+    let mut trigger_types = events
+        .read()
+        .map(|event| event.trigger_type)
+        .collect::<Vec<_>>();
+    if volumes.triggers <= 0.0 {
+        return;
+    }
+    trigger_types.sort_by_key(|trigger_type| !matches!(trigger_type, TriggerType::Penalty(_)));
+    for trigger_type in trigger_types.into_iter().take(TRIGGER_VOICES_PER_FRAME) {
+        let (source, speed, volume) = sounds.trigger(trigger_type);
+        play(
+            &mut commands,
+            "Trigger Sound",
+            source,
+            volume * volumes.triggers,
+            speed,
+        );
+    }
+}
+/// Participants knocked out in the same frame share one sound.
+fn play_elimination_sound(
+    mut commands: Commands,
+    mut events: EventReader<EliminationEvent>,
+    sounds: Res<EventSounds>,
+    volumes: Res<SoundVolumes>,
+) {
+    events.clear();
+    if volumes.eliminations > 0.0 {
+        play(
+            &mut commands,
+            "Elimination Sound",
+            sounds.elimination.clone(),
+            ELIMINATION_VOLUME * volumes.eliminations,
+            1.0,
+        );
+    }
+}
+fn play_game_over_sound(
+    mut commands: Commands,
+    outcome: Res<MatchOutcome>,
+    sounds: Res<EventSounds>,
+    volumes: Res<SoundVolumes>,
+) {
+    let chord = match *outcome {
+        MatchOutcome::Undecided => return,
+        MatchOutcome::Winner(_) => &sounds.victory,
+        MatchOutcome::Draw => &sounds.draw,
+    };
+    if volumes.game_over <= 0.0 {
+        return;
+    }
+    for source in chord {
+        play(
+            &mut commands,
+            "Game Over Sound",
+            source.clone(),
+            GAME_OVER_VOLUME * volumes.game_over,
+            1.0,
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+
+use crate::{
+    battlefield::MatchOutcome,
+    config::{GameConfig, Tuning},
+    headless::{headless_app, HEADLESS_TIMESTEP},
+    panel_plugin::TriggerEvent,
+    state::AppState,
+    utils::{Participant, ParticipantMap},
+};
+
+// Constants {{{
+
+/// Matches that haven't ended after this many seconds of simulated time are counted as
+/// unfinished.
+const MAX_MATCH_DURATION: f32 = 1200.0;
+const USAGE: &str = "Usage: multiply_or_release audit <match count> [first seed]";
+
+// }}}
+
+struct AuditPlugin;
+impl Plugin for AuditPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MatchRecord>().add_systems(
+            Update,
+            (
+                count_zone_hits.run_if(on_event::<TriggerEvent>()),
+                record_outcome.run_if(in_state(AppState::GameOver)),
+            ),
+        );
+    }
+}
+
+#[derive(Resource, Default)]
+struct MatchRecord {
+    zone_hits: BTreeMap<String, u64>,
+    /// `Some` once the match is over, holding the winner or `None` for a draw.
+    outcome: Option<Option<Participant>>,
+}
+#[derive(Default)]
+struct AuditReport {
+    wins: ParticipantMap<u64>,
+    draws: u64,
+    unfinished: u64,
+    /// The total length in seconds of all the finished matches.
+    total_duration: f32,
+    zone_hits: BTreeMap<String, u64>,
+}
+impl AuditReport {
+    fn print(&self, match_count: u64) {
+        let finished = match_count - self.unfinished;
+        let percent = |count: u64, total: u64| {
+            if total == 0 {
+                0.0
+            } else {
+                count as f32 / total as f32 * 100.0
+            }
+        };
+        println!(
+            "Matches: {} ({} finished, {} unfinished)",
+            match_count, finished, self.unfinished
+        );
+        if finished > 0 {
+            println!(
+                "Average match length: {:.1}s",
+                self.total_duration / finished as f32
+            );
+        }
+        println!("Win rate by starting corner:");
+        for participant in Participant::ALL {
+            let wins = self.wins[participant];
+            // `Participant`'s `Display` doesn't pad, so it has to go through a `String` first.
+            let name = participant.to_string();
+            println!(
+                "  {:<12} {:<7} {:>5} ({:.1}%)",
+                corner_name(participant),
+                name,
+                wins,
+                percent(wins, finished)
+            );
+        }
+        println!(
+            "  {:<20} {:>5} ({:.1}%)",
+            "Draws",
+            self.draws,
+            percent(self.draws, finished)
+        );
+        println!("Trigger zone hits:");
+        let total_hits = self.zone_hits.values().sum();
+        for (zone, &hits) in &self.zone_hits {
+            println!(
+                "  {:<20} {:>7} ({:.1}%)",
+                zone,
+                hits,
+                percent(hits, total_hits)
+            );
+        }
+    }
+}
+
+/// Runs the `audit` subcommand with the arguments that follow it.
+pub fn run_cli(args: &[String]) {
+    let parse = |arg: Option<&String>, default: Option<u64>| match arg {
+        Some(arg) => arg.parse().ok(),
+        None => default,
+    };
+    let (Some(match_count), Some(first_seed)) =
+        (parse(args.first(), None), parse(args.get(1), Some(0)))
+    else {
+        eprintln!("{}", USAGE);
+        std::process::exit(2);
+    };
+    run(match_count, first_seed);
+}
+/// Runs `match_count` headless matches with consecutive seeds and prints the aggregate results.
+pub fn run(match_count: u64, first_seed: u64) {
+    let max_frames = (MAX_MATCH_DURATION / HEADLESS_TIMESTEP) as u64;
+    let mut report = AuditReport::default();
+    for seed in first_seed..first_seed + match_count {
+        let config = GameConfig {
+            seed: Some(seed),
+            ..default()
+        };
+        let mut app = headless_app(config, Tuning::default(), AuditPlugin);
+        let mut frames = 0;
+        while frames < max_frames && app.world().resource::<MatchRecord>().outcome.is_none() {
+            app.update();
+            frames += 1;
+        }
+        let record = app.world_mut().remove_resource::<MatchRecord>().unwrap();
+        for (zone, hits) in record.zone_hits {
+            *report.zone_hits.entry(zone).or_default() += hits;
+        }
+        let Some(outcome) = record.outcome else {
+            report.unfinished += 1;
+            eprintln!("Seed {}: unfinished", seed);
+            continue;
+        };
+        let duration = frames as f32 * HEADLESS_TIMESTEP;
+        report.total_duration += duration;
+        match outcome {
+            Some(winner) => {
+                report.wins[winner] += 1;
+                eprintln!("Seed {}: {} won after {:.1}s", seed, winner, duration);
+            }
+            None => {
+                report.draws += 1;
+                eprintln!("Seed {}: draw after {:.1}s", seed, duration);
+            }
+        }
+    }
+    report.print(match_count);
+}
+fn corner_name(participant: Participant) -> &'static str {
+    match participant {
+        Participant::A => "top right",
+        Participant::B => "top left",
+        Participant::C => "bottom right",
+        Participant::D => "bottom left",
+    }
+}
+fn count_zone_hits(mut events: EventReader<TriggerEvent>, mut record: ResMut<MatchRecord>) {
+    for event in events.read() {
+        let zone = event.trigger_type.to_string().replace('\n', " ");
+        *record.zone_hits.entry(zone).or_default() += 1;
+    }
+}
+/// The tie break can pick a winner out of participants eliminated together, so the winner comes
+/// from the outcome rather than from who's still standing.
+fn record_outcome(outcome: Res<MatchOutcome>, mut record: ResMut<MatchRecord>) {
+    if record.outcome.is_none() {
+        record.outcome = Some(outcome.winner());
+    }
+}
@@ -0,0 +1,248 @@
+use std::{fs, io::ErrorKind, time::Duration};
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    battlefield::{restore_battlefield, snapshot_battlefield, BattlefieldSnapshot},
+    config::{GameConfig, MatchSeed},
+    panel_plugin::seed_match_rngs,
+    state::AppState,
+    stats::{MatchFinishedEvent, MatchStats},
+};
+
+// Constants {{{
+
+const AUTOSAVE_PATH: &str = "autosave.ron";
+/// The autosave is written here first and then moved over the old one, so that a crash in the
+/// middle of writing doesn't leave a broken autosave behind.
+const AUTOSAVE_TEMP_PATH: &str = "autosave.ron.tmp";
+/// Seconds between autosaves.
+const AUTOSAVE_INTERVAL: f32 = 30.0;
+
+const PROMPT_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.7);
+const PROMPT_FONT_SIZE: f32 = 48.0;
+const PROMPT_BUTTON_FONT_SIZE: f32 = 32.0;
+const NORMAL_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
+const HOVERED_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
+
+// }}}
+
+/// Periodically saves the match to disk, and offers to resume it when play starts on the next
+/// launch if the game was closed before the match ended.
+pub struct AutosavePlugin;
+impl Plugin for AutosavePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Playing), load_autosave.run_if(run_once()))
+            .add_systems(
+                Update,
+                (
+                    handle_prompt_buttons.run_if(resource_exists::<PendingResume>),
+                    resume_match
+                        .pipe(restore_battlefield)
+                        .run_if(resource_exists::<PendingResume>),
+                    snapshot_battlefield.pipe(write_autosave).run_if(
+                        not(resource_exists::<PendingResume>)
+                            .and_then(in_state(AppState::Playing))
+                            .and_then(on_timer(Duration::from_secs_f32(AUTOSAVE_INTERVAL))),
+                    ),
+                    delete_autosave.run_if(on_event::<MatchFinishedEvent>()),
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Autosave {
+    config: GameConfig,
+    seed: u64,
+    stats: MatchStats,
+    battlefield: BattlefieldSnapshot,
+}
+/// Present while the player is being asked whether to resume the interrupted match.
+#[derive(Resource)]
+struct PendingResume {
+    autosave: Autosave,
+    /// Whether the match should be resumed, once the player has picked.
+    choice: Option<bool>,
+}
+#[derive(Component, Clone, Copy)]
+struct ResumePrompt;
+/// A button of the prompt, holding whether it resumes the match.
+#[derive(Component, Clone, Copy)]
+struct ResumeButton(bool);
+
+fn load_autosave(mut commands: Commands, mut time: ResMut<Time<Virtual>>) {
+    let text = match fs::read_to_string(AUTOSAVE_PATH) {
+        Ok(text) => text,
+        Err(error) if error.kind() == ErrorKind::NotFound => return,
+        Err(error) => {
+            warn!("Failed to read {}: {}", AUTOSAVE_PATH, error);
+            return;
+        }
+    };
+    let autosave = match ron::from_str(&text) {
+        Ok(autosave) => autosave,
+        Err(error) => {
+            warn!(
+                "Ignoring the broken autosave in {}: {}",
+                AUTOSAVE_PATH, error
+            );
+            return;
+        }
+    };
+    // Hold the new match still while the player decides.
+    time.pause();
+    commands.insert_resource(PendingResume {
+        autosave,
+        choice: None,
+    });
+    commands
+        .spawn((
+            Name::new("Resume Prompt"),
+            ResumePrompt,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(20.0),
+                    ..default()
+                },
+                background_color: PROMPT_BACKGROUND.into(),
+                z_index: ZIndex::Global(1),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Resume the interrupted match?",
+                TextStyle {
+                    font: default(),
+                    font_size: PROMPT_FONT_SIZE,
+                    color: Color::WHITE,
+                },
+            ));
+            for (label, resume) in [("Resume", true), ("New Match", false)] {
+                parent
+                    .spawn((
+                        ResumeButton(resume),
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(240.0),
+                                height: Val::Px(65.0),
+                                border: UiRect::all(Val::Px(5.0)),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            border_color: BorderColor(Color::BLACK),
+                            border_radius: BorderRadius::MAX,
+                            background_color: NORMAL_BUTTON.into(),
+                            ..default()
+                        },
+                    ))
+                    .with_children(|button| {
+                        button.spawn(TextBundle::from_section(
+                            label,
+                            TextStyle {
+                                font: default(),
+                                font_size: PROMPT_BUTTON_FONT_SIZE,
+                                color: Color::srgb(0.9, 0.9, 0.9),
+                            },
+                        ));
+                    });
+            }
+        });
+}
+fn handle_prompt_buttons(
+    mut pending: ResMut<PendingResume>,
+    mut interaction_query: Query<
+        (
+            &Interaction,
+            &ResumeButton,
+            &mut BackgroundColor,
+            &mut BorderColor,
+        ),
+        Changed<Interaction>,
+    >,
+) {
+    for (interaction, &ResumeButton(resume), mut color, mut border_color) in &mut interaction_query
+    {
+        match *interaction {
+            Interaction::Pressed => pending.choice = Some(resume),
+            Interaction::Hovered => {
+                *color = HOVERED_BUTTON.into();
+                border_color.0 = Color::WHITE;
+            }
+            Interaction::None => {
+                *color = NORMAL_BUTTON.into();
+                border_color.0 = Color::BLACK;
+            }
+        }
+    }
+}
+/// Once the player has picked, clears the prompt and hands over the battlefield to restore if the
+/// match is to be resumed.
+fn resume_match(
+    mut commands: Commands,
+    pending: Res<PendingResume>,
+    prompt: Query<Entity, With<ResumePrompt>>,
+    mut time: ResMut<Time<Virtual>>,
+    mut config: ResMut<GameConfig>,
+    mut seed: ResMut<MatchSeed>,
+    mut stats: ResMut<MatchStats>,
+) -> Option<BattlefieldSnapshot> {
+    let resume = pending.choice?;
+    commands.remove_resource::<PendingResume>();
+    for entity in &prompt {
+        commands.entity(entity).despawn_recursive();
+    }
+    time.unpause();
+    if !resume {
+        return None;
+    }
+    let autosave = &pending.autosave;
+    *config = autosave.config.clone();
+    seed.0 = autosave.seed;
+    // The generators were seeded for the match this launch started, maybe with other rules.
+    seed_match_rngs(&mut commands, config.mirror_spawns, autosave.seed);
+    *stats = autosave.stats.clone();
+    Some(autosave.battlefield.clone())
+}
+fn write_autosave(
+    In(battlefield): In<BattlefieldSnapshot>,
+    config: Res<GameConfig>,
+    seed: Res<MatchSeed>,
+    stats: Res<MatchStats>,
+) {
+    let autosave = Autosave {
+        config: config.clone(),
+        seed: seed.0,
+        stats: stats.clone(),
+        battlefield,
+    };
+    let result = ron::to_string(&autosave)
+        .map_err(|error| error.to_string())
+        .and_then(|text| {
+            fs::write(AUTOSAVE_TEMP_PATH, text)
+                .and_then(|_| fs::rename(AUTOSAVE_TEMP_PATH, AUTOSAVE_PATH))
+                .map_err(|error| error.to_string())
+        });
+    if let Err(error) = result {
+        warn!("Failed to autosave to {}: {}", AUTOSAVE_PATH, error);
+    }
+}
+/// A finished match has nothing left to resume.
+fn delete_autosave() {
+    match fs::remove_file(AUTOSAVE_PATH) {
+        Err(error) if error.kind() != ErrorKind::NotFound => {
+            warn!("Failed to delete {}: {}", AUTOSAVE_PATH, error);
+        }
+        _ => (),
+    }
+}
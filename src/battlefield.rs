@@ -3,51 +3,188 @@
 use std::{
     collections::VecDeque,
     f32::consts::{FRAC_PI_2, PI},
+    time::Duration,
 };
 
 use bevy::{color::palettes::css, prelude::*, sprite::Mesh2dHandle, time::Stopwatch};
 use bevy_hanabi::prelude::*;
 use bevy_rapier2d::prelude::*;
+use rand::{seq::IteratorRandom, Rng};
+use serde::{Deserialize, Serialize};
 
 use crate::{
     collision_groups::{self, all_new_bullets_except},
-    panel_plugin::{TriggerEvent, TriggerType},
+    config::{
+        BulletPhasePolicy, BurstPattern, DisplayConfig, GameConfig, ReleaseTuning, TieBreak,
+        Tuning, VictoryCondition,
+    },
+    mutators::MutatorEffects,
+    panel_plugin::{MatchRng, SabotageEvent, TriggerEvent, TriggerType, WorkerBall},
+    state::AppState,
     utils::{
-        BallColor, EffectPropertiesExt, Participant, ParticipantMap, TileColor, TileHitEffect,
+        BallColor, EffectPropertiesExt, EntityBudget, Participant, ParticipantMap, ScreenShake,
+        TileColor, TileHitEffect, TrailEffect,
     },
 };
 
 // Constants {{{
 
-const TILE_COUNT: usize = 100;
-const TILE_DIMENSION: f32 = BATTLEFIELD_HALF_WIDTH / TILE_COUNT as f32;
+#[cfg(not(target_arch = "wasm32"))]
+pub const DEFAULT_TILE_COUNT: usize = 100;
+/// Browsers can't keep up with as many tiles.
+#[cfg(target_arch = "wasm32")]
+pub const DEFAULT_TILE_COUNT: usize = 50;
+const LOW_SPEC_TILE_COUNT: usize = 40;
+
+/// How long the flash shown in place of the particle effects lasts when those are turned off.
+const TILE_FLASH_DURATION: f32 = 0.25;
+/// The size of the flash relative to the tile.
+const TILE_FLASH_SCALE: f32 = 3.0;
+/// Captures by the same participant within a square this wide in the same frame share one capture
+/// effect.
+const CAPTURE_EFFECT_REGION_SIZE: f32 = 48.0;
+/// How much bigger a capture effect gets for every capture after the first it stands for.
+const CAPTURE_EFFECT_GROWTH: f32 = 0.25;
+/// The capture effects that can be shown every second, on average.
+const CAPTURE_EFFECT_BUDGET_PER_SECOND: f32 = 120.0;
+/// The most capture effects that can be shown at once, after going without for a while.
+const CAPTURE_EFFECT_BUDGET_BURST: f32 = 24.0;
 pub const BATTLEFIELD_HALF_WIDTH: f32 = 360.0;
 const BATTLEFIELD_BOUNDARY_HALF_WIDTH: f32 = 50.0;
 
-const BOOSTED_TURRET_CHARGE_VALUE: u64 = 16;
+/// The charge turrets start with, and start over from after releasing unless the release zone says
+/// otherwise.
+pub const DEFAULT_BOOSTED_TURRET_CHARGE_VALUE: u64 = 16;
 /// The time in seconds after getting hit that a turret's charge will reset to 1 whenever it fires
-/// instead of [ `BOOSTED_TURRET_CHARGE_VALUE` ]
-const TURRET_BOOST_COOLDOWN: f32 = 5.0;
-const TURRET_POSITION: f32 = 330.0;
+/// instead of the boosted charge
+pub const DEFAULT_TURRET_BOOST_COOLDOWN: f32 = 5.0;
+pub const DEFAULT_TURRET_POSITION: f32 = 330.0;
 const TURRET_HEAD_COLOR: Color = Color::Srgba(css::DARK_GRAY);
 const TURRET_HEAD_THICNESS: f32 = 3.0;
 const TURRET_HEAD_LENGTH: f32 = 50.0;
+/// How much longer and thicker the barrel gets for every charge level, up to
+/// [`TURRET_HEAD_MAX_LEVEL`].
+const TURRET_HEAD_LENGTH_PER_LEVEL: f32 = 1.5;
+const TURRET_HEAD_THICNESS_PER_LEVEL: f32 = 0.5;
+const TURRET_HEAD_MAX_LEVEL: u64 = 16;
 const TURRET_ROTATION_SPEED: f32 = 0.75;
 
-const MULTI_SHOT_CHARGE_OFFSET: u64 = 8;
+pub const DEFAULT_MULTI_SHOT_CHARGE_OFFSET: u64 = 8;
+/// The angle in radians between the outermost bullets of a [`BurstPattern::Fan`].
+const FAN_SPREAD: f32 = PI / 3.0;
+/// Time in seconds between the bullets of a [`BurstPattern::Salvo`].
+const SALVO_SHOT_INTERVAL: f32 = 0.1;
+/// How far in radians a bullet aimed at a tile with [`GameConfig::tile_targeting`] can stray to
+/// either side of it.
+const TILE_TARGETING_SPREAD: f32 = 0.05;
 
-/// The width of a rectangular area at the corner where the `NEW_BULLET` tag will not be dropped.
-const NEW_BULLET_PHASE_RANGE: f32 = 2.0 * (BATTLEFIELD_HALF_WIDTH - TURRET_POSITION);
+/// How long a bullet, once fired, can't capture tiles or hit turrets, so it isn't used up at the
+/// muzzle before it gets anywhere.
+const SPAWN_PROTECTION_DURATION: f32 = 0.15;
+/// The width of a rectangular area at the corner where [`BulletPhasePolicy::Distance`] keeps the
+/// [`NewBullet`] tag on.
 const BULLET_TEXT_COLOR: Color = Color::BLACK;
 const BULLET_TEXT_FONT_SIZE_ASPECT: f32 = 0.5;
+/// How long the number on a turret takes to roll up to its new charge after a Multiply.
+const CHARGE_ROLLUP_DURATION: f32 = 0.4;
+/// Multiplying the charge by at least this much at once makes the number flash.
+const CHARGE_PULSE_RATIO: u64 = 8;
+const CHARGE_PULSE_DURATION: f32 = 0.6;
+const CHARGE_PULSE_COLOR: Color = Color::WHITE;
+const CHARGE_TEXT_SHADOW_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.6);
+/// How far the shadow sits from the text, relative to the font size.
+const CHARGE_TEXT_SHADOW_OFFSET: f32 = 0.08;
 const BULLET_MINIMUM_TEXT_SIZE: f32 = 8.0;
 const BULLET_SIZE_FACTOR: f32 = 2.0;
 const BULLET_DENSITY_FACTOR: f32 = 5.0;
+/// How far the aura of a bullet past the size cap reaches out for every level past the cap, as a
+/// fraction of the bullet's radius.
+const BULLET_AURA_SIZE_PER_LEVEL: f32 = 0.1;
+/// Pulses per second.
+const BULLET_AURA_PULSE_RATE: f32 = 2.0;
+const BULLET_AURA_ALPHA: f32 = 0.6;
 const BULLET_RESTITUTION_COEFFICIENT: f32 = 0.75;
-const CHARGED_SHOT_BULLET_SPEED: f32 = 250.0;
-const BURST_SHOT_BULLET_SPEED: f32 = 500.0;
+pub const DEFAULT_CHARGED_SHOT_BULLET_SPEED: f32 = 250.0;
+pub const DEFAULT_BURST_SHOT_BULLET_SPEED: f32 = 500.0;
 /// Time in seconds the turret will stop firing for after firing a charged shot.
 const CHARGED_SHOT_COOLDOWN: f32 = 0.5;
+/// The most that the factor of a Multiply zone goes up by for the participant furthest behind in
+/// territory, with the catch-up rule on.
+const MAX_CATCH_UP_BONUS: f32 = 2.0;
+/// With [`GameConfig::shot_ai`] on, a turret with enemy bullets this close sprays a burst shot to
+/// fend them off.
+const SHOT_AI_THREAT_RADIUS: f32 = 200.0;
+/// With [`GameConfig::overheat`] on, the heat a turret builds up every time it gets a release.
+const HEAT_PER_RELEASE: f32 = 1.0;
+/// The heat at which a turret overheats, staying that way until it has cooled down all the way.
+const MAX_HEAT: f32 = 6.0;
+/// The heat a turret loses every second.
+const HEAT_COOLING_RATE: f32 = 1.0;
+/// How fast an overheated turret turns, compared to the others.
+const OVERHEATED_ROTATION_FACTOR: f32 = 0.4;
+/// Time in seconds an overheated turret waits between shots.
+const OVERHEATED_SHOT_INTERVAL: f32 = 0.25;
+const HEAT_BAR_WIDTH: f32 = 24.0;
+const HEAT_BAR_HEIGHT: f32 = 3.0;
+/// The gap between the heat bar and the edge of the charge ball.
+const HEAT_BAR_GAP: f32 = 6.0;
+const HEAT_BAR_COLOR: Color = Color::Srgba(css::ORANGE);
+const OVERHEATED_BAR_COLOR: Color = Color::Srgba(css::RED);
+
+/// Time in seconds after the start of a match before the arena starts shrinking.
+const ARENA_SHRINK_DELAY: f32 = 120.0;
+/// The speed at which each side of the arena boundary moves inward.
+const ARENA_SHRINK_SPEED: f32 = 1.0;
+const ARENA_MIN_HALF_WIDTH: f32 = 60.0;
+const DEAD_TILE_COLOR: Color = Color::srgb(0.1, 0.1, 0.1);
+
+/// The charge level from which charge balls start to glow when bloom is on.
+const GLOW_START_LEVEL: u64 = 6;
+/// How much brighter than its normal color a charge ball gets with every level past
+/// [`GLOW_START_LEVEL`].
+const GLOW_INTENSITY_PER_LEVEL: f32 = 0.5;
+const GLOW_MAX_INTENSITY: f32 = 8.0;
+
+/// With vampiric duels on, the share of the charge lost by both sides of a bullet-turret exchange
+/// that goes to whichever side comes out of it with charge left.
+const VAMPIRIC_ABSORB_FRACTION: f64 = 0.5;
+
+/// With ball sabotage on, the share of its charge a turret loses when one of its worker balls gets
+/// bumped by an opponent's.
+const SABOTAGE_FRACTION: f64 = 0.02;
+/// How many times a second each turret can lose charge to sabotage, so a pile of balls rubbing
+/// against each other can't drain it.
+const SABOTAGE_MAX_PER_SECOND: u32 = 3;
+
+/// Once the match is over, the winner fires a bullet this often at the tiles it doesn't own yet.
+const VICTORY_LAP_INTERVAL: f32 = 0.1;
+const VICTORY_LAP_BULLET_SPEED: f32 = 150.0;
+
+/// How long a turret that made its last stand can't be hit.
+const LAST_STAND_SHIELD_DURATION: f32 = 2.0;
+const LAST_STAND_SHIELD_RADIUS: f32 = 45.0;
+const LAST_STAND_SHIELD_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.6);
+const LAST_STAND_SHOCKWAVE_RADIUS: f32 = 200.0;
+const LAST_STAND_SHAKE_DURATION: f32 = 0.5;
+const LAST_STAND_SHAKE_INTENSITY: f32 = 8.0;
+
+/// How long a turret's charge ball takes to recover from taking a charged shot.
+const KNOCKBACK_DURATION: f32 = 0.4;
+/// How far the charge ball gets knocked back for each level of damage dealt.
+const KNOCKBACK_DISTANCE_PER_LEVEL: f32 = 1.5;
+const KNOCKBACK_MAX_DISTANCE: f32 = 20.0;
+/// How much the charge ball shrinks for each level of damage dealt, as a fraction of its size.
+const KNOCKBACK_SQUASH_PER_LEVEL: f32 = 0.03;
+const KNOCKBACK_MAX_SQUASH: f32 = 0.4;
+const SHOCKWAVE_DURATION: f32 = 0.5;
+/// How far the shockwave reaches for each level of damage dealt.
+const SHOCKWAVE_RADIUS_PER_LEVEL: f32 = 8.0;
+/// The thickness of the shockwave ring relative to its radius.
+const SHOCKWAVE_THICKNESS: f32 = 0.1;
+const SHOCKWAVE_Z: f32 = 2.0;
+
+/// Where unused bullet trails are kept, out of sight above the battlefield.
+const BULLET_TRAIL_PARKING_POSITION: Vec3 = Vec3::new(0.0, 4.0 * BATTLEFIELD_HALF_WIDTH, 0.0);
 
 // Z-index
 const TILE_Z: f32 = -1.0;
@@ -57,41 +194,155 @@ const BULLET_TEXT_Z: f32 = 3.0;
 // head needs to be negative to put it behind the main turret.
 const TURRET_HEAD_Z: f32 = -1.0;
 const TURRET_PLATFORM_Z: f32 = -1.0;
+const TILE_FLASH_Z: f32 = -0.5;
+/// Relative to the turret, putting it in front of the charge ball but behind the text.
+const HEAT_BAR_Z: f32 = -0.5;
+/// Relative to the bullet or turret, in front of the charge ball but behind the text.
+const CHARGE_TEXT_SHADOW_Z: f32 = -0.25;
 
 // }}}
 
 pub struct BattlefieldPlugin;
 impl Plugin for BattlefieldPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<EliminationEvent>()
+        app.init_resource::<Tuning>()
+            .add_event::<EliminationEvent>()
             .add_event::<RestartEvent>()
+            .add_event::<BulletImpactEvent>()
+            .add_event::<TileCaptured>()
+            .add_event::<TurretDamaged>()
+            .add_event::<LastStandEvent>()
+            .add_event::<SpawnBulletEvent>()
+            .add_event::<SpawnProtectionExpired>()
+            .init_resource::<GlowMaterials>()
+            .init_resource::<TileCounts>()
+            .init_resource::<QuadrantTileCounts>()
+            .init_resource::<CatchUpBonus>()
+            .init_resource::<MatchOutcome>()
+            .init_resource::<Placements>()
+            .init_resource::<SabotageBudget>()
+            .init_resource::<VictoryLap>()
+            .register_type::<Charge>()
+            .register_type::<Turret>()
+            .register_type::<BarrelOffset>()
             .add_systems(Startup, setup)
             .add_systems(
                 Update,
                 (
                     rotate_turret,
+                    report_bullet_impacts.before(handle_bullet_tile_collision),
                     handle_bullet_tile_collision,
+                    (recolor_captured_tiles, spawn_capture_effects)
+                        .after(handle_bullet_tile_collision)
+                        .run_if(on_event::<TileCaptured>()),
                     handle_bullet_turret_collision
-                        .run_if(game_is_going)
+                        .run_if(in_state(AppState::Playing))
                         .after(handle_bullet_tile_collision),
-                    handle_trigger_events
+                    count_tiles
+                        .run_if(on_event::<TileCaptured>().or_else(on_event::<RestartEvent>()))
+                        .after(handle_bullet_tile_collision)
+                        .after(restart),
+                    update_catch_up_bonus.after(count_tiles),
+                    (record_turret_hits, knock_back_turrets)
                         .after(handle_bullet_turret_collision)
+                        .run_if(on_event::<TurretDamaged>()),
+                    handle_trigger_events
+                        .after(record_turret_hits)
+                        .after(update_catch_up_bonus)
                         .run_if(on_event::<TriggerEvent>().or_else(on_event::<RestartEvent>())),
                     update_charge_level.after(handle_trigger_events),
                     update_charge_ball.after(update_charge_level),
+                    roll_up_charges.after(update_charge_ball),
+                    update_turret_barrels.after(update_charge_level),
+                    animate_turret_knockback.after(update_charge_ball),
+                    animate_shockwaves.run_if(any_with_component::<Shockwave>),
+                    update_charge_glow
+                        .run_if(bloom_enabled)
+                        .after(update_charge_level),
                     handle_elimination
                         .run_if(on_event::<EliminationEvent>())
                         .after(update_charge_level),
                     cleanup_particle_emitters.before(handle_bullet_tile_collision),
+                    fade_tile_flashes.run_if(any_with_component::<TileFlash>),
                     restart.run_if(on_event::<RestartEvent>()),
+                    shrink_arena
+                        .run_if(in_state(AppState::Playing).and_then(shrinking_arena_enabled)),
+                    apply_arena_boundary
+                        .run_if(resource_changed::<ArenaBoundary>)
+                        .after(shrink_arena)
+                        .after(restart),
                 ),
             )
+            .add_systems(
+                Update,
+                (
+                    refill_sabotage_budget,
+                    handle_sabotage.run_if(on_event::<SabotageEvent>()),
+                )
+                    .chain()
+                    .before(update_charge_level),
+            )
+            .add_systems(
+                Update,
+                (
+                    spawn_last_stand_effects.run_if(on_event::<LastStandEvent>()),
+                    update_last_stand_shields,
+                )
+                    .chain()
+                    .after(handle_bullet_turret_collision),
+            )
+            .add_systems(
+                Update,
+                (cool_turrets.before(rotate_turret), update_heat_bars)
+                    .run_if(overheat_enabled)
+                    .after(handle_trigger_events),
+            )
+            .add_systems(Update, draw_bullet_auras.run_if(bullet_size_capped))
+            .add_systems(
+                Update,
+                (add_charge_text_shadows, update_charge_text_shadows)
+                    .chain()
+                    .run_if(charge_text_shadow_enabled)
+                    .after(roll_up_charges),
+            )
+            .add_systems(
+                PostUpdate,
+                keep_charge_text_upright.before(TransformSystem::TransformPropagate),
+            )
+            .add_systems(
+                Update,
+                spawn_requested_bullets.run_if(on_event::<SpawnBulletEvent>()),
+            )
+            .add_systems(
+                Update,
+                expire_spawn_protection
+                    .run_if(any_with_component::<SpawnProtection>)
+                    .before(handle_bullet_tile_collision),
+            )
+            .add_systems(
+                FixedUpdate,
+                victory_lap.run_if(in_state(AppState::GameOver).and_then(has_winner)),
+            )
+            .add_systems(
+                Update,
+                check_victory_conditions
+                    .run_if(in_state(AppState::Playing))
+                    .after(count_tiles)
+                    .after(handle_elimination),
+            )
+            .add_systems(
+                PostUpdate,
+                (attach_bullet_trails, update_bullet_trails)
+                    .chain()
+                    .run_if(resource_exists::<TrailEffect>)
+                    .after(TransformSystem::TransformPropagate),
+            )
             .add_systems(
                 FixedUpdate,
                 (
                     update_bullets_solver_groups.before(fire_shots),
                     fire_shots
-                        .run_if(game_is_going)
+                        .run_if(in_state(AppState::Playing))
                         .after(handle_trigger_events),
                 ),
             );
@@ -120,7 +371,12 @@ impl EffectInstanceManager {
     }
 }
 #[derive(Event, Default)]
-pub struct RestartEvent;
+pub struct RestartEvent {
+    /// Play the next match with the same seed as the one before.
+    pub rematch: bool,
+    /// Play the next match with this seed, whether or not it's a rematch.
+    pub seed: Option<u64>,
+}
 #[derive(Event)]
 pub struct EliminationEvent {
     pub participant: Participant,
@@ -130,20 +386,219 @@ impl EliminationEvent {
         Self { participant }
     }
 }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImpactKind {
+    Wall,
+    Tile,
+    Turret,
+}
+/// Sent when a turret survives a hit that would have finished it off, with the last stand rule on.
+#[derive(Debug, Event, Clone, Copy)]
+pub struct LastStandEvent {
+    pub participant: Participant,
+}
+/// Sent whenever a bullet hits an enemy turret, even if one of them had no charge to lose.
+#[derive(Debug, Event, Clone, Copy)]
+pub struct TurretDamaged {
+    pub victim: Participant,
+    pub attacker: Participant,
+    pub amount: u64,
+    /// The charge the turret has left.
+    pub remaining: u64,
+    pub charged_shot: bool,
+    pub bullet_velocity: Vec2,
+}
+/// Sent whenever a bullet takes a tile from another participant.
+#[derive(Debug, Event, Clone, Copy)]
+pub struct TileCaptured {
+    pub tile: Entity,
+    pub from: Participant,
+    pub to: Participant,
+    pub position: Vec2,
+    /// The charge the bullet has left after paying for the tile.
+    pub bullet_charge: u64,
+    pub bullet_velocity: Vec2,
+}
+/// Puts a bullet straight onto the battlefield, without a turret firing it.
+#[derive(Debug, Event)]
+pub struct SpawnBulletEvent {
+    pub owner: Participant,
+    pub position: Vec2,
+    pub charge: u64,
+    /// The direction the bullet flies in, in radians.
+    pub angle: f32,
+    pub speed: f32,
+}
+/// Sent whenever a bullet runs into a wall, an enemy tile, or a turret.
+#[derive(Debug, Event)]
+pub struct BulletImpactEvent {
+    pub kind: ImpactKind,
+    /// The charge level of the bullet at the moment of impact.
+    pub level: u64,
+    /// The speed of the bullet relative to what it hit. Everything a bullet can hit is fixed, so
+    /// this is just the speed of the bullet.
+    pub speed: f32,
+}
 #[derive(Resource)]
 pub struct SurvivorCount(pub u8);
+/// The number of tiles each participant owns, kept up to date capture by capture rather than by
+/// counting all the tiles.
+#[derive(Resource, Default)]
+pub struct TileCounts(pub ParticipantMap<u32>);
+/// The number of tiles each participant owns in each quadrant, the quadrants going by who starts
+/// out with them.
+#[derive(Resource, Default)]
+pub struct QuadrantTileCounts(pub ParticipantMap<ParticipantMap<u32>>);
+impl QuadrantTileCounts {
+    /// Everyone owning all of their own quadrant.
+    fn new(grid: TileGrid) -> Self {
+        let per_quadrant = (grid.count * grid.count) as u32;
+        let mut counts = Self::default();
+        for participant in Participant::ALL {
+            counts.0[participant][participant] = per_quadrant;
+        }
+        counts
+    }
+    /// Adds up the counts of all the quadrants.
+    fn totals(&self) -> TileCounts {
+        let mut totals = TileCounts::default();
+        for quadrant in Participant::ALL {
+            for participant in Participant::ALL {
+                totals.0[participant] += self.0[quadrant][participant];
+            }
+        }
+        totals
+    }
+}
+/// With the catch-up rule on, the surviving participant with the least territory, and how much
+/// gets added to the factors of the Multiply zones for them.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CatchUpBonus(pub Option<(Participant, u8)>);
 impl Default for SurvivorCount {
     fn default() -> Self {
         Self(4)
     }
 }
+impl SurvivorCount {
+    fn seated(participants: &ParticipantMap<bool>) -> Self {
+        Self(
+            Participant::ALL
+                .into_iter()
+                .filter(|&p| participants[p])
+                .count() as u8,
+        )
+    }
+}
+/// Where a participant finished in the match.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    /// 1 for the winner. Participants eliminated at the same time share a place.
+    pub place: u8,
+    /// When the participant was eliminated, or when the match ended for the winner, in seconds
+    /// since the start of the match.
+    pub time: f32,
+}
+/// The placements of the current match, filled in as participants get eliminated.
+#[derive(Debug, Resource, Default, Clone)]
+pub struct Placements(pub ParticipantMap<Option<Placement>>);
+/// How the match ended, decided as soon as at most one participant is left, or one of the
+/// [`VictoryCondition`]s is met.
+#[derive(Debug, Resource, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MatchOutcome {
+    #[default]
+    Undecided,
+    Winner(Participant),
+    /// The last participants were eliminated at the same time, and the tie break didn't separate
+    /// them.
+    Draw,
+}
+impl MatchOutcome {
+    pub fn winner(self) -> Option<Participant> {
+        match self {
+            Self::Winner(winner) => Some(winner),
+            Self::Undecided | Self::Draw => None,
+        }
+    }
+}
+/// The part of the battlefield state that gets autosaved. Bullets in flight and the turrets'
+/// firing queues are left out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BattlefieldSnapshot {
+    /// The owner of every tile, in the order they were spawned in.
+    tile_owners: Vec<Participant>,
+    charges: ParticipantMap<u64>,
+    survivors: ParticipantMap<bool>,
+    turret_time: f32,
+    arena_half_width: f32,
+}
+/// The tile grid of the current match, taken from the config when the match starts.
+#[derive(Resource, Clone, Copy)]
+pub struct TileGrid {
+    /// The number of tiles along each side of a quadrant.
+    count: usize,
+}
+impl TileGrid {
+    fn new(config: &GameConfig) -> Self {
+        let count = if config.low_spec {
+            config.tile_count.min(LOW_SPEC_TILE_COUNT)
+        } else {
+            config.tile_count
+        };
+        Self {
+            count: count.max(1),
+        }
+    }
+    pub fn tile_dimension(&self) -> f32 {
+        BATTLEFIELD_HALF_WIDTH / self.count as f32
+    }
+    /// The number of tiles along each side of the whole battlefield.
+    pub fn side_count(&self) -> usize {
+        self.count * 2
+    }
+    /// The cell the position is in, counting row by row from the bottom left corner.
+    fn cell_index(&self, position: Vec2) -> usize {
+        let side = self.side_count();
+        let cell = |coordinate: f32| {
+            (((coordinate + BATTLEFIELD_HALF_WIDTH) / self.tile_dimension()) as usize).min(side - 1)
+        };
+        cell(position.y) * side + cell(position.x)
+    }
+}
+/// The current extent of the battlefield, which only differs from [`BATTLEFIELD_HALF_WIDTH`] when
+/// the arena is shrinking.
+#[derive(Resource, Clone, Copy)]
+pub struct ArenaBoundary {
+    half_width: f32,
+}
+impl Default for ArenaBoundary {
+    fn default() -> Self {
+        Self {
+            half_width: BATTLEFIELD_HALF_WIDTH,
+        }
+    }
+}
+impl ArenaBoundary {
+    fn ratio(&self) -> f32 {
+        self.half_width / BATTLEFIELD_HALF_WIDTH
+    }
+    pub fn is_shrinking(&self) -> bool {
+        self.half_width < BATTLEFIELD_HALF_WIDTH
+    }
+}
 #[derive(Component, Clone, Copy)]
 struct BattlefieldRoot;
+/// The parent of all the tiles.
 #[derive(Component, Clone, Copy)]
-struct TileRoot;
+pub struct TileRoot;
 /// Marker to mark this entity as a tile.
 #[derive(Component, Clone, Copy)]
-struct Tile;
+pub struct Tile;
+/// A sprite flashed over a captured tile in place of the particle effects.
+#[derive(Component)]
+struct TileFlash(Timer);
+/// Marker for tiles that have been left outside of a shrinking arena and can no longer be captured.
+#[derive(Component, Clone, Copy)]
+struct DeadTile;
 /// Component bundle for each of the individual tiles on the battle field.
 #[derive(Bundle)]
 struct TileBundle {
@@ -161,31 +616,28 @@ struct TileBundle {
     name: Name,
 }
 impl TileBundle {
-    fn new(owner: Participant, color: Color, x: f32, y: f32) -> Self {
+    fn new(owner: Participant, color: Color, x: f32, y: f32, dimension: f32) -> Self {
         Self {
             markers: (Tile, Sensor),
             sprite_bundle: SpriteBundle {
                 transform: Transform {
                     translation: Vec3::new(x, y, TILE_Z),
-                    scale: Vec3::new(TILE_DIMENSION, TILE_DIMENSION, 1.0),
+                    scale: Vec3::new(dimension, dimension, 1.0),
                     rotation: Quat::IDENTITY,
                 },
                 sprite: Sprite { color, ..default() },
                 ..default()
             },
             collider: Collider::cuboid(0.5, 0.5),
-            collision_groups: CollisionGroups::new(
-                collision_groups::tile(owner),
-                collision_groups::all_bullets_except(owner)
-                    | collision_groups::all_new_bullets_except(owner),
-            ),
+            collision_groups: tile_collision_groups(owner),
             owner,
             name: Name::new("Tile"),
         }
     }
 }
+/// How long the current match has been going.
 #[derive(Resource, Default, Clone)]
-struct TurretStopwatch(Stopwatch);
+pub struct TurretStopwatch(Stopwatch);
 impl TurretStopwatch {
     fn get(&self) -> f32 {
         (self.0.elapsed_secs() * TURRET_ROTATION_SPEED) % (2.0 * PI)
@@ -193,20 +645,27 @@ impl TurretStopwatch {
 }
 #[derive(Component, Deref, Clone, Copy)]
 struct ChargeBallLink(Entity);
-#[derive(Debug, Component, Clone, Copy)]
-struct Charge {
+#[derive(Debug, Component, Clone, Copy, Reflect)]
+#[reflect(Component)]
+pub struct Charge {
     value: u64,
     level: u64,
 }
 impl Default for Charge {
     fn default() -> Self {
         Self {
-            value: BOOSTED_TURRET_CHARGE_VALUE,
-            level: Self::calculate_level(BOOSTED_TURRET_CHARGE_VALUE),
+            value: DEFAULT_BOOSTED_TURRET_CHARGE_VALUE,
+            level: Self::calculate_level(DEFAULT_BOOSTED_TURRET_CHARGE_VALUE),
         }
     }
 }
 impl Charge {
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+    pub fn level(&self) -> u64 {
+        self.level
+    }
     fn calculate_level(value: u64) -> u64 {
         (value as f64).log2().ceil() as u64 + 1
     }
@@ -225,19 +684,31 @@ impl Charge {
             self.value = u64::MAX;
         }
     }
-    fn reset_boosted(&mut self) {
-        self.value = BOOSTED_TURRET_CHARGE_VALUE;
+    /// Takes `percent` percent off the charge, but never all of it.
+    fn penalize(&mut self, percent: u8) {
+        let lost = (self.value as u128 * percent.min(100) as u128 / 100) as u64;
+        self.value = (self.value - lost).max(1);
+    }
+    fn reset_boosted(&mut self, value: u64) {
+        self.value = value;
         self.update_level();
     }
     fn reset(&mut self) {
         self.value = 1;
         self.level = 1;
     }
-    fn get_scale(&self) -> f32 {
-        self.level as f32 * BULLET_SIZE_FACTOR
+    /// The level the size goes by, which stops at `cap`.
+    fn size_level(&self, cap: Option<u64>) -> u64 {
+        cap.map_or(self.level, |cap| self.level.min(cap.max(1)))
+    }
+    fn get_scale(&self, cap: Option<u64>) -> f32 {
+        self.size_level(cap) as f32 * BULLET_SIZE_FACTOR
     }
-    fn get_density(&self) -> f32 {
-        self.level as f32 * BULLET_DENSITY_FACTOR
+    /// Past the size cap, the density makes up for the size, so the mass stays the same as if the
+    /// ball had kept growing.
+    fn get_density(&self, cap: Option<u64>) -> f32 {
+        let growth = self.level as f32 / self.size_level(cap) as f32;
+        self.level as f32 * BULLET_DENSITY_FACTOR * growth * growth
     }
 }
 #[derive(Bundle)]
@@ -258,12 +729,86 @@ impl ChargeBallBundle {
         }
     }
 }
+/// The over-bright materials for the charge balls that glow, indexed by participant and then by
+/// how many levels past [`GLOW_START_LEVEL`] the charge is. Filled in as needed.
+#[derive(Resource, Default)]
+struct GlowMaterials(ParticipantMap<Vec<Handle<ColorMaterial>>>);
 #[derive(Resource, Deref)]
 struct BulletMesh(Mesh2dHandle);
+#[derive(Resource, Deref)]
+struct ShockwaveMesh(Mesh2dHandle);
+/// The recoil of a turret's charge ball after taking a charged shot.
+#[derive(Component)]
+struct TurretKnockback {
+    timer: Timer,
+    offset: Vec2,
+    squash: f32,
+}
+/// A ring spreading out from a turret that took a charged shot.
+#[derive(Component)]
+struct Shockwave {
+    timer: Timer,
+    radius: f32,
+}
 #[derive(Clone, Copy, Component)]
-struct Bullet;
+pub struct Bullet;
+/// Marker for bullets that still pass through the other bullets fired by the same turret, with how
+/// long ago they were fired.
+#[derive(Component, Default)]
+pub struct NewBullet(Stopwatch);
+/// Counts down the grace period of a bullet that was just fired. Unlike [`NewBullet`], which is
+/// about where the bullet is, this is only about how long it's been around.
+#[derive(Component)]
+struct SpawnProtection(Timer);
+impl Default for SpawnProtection {
+    fn default() -> Self {
+        Self(Timer::from_seconds(
+            SPAWN_PROTECTION_DURATION,
+            TimerMode::Once,
+        ))
+    }
+}
+/// Sent when the spawn protection of a bullet runs out, with everything it's touching right then.
+/// The collisions that started while it was protected were ignored, and won't start again.
+#[derive(Event)]
+struct SpawnProtectionExpired {
+    bullet: Entity,
+    touching: Vec<Entity>,
+}
+/// Marker for bullets fired as a single charged shot rather than as part of a burst.
 #[derive(Clone, Copy, Component)]
-struct NewBullet;
+struct ChargedShot;
+/// A trail following the linked bullet.
+#[derive(Component, Clone, Copy)]
+struct BulletTrail(Entity);
+/// Marker for trails that are parked and ready to be given to the next significant shot.
+#[derive(Component, Clone, Copy)]
+struct InactiveBulletTrail;
+#[derive(Bundle)]
+struct BulletTrailBundle {
+    link: BulletTrail,
+    peb: ParticleEffectBundle,
+    name: Name,
+}
+impl BulletTrailBundle {
+    fn new(
+        target: Entity,
+        position: Vec3,
+        color: impl Into<LinearRgba>,
+        effect: Handle<EffectAsset>,
+    ) -> Self {
+        Self {
+            link: BulletTrail(target),
+            peb: ParticleEffectBundle {
+                effect: ParticleEffect::new(effect),
+                effect_properties: EffectProperties::from_spawn_color(color)
+                    .with_position(position.x, position.y),
+                ..default()
+            },
+            name: Name::new("Bullet Trail"),
+        }
+    }
+}
 /// Component bundle for the bullets that the turrets fire.
 #[derive(Bundle)]
 struct BulletBundle {
@@ -277,6 +822,7 @@ struct BulletBundle {
         LockedAxes,
         ActiveEvents,
     ),
+    spawn_protection: SpawnProtection,
     charge: Charge,
     link: ChargeBallLink,
     /// Rapier collider component.
@@ -310,7 +856,7 @@ impl BulletBundle {
             link: ChargeBallLink(ball),
             markers: (
                 Bullet,
-                NewBullet,
+                NewBullet::default(),
                 GravityScale(0.0),
                 Friction {
                     coefficient: 0.0,
@@ -323,6 +869,7 @@ impl BulletBundle {
                 LockedAxes::ROTATION_LOCKED,
                 ActiveEvents::COLLISION_EVENTS,
             ),
+            spawn_protection: SpawnProtection::default(),
             collider: Collider::ball(1.0),
             collision_groups: CollisionGroups::new(
                 collision_groups::new_bullet(owner),
@@ -357,32 +904,76 @@ impl BulletBundle {
         }
     }
 }
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Reflect)]
 enum ShotType {
     Charged,
     Multi,
 }
-#[derive(Component)]
-struct Turret {
-    firing_queue: VecDeque<(ShotType, Charge)>,
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Turret {
+    firing_queue: VecDeque<(ShotType, Charge, ReleaseTuning)>,
     last_hit_timestamp: f32,
     last_charged_shot_timestamp: f32,
+    last_salvo_shot_timestamp: f32,
+    last_shot_timestamp: f32,
+    heat: f32,
+    overheated: bool,
 }
 impl Default for Turret {
     fn default() -> Self {
         Self {
             firing_queue: VecDeque::new(),
-            last_hit_timestamp: -TURRET_BOOST_COOLDOWN,
+            last_hit_timestamp: f32::NEG_INFINITY,
             last_charged_shot_timestamp: -CHARGED_SHOT_COOLDOWN,
+            last_salvo_shot_timestamp: -SALVO_SHOT_INTERVAL,
+            last_shot_timestamp: -OVERHEATED_SHOT_INTERVAL,
+            heat: 0.0,
+            overheated: false,
+        }
+    }
+}
+impl Turret {
+    /// How long until releasing gets the turret a boosted charge again, in seconds.
+    pub fn boost_cooldown_left(&self, now: f32, cooldown: f32) -> f32 {
+        (self.last_hit_timestamp + cooldown - now).max(0.0)
+    }
+}
+/// Whether a turret has used up its last stand this match, and what's left of the shield it got.
+#[derive(Component)]
+struct LastStand {
+    used: bool,
+    shield: Timer,
+}
+impl Default for LastStand {
+    fn default() -> Self {
+        let mut shield = Timer::from_seconds(LAST_STAND_SHIELD_DURATION, TimerMode::Once);
+        shield.tick(shield.duration());
+        Self {
+            used: false,
+            shield,
         }
     }
 }
+impl LastStand {
+    fn shielded(&self) -> bool {
+        !self.shield.finished()
+    }
+}
+/// A copy of the charge in a ball, drawn behind it in a light color to make it stand out.
+#[derive(Component, Clone, Copy)]
+struct ChargeTextShadow;
+/// The ring drawn around a turret while its last stand shield is up.
+#[derive(Component, Clone, Copy)]
+struct LastStandShield(Participant);
 #[derive(Bundle)]
 struct TurretBundle {
     firing_queue: Turret,
+    last_stand: LastStand,
     charge: Charge,
     link: ChargeBallLink,
     platform: TurretPlatformLink,
+    rollup: ChargeRollup,
     text_bundle: Text2dBundle,
     owner: Participant,
     rb: RigidBody,
@@ -398,9 +989,11 @@ impl TurretBundle {
             owner,
             name: Name::new(format!("Turret: {}", owner)),
             firing_queue: Turret::default(),
+            last_stand: LastStand::default(),
             charge: Charge::default(),
             link: ChargeBallLink(ball),
             platform: TurretPlatformLink(platform),
+            rollup: ChargeRollup::default(),
             rb: RigidBody::Fixed,
             collider: Collider::ball(1.0),
             collision_groups: CollisionGroups::new(
@@ -424,6 +1017,32 @@ impl TurretBundle {
         }
     }
 }
+/// The charge shown on a turret, which rolls up to the actual charge rather than jumping to it, so
+/// a big Multiply can be seen paying off.
+#[derive(Component)]
+struct ChargeRollup {
+    shown: u64,
+    from: u64,
+    to: u64,
+    timer: Timer,
+    pulse: Timer,
+}
+impl Default for ChargeRollup {
+    fn default() -> Self {
+        let finished = |duration| {
+            let mut timer = Timer::from_seconds(duration, TimerMode::Once);
+            timer.tick(timer.duration());
+            timer
+        };
+        Self {
+            shown: 0,
+            from: 0,
+            to: 0,
+            timer: finished(CHARGE_ROLLUP_DURATION),
+            pulse: finished(CHARGE_PULSE_DURATION),
+        }
+    }
+}
 /// Marker to indicate the entity is a turret head.
 #[derive(Component)]
 struct TurretBarrel;
@@ -456,8 +1075,36 @@ impl TurretBarrelBundle {
 }
 #[derive(Component)]
 struct TurretPlatformLink(Entity);
+/// Marker for the bar under a turret showing how close it is to overheating.
+#[derive(Component, Clone, Copy)]
+struct HeatBar;
+#[derive(Bundle)]
+struct HeatBarBundle {
+    marker: HeatBar,
+    sprite_bundle: SpriteBundle,
+    name: Name,
+}
+impl HeatBarBundle {
+    fn new() -> Self {
+        Self {
+            marker: HeatBar,
+            name: Name::new("Heat Bar"),
+            sprite_bundle: SpriteBundle {
+                sprite: Sprite {
+                    color: HEAT_BAR_COLOR,
+                    custom_size: Some(Vec2::new(HEAT_BAR_WIDTH, HEAT_BAR_HEIGHT)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(0.0, 0.0, HEAT_BAR_Z),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+        }
+    }
+}
 /// Component for a turret.
-#[derive(Component, Default)]
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
 struct BarrelOffset(f32);
 /// Component bundle for a turret.
 #[derive(Bundle, Default)]
@@ -485,25 +1132,22 @@ fn setup(
     mut meshes: ResMut<Assets<Mesh>>,
     colors: Res<ParticipantMap<TileColor>>,
     materials: Res<ParticipantMap<Handle<ColorMaterial>>>,
+    mut survivors: ResMut<ParticipantMap<bool>>,
+    config: Res<GameConfig>,
+    tuning: Res<Tuning>,
 ) {
+    let grid = TileGrid::new(&config);
+    commands.insert_resource(grid);
+    let quadrant_counts = QuadrantTileCounts::new(grid);
+    commands.insert_resource(quadrant_counts.totals());
+    commands.insert_resource(quadrant_counts);
     commands.insert_resource(EffectInstanceManager::default());
+    commands.insert_resource(CaptureEffectBudget::default());
     commands.insert_resource(TurretStopwatch::default());
-    commands.insert_resource(SurvivorCount::default());
-    const OFFSET: f32 = BATTLEFIELD_HALF_WIDTH + BATTLEFIELD_BOUNDARY_HALF_WIDTH;
-    let horizontal_cuboid = Collider::cuboid(
-        BATTLEFIELD_HALF_WIDTH + BATTLEFIELD_BOUNDARY_HALF_WIDTH * 2.0,
-        BATTLEFIELD_BOUNDARY_HALF_WIDTH,
-    );
-    let vertical_cuboid = Collider::cuboid(
-        BATTLEFIELD_BOUNDARY_HALF_WIDTH,
-        BATTLEFIELD_HALF_WIDTH + BATTLEFIELD_BOUNDARY_HALF_WIDTH * 2.0,
-    );
-    let collider = Collider::compound(vec![
-        (Vect::new(OFFSET, 0.0), 0.0, vertical_cuboid.clone()),
-        (Vect::new(-OFFSET, 0.0), 0.0, vertical_cuboid.clone()),
-        (Vect::new(0.0, OFFSET), 0.0, horizontal_cuboid.clone()),
-        (Vect::new(0.0, -OFFSET), 0.0, horizontal_cuboid.clone()),
-    ]);
+    commands.insert_resource(SurvivorCount::seated(&config.participants));
+    *survivors = config.participants;
+    commands.insert_resource(ArenaBoundary::default());
+    let collider = boundary_collider(BATTLEFIELD_HALF_WIDTH);
     let root = commands
         .spawn((
             Name::new("Battlefield Root"),
@@ -525,11 +1169,38 @@ fn setup(
         .spawn((Name::new("Tile Root"), (TileRoot, SpatialBundle::default())))
         .set_parent(root)
         .id();
-    setup_tiles(&mut commands, tile_root, &colors);
+    setup_tiles(&mut commands, tile_root, &colors, grid);
     let mesh = Mesh2dHandle(meshes.add(Circle::new(1.0)));
-    let maps = setup_turrets(&mut commands, root, mesh.clone(), &materials);
+    let maps = setup_turrets(
+        &mut commands,
+        root,
+        mesh.clone(),
+        &materials,
+        config.participants,
+        tuning.turret_position,
+    );
     commands.insert_resource(maps);
     commands.insert_resource(BulletMesh(mesh));
+    commands.insert_resource(ShockwaveMesh(Mesh2dHandle(
+        meshes.add(Annulus::new(1.0 - SHOCKWAVE_THICKNESS, 1.0)),
+    )));
+}
+fn boundary_collider(half_width: f32) -> Collider {
+    let offset = half_width + BATTLEFIELD_BOUNDARY_HALF_WIDTH;
+    let horizontal_cuboid = Collider::cuboid(
+        half_width + BATTLEFIELD_BOUNDARY_HALF_WIDTH * 2.0,
+        BATTLEFIELD_BOUNDARY_HALF_WIDTH,
+    );
+    let vertical_cuboid = Collider::cuboid(
+        BATTLEFIELD_BOUNDARY_HALF_WIDTH,
+        half_width + BATTLEFIELD_BOUNDARY_HALF_WIDTH * 2.0,
+    );
+    Collider::compound(vec![
+        (Vect::new(offset, 0.0), 0.0, vertical_cuboid.clone()),
+        (Vect::new(-offset, 0.0), 0.0, vertical_cuboid.clone()),
+        (Vect::new(0.0, offset), 0.0, horizontal_cuboid.clone()),
+        (Vect::new(0.0, -offset), 0.0, horizontal_cuboid.clone()),
+    ])
 }
 fn rotate_turret(
     time: Res<Time>,
@@ -542,6 +1213,60 @@ fn rotate_turret(
         *transform = transform.with_rotation(Quat::from_rotation_z(base_offset + angle_offset));
     }
 }
+/// Cools the turrets down, and holds back the barrels of the overheated ones so they turn slower.
+fn cool_turrets(
+    time: Res<Time>,
+    mut turrets: Query<(&mut Turret, &TurretPlatformLink)>,
+    mut platforms: Query<&mut BarrelOffset>,
+) {
+    let delta = time.delta_seconds();
+    for (mut turret, &TurretPlatformLink(link)) in &mut turrets {
+        if turret.heat == 0.0 {
+            continue;
+        }
+        turret.heat = (turret.heat - HEAT_COOLING_RATE * delta).max(0.0);
+        if !turret.overheated {
+            continue;
+        }
+        if turret.heat == 0.0 {
+            turret.overheated = false;
+        } else if let Ok(mut offset) = platforms.get_mut(link) {
+            offset.0 -= TURRET_ROTATION_SPEED * (1.0 - OVERHEATED_ROTATION_FACTOR) * delta;
+        }
+    }
+}
+fn update_heat_bars(
+    config: Res<GameConfig>,
+    turrets: Query<(&Turret, &Charge, &Transform, &Children)>,
+    mut bars: Query<
+        (&mut Transform, &mut Sprite, &mut Visibility),
+        (With<HeatBar>, Without<Turret>),
+    >,
+) {
+    for (turret, charge, turret_transform, children) in &turrets {
+        let mut iter = bars.iter_many_mut(children);
+        while let Some((mut transform, mut sprite, mut visibility)) = iter.fetch_next() {
+            if turret.heat == 0.0 {
+                *visibility = Visibility::Hidden;
+                continue;
+            }
+            *visibility = Visibility::Inherited;
+            // On the side of the charge ball facing the middle of the battlefield, which keeps it
+            // on screen.
+            let ball_radius = charge
+                .get_scale(config.bullet_size_cap)
+                .max(BULLET_MINIMUM_TEXT_SIZE);
+            transform.translation.y =
+                -turret_transform.translation.y.signum() * (ball_radius + HEAT_BAR_GAP);
+            transform.scale.x = (turret.heat / MAX_HEAT).min(1.0);
+            sprite.color = if turret.overheated {
+                OVERHEATED_BAR_COLOR
+            } else {
+                HEAT_BAR_COLOR
+            };
+        }
+    }
+}
 fn update_charge_level(
     mut commands: Commands,
     mut query: Query<(Entity, &mut Charge, &Participant, Option<&Turret>), Changed<Charge>>,
@@ -557,33 +1282,83 @@ fn update_charge_level(
         }
     }
 }
-fn setup_tiles(commands: &mut Commands, tile_root: Entity, colors: &ParticipantMap<TileColor>) {
-    for i in 0..TILE_COUNT {
-        let x = TILE_DIMENSION / 2.0 + i as f32 * TILE_DIMENSION;
-        for j in 0..TILE_COUNT {
-            let y = TILE_DIMENSION / 2.0 + j as f32 * TILE_DIMENSION;
+fn fade_tile_flashes(
+    mut commands: Commands,
+    mut flashes: Query<(Entity, &mut Sprite, &mut TileFlash)>,
+    time: Res<Time>,
+) {
+    for (entity, mut sprite, mut flash) in &mut flashes {
+        flash.0.tick(time.delta());
+        if flash.0.finished() {
+            commands.entity(entity).despawn();
+        } else {
+            sprite.color.set_alpha(flash.0.fraction_remaining());
+        }
+    }
+}
+fn tile_collision_groups(owner: Participant) -> CollisionGroups {
+    CollisionGroups::new(
+        collision_groups::tile(owner),
+        collision_groups::all_bullets_except(owner) | all_new_bullets_except(owner),
+    )
+}
+fn setup_tiles(
+    commands: &mut Commands,
+    tile_root: Entity,
+    colors: &ParticipantMap<TileColor>,
+    grid: TileGrid,
+) {
+    let dimension = grid.tile_dimension();
+    for i in 0..grid.count {
+        let x = dimension / 2.0 + i as f32 * dimension;
+        for j in 0..grid.count {
+            let y = dimension / 2.0 + j as f32 * dimension;
             commands
-                .spawn(TileBundle::new(Participant::A, colors.a.0, x, y))
+                .spawn(TileBundle::new(Participant::A, colors.a.0, x, y, dimension))
                 .set_parent(tile_root);
             commands
-                .spawn(TileBundle::new(Participant::B, colors.b.0, -x, y))
+                .spawn(TileBundle::new(
+                    Participant::B,
+                    colors.b.0,
+                    -x,
+                    y,
+                    dimension,
+                ))
                 .set_parent(tile_root);
             commands
-                .spawn(TileBundle::new(Participant::C, colors.c.0, x, -y))
+                .spawn(TileBundle::new(
+                    Participant::C,
+                    colors.c.0,
+                    x,
+                    -y,
+                    dimension,
+                ))
                 .set_parent(tile_root);
             commands
-                .spawn(TileBundle::new(Participant::D, colors.d.0, -x, -y))
+                .spawn(TileBundle::new(
+                    Participant::D,
+                    colors.d.0,
+                    -x,
+                    -y,
+                    dimension,
+                ))
                 .set_parent(tile_root);
         }
     }
 }
+/// Participants sitting the match out get no turret, and [`Entity::PLACEHOLDER`] in its place.
 fn setup_turrets(
     commands: &mut Commands,
     root: Entity,
     mesh: Mesh2dHandle,
     materials: &ParticipantMap<Handle<ColorMaterial>>,
+    seated: ParticipantMap<bool>,
+    position: f32,
 ) -> ParticipantMap<Entity> {
     let mut spawn_turret = |owner: Participant, base_offset: f32, x: f32, y: f32| {
+        if !seated[owner] {
+            return Entity::PLACEHOLDER;
+        }
         let ball = commands
             .spawn(ChargeBallBundle::new(
                 mesh.clone(),
@@ -597,23 +1372,38 @@ fn setup_turrets(
         commands
             .spawn(TurretBarrelBundle::new())
             .set_parent(platform);
+        let heat_bar = commands.spawn(HeatBarBundle::new()).id();
         commands
             .spawn(TurretBundle::new(owner, x, y, ball, platform))
             .set_parent(root)
-            .push_children(&[ball, platform])
+            .push_children(&[ball, platform, heat_bar])
             .id()
     };
-    let a = spawn_turret(Participant::A, PI, TURRET_POSITION, TURRET_POSITION);
-    let b = spawn_turret(
-        Participant::B,
-        -FRAC_PI_2,
-        -TURRET_POSITION,
-        TURRET_POSITION,
-    );
-    let c = spawn_turret(Participant::C, FRAC_PI_2, TURRET_POSITION, -TURRET_POSITION);
-    let d = spawn_turret(Participant::D, 0.0, -TURRET_POSITION, -TURRET_POSITION);
+    let a = spawn_turret(Participant::A, PI, position, position);
+    let b = spawn_turret(Participant::B, -FRAC_PI_2, -position, position);
+    let c = spawn_turret(Participant::C, FRAC_PI_2, position, -position);
+    let d = spawn_turret(Participant::D, 0.0, -position, -position);
     ParticipantMap::new(a, b, c, d)
 }
+/// Makes the barrel of every turret longer and thicker the more charge it holds, like a loaded
+/// cannon.
+fn update_turret_barrels(
+    turrets: Query<(&Charge, &TurretPlatformLink), Changed<Charge>>,
+    platforms: Query<&Children>,
+    mut barrels: Query<&mut Transform, With<TurretBarrel>>,
+) {
+    for (charge, &TurretPlatformLink(platform)) in &turrets {
+        let level = charge.level.min(TURRET_HEAD_MAX_LEVEL) as f32;
+        let length = TURRET_HEAD_LENGTH + level * TURRET_HEAD_LENGTH_PER_LEVEL;
+        let thickness = TURRET_HEAD_THICNESS + level * TURRET_HEAD_THICNESS_PER_LEVEL;
+        let mut iter = barrels.iter_many_mut(platforms.get(platform).into_iter().flatten());
+        while let Some(mut transform) = iter.fetch_next() {
+            transform.translation.x = length / 2.0;
+            transform.scale.x = length;
+            transform.scale.y = thickness;
+        }
+    }
+}
 fn update_charge_ball(
     mut balls: Query<
         (
@@ -623,25 +1413,41 @@ fn update_charge_ball(
             &Charge,
             &ChargeBallLink,
             Entity,
+            Has<ChargeRollup>,
         ),
         Or<(Changed<Charge>, Added<Charge>)>,
     >,
     turret_query: Query<(), With<Turret>>,
     mut transform_query: Query<&mut Transform>,
+    config: Res<GameConfig>,
+    display_config: Res<DisplayConfig>,
+    mutators: Res<MutatorEffects>,
 ) {
-    for (mut collider_scale, mass_properties, mut text, charge, &ChargeBallLink(link), entity) in
-        &mut balls
+    for (
+        mut collider_scale,
+        mass_properties,
+        mut text,
+        charge,
+        &ChargeBallLink(link),
+        entity,
+        rolls_up,
+    ) in &mut balls
     {
-        let mut scale = charge.get_scale();
-        if scale < BULLET_MINIMUM_TEXT_SIZE && turret_query.get(entity).is_ok() {
-            scale = BULLET_MINIMUM_TEXT_SIZE;
+        let mut scale = charge.get_scale(config.bullet_size_cap);
+        let mut density = charge.get_density(config.bullet_size_cap);
+        if turret_query.get(entity).is_ok() {
+            scale = scale.max(BULLET_MINIMUM_TEXT_SIZE);
+        } else {
+            // Giant bullets weigh the same as usual, spread out over more space.
+            scale *= mutators.bullet_size_factor;
+            density /= mutators.bullet_size_factor * mutators.bullet_size_factor;
         }
         let new_scale = ColliderScale::Absolute(Vect::splat(scale));
         if *collider_scale != new_scale {
             *collider_scale = new_scale;
         }
         if let Some(mut mass_properties) = mass_properties {
-            let new_density = ColliderMassProperties::Density(charge.get_density());
+            let new_density = ColliderMassProperties::Density(density);
             if *mass_properties != new_density {
                 *mass_properties = new_density;
             }
@@ -649,64 +1455,312 @@ fn update_charge_ball(
         let mut ball_transform = transform_query.get_mut(link).unwrap();
         ball_transform.scale.x = scale;
         ball_transform.scale.y = scale;
-        let diameter = scale * 2.0;
-        let section = &mut text.sections[0];
-        if diameter < BULLET_MINIMUM_TEXT_SIZE {
-            section.value.clear();
-        } else {
-            section.value = charge.value.to_string();
-            let digit_count = section.value.len() as f32;
-            let full_size_horizontal = diameter * BULLET_TEXT_FONT_SIZE_ASPECT * digit_count;
-            if diameter < full_size_horizontal {
-                section.style.font_size = diameter / digit_count / BULLET_TEXT_FONT_SIZE_ASPECT;
-            } else {
-                section.style.font_size = diameter;
-            }
+        // The number on turrets is taken care of in `roll_up_charges`.
+        if !rolls_up {
+            fit_charge_text(
+                &mut text.sections[0],
+                charge.value,
+                scale * 2.0,
+                display_config.readable_charge_text_size,
+            );
         }
     }
 }
-fn update_bullets_solver_groups(
-    mut commands: Commands,
-    rapier: Res<RapierContext>,
-    mut bullet_query: Query<
-        (
-            Entity,
-            &mut CollisionGroups,
-            &mut SolverGroups,
-            &Participant,
-            &Transform,
-        ),
-        With<NewBullet>,
-    >,
+/// Writes the charge in a ball this wide, shrinking the text so it fits. If that would make it
+/// smaller than `readable_size`, it's written as the power of two it rounds up to instead, which
+/// takes fewer characters.
+fn fit_charge_text(section: &mut TextSection, value: u64, diameter: f32, readable_size: f32) {
+    if diameter < BULLET_MINIMUM_TEXT_SIZE {
+        section.value.clear();
+        return;
+    }
+    let font_size = |text: &str| {
+        let digit_count = text.len() as f32;
+        (diameter / digit_count / BULLET_TEXT_FONT_SIZE_ASPECT).min(diameter)
+    };
+    section.value = value.to_string();
+    if font_size(&section.value) < readable_size {
+        section.value = format!("2^{}", Charge::calculate_level(value) - 1);
+    }
+    section.style.font_size = font_size(&section.value);
+}
+/// Rolls the number on every turret up to its charge after a Multiply, going through the values
+/// geometrically so every doubling takes as long. Anything that lowers the charge shows right
+/// away.
+fn roll_up_charges(
+    mut turrets: Query<(&Charge, &mut ChargeRollup, &mut Text)>,
+    config: Res<GameConfig>,
+    display_config: Res<DisplayConfig>,
+    time: Res<Time>,
 ) {
-    for (entity, mut collision_groups, mut solver_groups, &participant, transform) in
-        &mut bullet_query
-    {
-        if BATTLEFIELD_HALF_WIDTH - transform.translation.x.abs() < NEW_BULLET_PHASE_RANGE
-            && BATTLEFIELD_HALF_WIDTH - transform.translation.y.abs() < NEW_BULLET_PHASE_RANGE
-        {
-            continue;
+    for (charge, mut rollup, mut text) in &mut turrets {
+        let rollup = rollup.as_mut();
+        if charge.value != rollup.to {
+            if charge.value > rollup.shown {
+                // Not when the turret first shows up.
+                if rollup.shown > 0
+                    && charge.value >= rollup.shown.saturating_mul(CHARGE_PULSE_RATIO)
+                {
+                    rollup.pulse.reset();
+                }
+                rollup.from = rollup.shown.max(1);
+                rollup.timer.reset();
+            } else {
+                rollup.from = charge.value;
+                rollup.timer.tick(rollup.timer.duration());
+            }
+            rollup.to = charge.value;
         }
-        if !rapier
-            .contact_pairs_with(entity)
-            .any(|x| x.has_any_active_contact())
-        {
-            collision_groups.memberships = collision_groups::bullet(participant);
-            collision_groups.filters = collision_groups::BATTLEFIELD_ROOT
-                | collision_groups::ALL_BULLETS
-                | collision_groups::ALL_NEW_BULLETS
-                | collision_groups::ALL_TURRETS
-                | collision_groups::all_tiles_except(participant);
-            solver_groups.memberships = collision_groups::bullet(participant);
-            solver_groups.filters = collision_groups::BATTLEFIELD_ROOT
-                | collision_groups::ALL_BULLETS
-                | collision_groups::ALL_NEW_BULLETS
-                | collision_groups::ALL_TURRETS;
-            commands.entity(entity).remove::<NewBullet>();
+        let pulsing = !rollup.pulse.finished();
+        if rollup.timer.finished() && rollup.shown == rollup.to && !pulsing {
+            continue;
         }
+        rollup.timer.tick(time.delta());
+        rollup.pulse.tick(time.delta());
+        let t = rollup.timer.fraction();
+        // Eases out, so the number slows down as it gets close.
+        let eased = 1.0 - (1.0 - t) * (1.0 - t);
+        let ratio = rollup.to as f64 / rollup.from.max(1) as f64;
+        rollup.shown = if rollup.timer.finished() {
+            rollup.to
+        } else {
+            ((rollup.from as f64 * ratio.powf(eased as f64)) as u64).min(rollup.to)
+        };
+        let diameter = charge
+            .get_scale(config.bullet_size_cap)
+            .max(BULLET_MINIMUM_TEXT_SIZE)
+            * 2.0;
+        let section = &mut text.sections[0];
+        fit_charge_text(
+            section,
+            rollup.shown,
+            diameter,
+            display_config.readable_charge_text_size,
+        );
+        section.style.color =
+            BULLET_TEXT_COLOR.mix(&CHARGE_PULSE_COLOR, rollup.pulse.fraction_remaining());
     }
 }
-fn fire_shots(
+fn bloom_enabled(config: Res<DisplayConfig>) -> bool {
+    config.bloom
+}
+fn charge_text_shadow_enabled(config: Res<DisplayConfig>) -> bool {
+    config.charge_text_shadow
+}
+fn add_charge_text_shadows(
+    mut commands: Commands,
+    charges: Query<Entity, (Added<Charge>, With<Text>)>,
+) {
+    for entity in &charges {
+        commands.entity(entity).with_children(|parent| {
+            parent.spawn((
+                Name::new("Charge Text Shadow"),
+                ChargeTextShadow,
+                Text2dBundle {
+                    text: Text::from_section(
+                        "",
+                        TextStyle {
+                            font: default(),
+                            font_size: BULLET_SIZE_FACTOR,
+                            color: CHARGE_TEXT_SHADOW_COLOR,
+                        },
+                    ),
+                    transform: Transform::from_xyz(0.0, 0.0, CHARGE_TEXT_SHADOW_Z),
+                    ..default()
+                },
+            ));
+        });
+    }
+}
+/// Copies the charge text into its shadow, offset down and to the right by a bit of the font
+/// size.
+fn update_charge_text_shadows(
+    texts: Query<(&Text, &Children), (Changed<Text>, With<Charge>)>,
+    mut shadows: Query<(&mut Text, &mut Transform), (With<ChargeTextShadow>, Without<Charge>)>,
+) {
+    for (text, children) in &texts {
+        let mut iter = shadows.iter_many_mut(children);
+        while let Some((mut shadow, mut transform)) = iter.fetch_next() {
+            let (from, to) = (&text.sections[0], &mut shadow.sections[0]);
+            to.value.clone_from(&from.value);
+            to.style.font_size = from.style.font_size;
+            let offset = from.style.font_size * CHARGE_TEXT_SHADOW_OFFSET;
+            transform.translation.x = offset;
+            transform.translation.y = -offset;
+        }
+    }
+}
+/// Keeps the charges upright whatever turns the balls they're in, so they can always be read.
+fn keep_charge_text_upright(
+    mut charges: Query<&mut Transform, (Changed<Transform>, With<Charge>, With<Text>)>,
+) {
+    for mut transform in &mut charges {
+        if transform.rotation != Quat::IDENTITY {
+            transform.rotation = Quat::IDENTITY;
+        }
+    }
+}
+/// Makes the charge balls glow brighter the higher their charge, so that they bloom.
+fn update_charge_glow(
+    charges: Query<(&Charge, &ChargeBallLink, &Participant), Changed<Charge>>,
+    mut ball_materials: Query<&mut Handle<ColorMaterial>>,
+    mut glow_materials: ResMut<GlowMaterials>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    base_materials: Res<ParticipantMap<Handle<ColorMaterial>>>,
+    colors: Res<ParticipantMap<BallColor>>,
+) {
+    for (charge, &ChargeBallLink(link), &owner) in &charges {
+        let Ok(mut ball_material) = ball_materials.get_mut(link) else {
+            continue;
+        };
+        let material = match charge.level.checked_sub(GLOW_START_LEVEL) {
+            None => base_materials.get(owner).clone(),
+            Some(step) => {
+                let step = step as usize;
+                let cache = &mut glow_materials.0[owner];
+                while cache.len() <= step {
+                    let intensity = (1.0 + (cache.len() + 1) as f32 * GLOW_INTENSITY_PER_LEVEL)
+                        .min(GLOW_MAX_INTENSITY);
+                    let color = (colors.get(owner).0.to_linear() * intensity).with_alpha(1.0);
+                    cache.push(materials.add(ColorMaterial::from(Color::from(color))));
+                }
+                cache[step].clone()
+            }
+        };
+        if *ball_material != material {
+            *ball_material = material;
+        }
+    }
+}
+/// Whether a bullet that was just fired, and isn't touching anything, can stop passing through the
+/// bullets fired by the same turret.
+fn can_phase_in(
+    policy: BulletPhasePolicy,
+    position: Vec2,
+    half_width: f32,
+    turret_position: f32,
+    age: Duration,
+) -> bool {
+    match policy {
+        BulletPhasePolicy::Distance => {
+            // Out of the corner the turret sits in.
+            let range = 2.0 * (BATTLEFIELD_HALF_WIDTH - turret_position);
+            half_width - position.x.abs() >= range || half_width - position.y.abs() >= range
+        }
+        BulletPhasePolicy::Timed(millis) => age >= Duration::from_millis(millis as u64),
+        BulletPhasePolicy::Contact => true,
+    }
+}
+fn update_bullets_solver_groups(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    tuning: Res<Tuning>,
+    rapier: Res<RapierContext>,
+    boundary: Res<ArenaBoundary>,
+    mut bullet_query: Query<(
+        Entity,
+        &mut NewBullet,
+        &mut CollisionGroups,
+        &mut SolverGroups,
+        &Participant,
+        &Transform,
+    )>,
+) {
+    for (
+        entity,
+        mut new_bullet,
+        mut collision_groups,
+        mut solver_groups,
+        &participant,
+        transform,
+    ) in &mut bullet_query
+    {
+        let age = new_bullet.0.tick(time.delta()).elapsed();
+        if !can_phase_in(
+            config.bullet_phase,
+            transform.translation.xy(),
+            boundary.half_width,
+            tuning.turret_position,
+            age,
+        ) {
+            continue;
+        }
+        if !rapier
+            .contact_pairs_with(entity)
+            .any(|x| x.has_any_active_contact())
+        {
+            collision_groups.memberships = collision_groups::bullet(participant);
+            collision_groups.filters = collision_groups::BATTLEFIELD_ROOT
+                | collision_groups::ALL_BULLETS
+                | collision_groups::ALL_NEW_BULLETS
+                | collision_groups::ALL_TURRETS
+                | collision_groups::all_tiles_except(participant);
+            solver_groups.memberships = collision_groups::bullet(participant);
+            solver_groups.filters = collision_groups::BATTLEFIELD_ROOT
+                | collision_groups::ALL_BULLETS
+                | collision_groups::ALL_NEW_BULLETS
+                | collision_groups::ALL_TURRETS;
+            commands.entity(entity).remove::<NewBullet>();
+        }
+    }
+}
+fn expire_spawn_protection(
+    mut commands: Commands,
+    time: Res<Time>,
+    rapier: Res<RapierContext>,
+    mut bullets: Query<(Entity, &mut SpawnProtection)>,
+    mut expired_events: EventWriter<SpawnProtectionExpired>,
+) {
+    for (entity, mut protection) in &mut bullets {
+        if !protection.0.tick(time.delta()).finished() {
+            continue;
+        }
+        commands.entity(entity).remove::<SpawnProtection>();
+        // Tiles are sensors, which only intersect, while turrets are solid and make contact.
+        let intersecting = rapier
+            .intersection_pairs_with(entity)
+            .filter(|&(_, _, intersecting)| intersecting)
+            .map(|(a, b, _)| if a == entity { b } else { a });
+        let in_contact = rapier
+            .contact_pairs_with(entity)
+            .filter(|pair| pair.has_any_active_contact())
+            .map(|pair| {
+                if pair.collider1() == entity {
+                    pair.collider2()
+                } else {
+                    pair.collider1()
+                }
+            });
+        let touching = intersecting.chain(in_contact).collect::<Vec<_>>();
+        if !touching.is_empty() {
+            expired_events.send(SpawnProtectionExpired {
+                bullet: entity,
+                touching,
+            });
+        }
+    }
+}
+/// The pairs of colliders that started touching, counting a bullet coming out of its spawn
+/// protection as starting to touch everything it's on.
+fn started_collisions<'a>(
+    collision_events: &'a mut EventReader<CollisionEvent>,
+    expired_events: &'a mut EventReader<SpawnProtectionExpired>,
+) -> impl Iterator<Item = (Entity, Entity)> + 'a {
+    collision_events
+        .read()
+        .filter_map(|event| match *event {
+            CollisionEvent::Started(a, b, _) => Some((a, b)),
+            CollisionEvent::Stopped(..) => None,
+        })
+        .chain(expired_events.read().flat_map(|event| {
+            event
+                .touching
+                .iter()
+                .map(move |&other| (event.bullet, other))
+        }))
+}
+fn fire_shots(
     mut commands: Commands,
     mesh: Res<BulletMesh>,
     materials: Res<ParticipantMap<Handle<ColorMaterial>>>,
@@ -714,73 +1768,272 @@ fn fire_shots(
     mut turrets: Query<(&mut Turret, &Transform, &Participant, &TurretPlatformLink)>,
     platform_query: Query<&BarrelOffset>,
     battlefield_root: Query<Entity, With<BattlefieldRoot>>,
+    boundary: Res<ArenaBoundary>,
+    budget: Res<EntityBudget>,
+    bullets: Query<(), Or<(With<Bullet>, With<NewBullet>)>>,
+    tiles: Query<(&Participant, &Transform), (With<Tile>, Without<DeadTile>)>,
+    mut rng: ResMut<MatchRng>,
+    config: Res<GameConfig>,
+    mutators: Res<MutatorEffects>,
     time: Res<Time>,
 ) {
+    let mut bullet_count = bullets.iter().count();
     for (mut turret, transform, &owner, &TurretPlatformLink(link)) in &mut turrets {
         if time.elapsed_seconds() - turret.last_charged_shot_timestamp < CHARGED_SHOT_COOLDOWN {
             continue;
         }
-        let Some((shot_type, charge)) = turret.firing_queue.pop_back() else {
+        if turret.overheated
+            && time.elapsed_seconds() - turret.last_shot_timestamp < OVERHEATED_SHOT_INTERVAL
+        {
+            continue;
+        }
+        if budget.max_bullets.is_some_and(|max| bullet_count >= max) {
+            break;
+        }
+        let Some((shot_type, charge, release)) = turret.firing_queue.pop_back() else {
             continue;
         };
         let get_offset = |radius: f32| {
             let translation = transform.translation;
             let absx = translation.x.abs();
-            let abs_offset = absx - absx.min(BATTLEFIELD_HALF_WIDTH - radius);
+            let abs_offset = absx - absx.min(boundary.half_width - radius);
             Vec2::new(translation.x.signum(), translation.y.signum()) * abs_offset
         };
-        let (charge, offset, bullet_speed) = match shot_type {
+        // Takes a shot worth `shot_value` off of the charge, and queues up whatever is left.
+        let split_off_shot = |queue: &mut VecDeque<(ShotType, Charge, ReleaseTuning)>,
+                              shot_value: u64| {
+            let shot = Charge::from_value(shot_value.min(charge.value));
+            let mut charge = charge;
+            match charge.value.checked_sub(shot.value) {
+                None | Some(0) => (),
+                Some(remaining_value) => {
+                    charge.value = remaining_value;
+                    charge.update_level();
+                    queue.push_back((shot_type, charge, release));
+                }
+            }
+            shot
+        };
+        // Each shot with its angle relative to the barrel.
+        let (shots, bullet_speed) = match shot_type {
             ShotType::Charged => {
-                let radius = charge.get_scale();
-                let offset = get_offset(radius);
                 turret.last_charged_shot_timestamp = time.elapsed_seconds();
-                (charge, offset, CHARGED_SHOT_BULLET_SPEED)
+                (vec![(charge, 0.0)], release.speed)
             }
-            ShotType::Multi => {
-                let shot_value = match charge.level.checked_sub(MULTI_SHOT_CHARGE_OFFSET) {
-                    None | Some(0) => 1,
-                    Some(value) => value,
-                };
-                let shot = Charge::from_value(shot_value);
-                let radius = shot.get_scale();
-                let offset = get_offset(radius);
-                let mut charge = charge;
-                match charge.value.checked_sub(shot.value) {
-                    None | Some(0) => (),
-                    Some(remaining_value) => {
-                        charge.value = remaining_value;
-                        charge.update_level();
-                        turret.firing_queue.push_back((shot_type, charge));
+            ShotType::Multi => match config.burst_pattern {
+                BurstPattern::Sequential => {
+                    let shot_value = match charge.level.checked_sub(release.burst_level_offset) {
+                        None | Some(0) => 1,
+                        Some(value) => value,
+                    };
+                    let shot = split_off_shot(&mut turret.firing_queue, shot_value);
+                    (vec![(shot, 0.0)], release.speed)
+                }
+                BurstPattern::Fan(count) => {
+                    let count = (count.max(1) as u64).min(charge.value.max(1));
+                    let (value, remainder) = (charge.value / count, charge.value % count);
+                    let (first_angle, step) = if count > 1 {
+                        (-FAN_SPREAD / 2.0, FAN_SPREAD / (count - 1) as f32)
+                    } else {
+                        (0.0, 0.0)
+                    };
+                    let shots = (0..count)
+                        .map(|i| {
+                            let shot = Charge::from_value(value + (i < remainder) as u64);
+                            (shot, first_angle + i as f32 * step)
+                        })
+                        .collect();
+                    (shots, release.speed)
+                }
+                BurstPattern::Salvo(shot_value) => {
+                    if time.elapsed_seconds() - turret.last_salvo_shot_timestamp
+                        < SALVO_SHOT_INTERVAL
+                    {
+                        turret.firing_queue.push_back((shot_type, charge, release));
+                        continue;
                     }
+                    turret.last_salvo_shot_timestamp = time.elapsed_seconds();
+                    let shot = split_off_shot(&mut turret.firing_queue, shot_value.max(1));
+                    (vec![(shot, 0.0)], release.speed)
                 }
-                (shot, offset, BURST_SHOT_BULLET_SPEED)
-            }
+            },
         };
+        turret.last_shot_timestamp = time.elapsed_seconds();
         let &BarrelOffset(base_angle) = platform_query.get(link).unwrap();
+        // Sampled in one go, since there can be tens of thousands of tiles to go through.
+        let targets = if config.tile_targeting && matches!(shot_type, ShotType::Multi) {
+            tiles
+                .iter()
+                .filter(|(&tile_owner, _)| tile_owner != owner)
+                .map(|(_, tile_transform)| tile_transform.translation.xy())
+                .choose_multiple(&mut **rng, shots.len())
+        } else {
+            Vec::new()
+        };
+        for (i, (shot, angle)) in shots.into_iter().enumerate() {
+            let position = transform.translation.xy()
+                - get_offset(shot.get_scale(config.bullet_size_cap) * mutators.bullet_size_factor);
+            // With fewer enemy tiles than bullets, some of them get more than one.
+            let firing_angle = match targets.get(i % targets.len().max(1)) {
+                Some(&target) => {
+                    let direction = target - position;
+                    direction.y.atan2(direction.x)
+                        + rng.gen_range(-TILE_TARGETING_SPREAD..=TILE_TARGETING_SPREAD)
+                }
+                None => turret_stopwatch.get() + base_angle + angle,
+            };
+            let ball = commands
+                .spawn(ChargeBallBundle::new(
+                    mesh.clone(),
+                    materials.get(owner).clone(),
+                ))
+                .id();
+            let mut bullet = commands.spawn(BulletBundle::new(
+                owner,
+                position,
+                ball,
+                shot,
+                firing_angle,
+                bullet_speed,
+            ));
+            bullet.set_parent(battlefield_root.single()).add_child(ball);
+            if matches!(shot_type, ShotType::Charged) {
+                bullet.insert(ChargedShot);
+            }
+            bullet_count += 1;
+        }
+    }
+}
+fn spawn_requested_bullets(
+    mut commands: Commands,
+    mut events: EventReader<SpawnBulletEvent>,
+    mesh: Res<BulletMesh>,
+    materials: Res<ParticipantMap<Handle<ColorMaterial>>>,
+    battlefield_root: Query<Entity, With<BattlefieldRoot>>,
+) {
+    let root = battlefield_root.single();
+    for event in events.read() {
         let ball = commands
             .spawn(ChargeBallBundle::new(
-                mesh.clone(),
-                materials.get(owner).clone(),
+                mesh.0.clone(),
+                materials.get(event.owner).clone(),
             ))
             .id();
         commands
             .spawn(BulletBundle::new(
-                owner,
-                transform.translation.xy() - offset,
+                event.owner,
+                event.position,
                 ball,
-                charge,
-                turret_stopwatch.get() + base_angle,
-                bullet_speed,
+                Charge::from_value(event.charge.max(1)),
+                event.angle,
+                event.speed,
             ))
-            .set_parent(battlefield_root.single())
+            .set_parent(root)
             .add_child(ball);
     }
 }
+/// Paces the winner's celebratory bullets, and takes turns picking which tile each one goes for.
+#[derive(Resource)]
+struct VictoryLap {
+    timer: Timer,
+    shots: usize,
+}
+impl Default for VictoryLap {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(VICTORY_LAP_INTERVAL, TimerMode::Repeating),
+            shots: 0,
+        }
+    }
+}
+fn has_winner(outcome: Res<MatchOutcome>) -> bool {
+    outcome.winner().is_some()
+}
+/// Keeps the battlefield going after the match ends, with the winner slowly painting the rest of
+/// the tiles in its color while the results are up.
+fn victory_lap(
+    mut commands: Commands,
+    mut lap: ResMut<VictoryLap>,
+    time: Res<Time>,
+    outcome: Res<MatchOutcome>,
+    mesh: Res<BulletMesh>,
+    materials: Res<ParticipantMap<Handle<ColorMaterial>>>,
+    turrets: Res<ParticipantMap<Entity>>,
+    turret_query: Query<&Transform, With<Turret>>,
+    tiles: Query<(&Participant, &Transform), With<Tile>>,
+    battlefield_root: Query<Entity, With<BattlefieldRoot>>,
+    budget: Res<EntityBudget>,
+    bullets: Query<(), Or<(With<Bullet>, With<NewBullet>)>>,
+) {
+    if !lap.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let Some(winner) = outcome.winner() else {
+        return;
+    };
+    let Ok(turret_transform) = turret_query.get(turrets[winner]) else {
+        return;
+    };
+    if budget
+        .max_bullets
+        .is_some_and(|max| bullets.iter().count() >= max)
+    {
+        return;
+    }
+    let remaining = tiles
+        .iter()
+        .filter(|(&owner, _)| owner != winner)
+        .collect::<Vec<_>>();
+    if remaining.is_empty() {
+        return;
+    }
+    let (_, tile_transform) = remaining[lap.shots % remaining.len()];
+    lap.shots += 1;
+    let position = turret_transform.translation.xy();
+    let direction = tile_transform.translation.xy() - position;
+    let shot = Charge::from_value(1);
+    let ball = commands
+        .spawn(ChargeBallBundle::new(
+            mesh.clone(),
+            materials.get(winner).clone(),
+        ))
+        .id();
+    commands
+        .spawn(BulletBundle::new(
+            winner,
+            position,
+            ball,
+            shot,
+            direction.y.atan2(direction.x),
+            VICTORY_LAP_BULLET_SPEED,
+        ))
+        .set_parent(battlefield_root.single())
+        .add_child(ball);
+}
+/// The kind of shot a turret with [`GameConfig::shot_ai`] on turns a release into.
+fn choose_shot_type(charge: u64, strongest_enemy: u64, nearest_threat: Option<f32>) -> ShotType {
+    if nearest_threat.is_some_and(|distance| distance < SHOT_AI_THREAT_RADIUS) {
+        // Lots of small bullets are better at catching the incoming ones.
+        ShotType::Multi
+    } else if charge >= strongest_enemy {
+        // Enough to knock out any turret in one hit, if it gets there.
+        ShotType::Charged
+    } else {
+        // Not worth aiming at anyone yet, so go for tiles instead.
+        ShotType::Multi
+    }
+}
 fn handle_trigger_events(
     mut trigger_events: EventReader<TriggerEvent>,
     mut restart_events: EventReader<RestartEvent>,
     turret_entities: Res<ParticipantMap<Entity>>,
-    mut turret_query: Query<(&mut Charge, &mut Turret)>,
+    mut turret_query: Query<(&mut Charge, &mut Turret, &Participant, &Transform)>,
+    bullets: Query<(&Participant, &Transform), With<Bullet>>,
+    zones: Query<&ReleaseTuning>,
+    catch_up_bonus: Res<CatchUpBonus>,
+    config: Res<GameConfig>,
+    tuning: Res<Tuning>,
+    mutators: Res<MutatorEffects>,
     time: Res<Time>,
 ) {
     if !restart_events.is_empty() {
@@ -789,23 +2042,57 @@ fn handle_trigger_events(
     }
     for event in trigger_events.read() {
         let &entity = turret_entities.get(event.participant);
-        let Ok((mut charge, mut turret)) = turret_query.get_mut(entity) else {
+        let strongest_enemy = turret_query
+            .iter()
+            .filter(|&(_, _, &owner, _)| owner != event.participant)
+            .map(|(charge, ..)| charge.value)
+            .max()
+            .unwrap_or_default();
+        let Ok((mut charge, mut turret, _, transform)) = turret_query.get_mut(entity) else {
             continue;
         };
         match event.trigger_type {
-            TriggerType::Multiply(factor) => charge.multiply(factor),
-            TriggerType::BurstShot => {
-                turret.firing_queue.push_front((ShotType::Multi, *charge));
-                if time.elapsed_seconds() - turret.last_hit_timestamp > TURRET_BOOST_COOLDOWN {
-                    charge.reset_boosted();
+            TriggerType::Multiply(factor) => match catch_up_bonus.0 {
+                Some((underdog, bonus)) if underdog == event.participant => charge.multiply(
+                    factor
+                        .saturating_add(bonus)
+                        .saturating_mul(mutators.multiply_factor),
+                ),
+                _ => charge.multiply(factor.saturating_mul(mutators.multiply_factor)),
+            },
+            TriggerType::Penalty(percent) => charge.penalize(percent),
+            TriggerType::BurstShot | TriggerType::ChargedShot => {
+                let shot_type = if config.shot_ai {
+                    let position = transform.translation.xy();
+                    let nearest_threat = bullets
+                        .iter()
+                        .filter(|&(&owner, _)| owner != event.participant)
+                        .map(|(_, bullet)| bullet.translation.xy().distance(position))
+                        .min_by(f32::total_cmp);
+                    choose_shot_type(charge.value, strongest_enemy, nearest_threat)
+                } else if matches!(event.trigger_type, TriggerType::BurstShot) {
+                    ShotType::Multi
                 } else {
-                    charge.reset();
+                    ShotType::Charged
+                };
+                let release = event
+                    .zone
+                    .and_then(|zone| zones.get(zone).ok())
+                    .copied()
+                    .unwrap_or_else(|| tuning.release(event.trigger_type));
+                turret
+                    .firing_queue
+                    .push_front((shot_type, *charge, release));
+                if config.overheat {
+                    turret.heat += HEAT_PER_RELEASE;
+                    if turret.heat >= MAX_HEAT {
+                        turret.overheated = true;
+                    }
                 }
-            }
-            TriggerType::ChargedShot => {
-                turret.firing_queue.push_front((ShotType::Charged, *charge));
-                if time.elapsed_seconds() - turret.last_hit_timestamp > TURRET_BOOST_COOLDOWN {
-                    charge.reset_boosted();
+                if !mutators.boost_cooldown
+                    || time.elapsed_seconds() - turret.last_hit_timestamp > tuning.boost_cooldown
+                {
+                    charge.reset_boosted(release.boosted_charge);
                 } else {
                     charge.reset();
                 }
@@ -815,39 +2102,321 @@ fn handle_trigger_events(
 }
 fn handle_bullet_turret_collision(
     mut collision_event_reader: EventReader<CollisionEvent>,
-    mut bullet_query: Query<(&Participant, &mut Charge), With<Bullet>>,
+    mut expired_events: EventReader<SpawnProtectionExpired>,
+    mut damage_events: EventWriter<TurretDamaged>,
+    mut last_stand_events: EventWriter<LastStandEvent>,
+    mut bullet_query: Query<
+        (&Participant, &mut Charge, &Velocity, Has<ChargedShot>),
+        (With<Bullet>, Without<SpawnProtection>),
+    >,
     mut turret_query: Query<
-        (&Participant, &mut Charge, &mut Turret),
+        (&Participant, &mut Charge, &mut LastStand),
         (With<Turret>, Without<Bullet>),
     >,
+    config: Res<GameConfig>,
+) {
+    for (a, b) in started_collisions(&mut collision_event_reader, &mut expired_events) {
+        let (&bullet_owner, mut bullet_charge, velocity, charged_shot) =
+            if let Ok(x) = bullet_query.get_mut(a) {
+                x
+            } else if let Ok(x) = bullet_query.get_mut(b) {
+                x
+            } else {
+                continue;
+            };
+        let (&turret_owner, mut turret_charge, mut last_stand) =
+            if let Ok(x) = turret_query.get_mut(a) {
+                x
+            } else if let Ok(x) = turret_query.get_mut(b) {
+                x
+            } else {
+                continue;
+            };
+        if turret_owner == bullet_owner {
+            continue;
+        }
+        // The shield eats the bullet without the turret losing anything.
+        if last_stand.shielded() {
+            bullet_charge.value = 0;
+            continue;
+        }
+        let mut min_value = bullet_charge.value.min(turret_charge.value);
+        bullet_charge.value -= min_value;
+        turret_charge.value -= min_value;
+        // At most one of them has charge left, until a last stand puts the turret back on 1.
+        let bullet_won = bullet_charge.value > 0;
+        let turret_won = turret_charge.value > 0;
+        if config.last_stand && min_value > 0 && turret_charge.value == 0 && !last_stand.used {
+            turret_charge.value = 1;
+            min_value -= 1;
+            last_stand.used = true;
+            last_stand.shield.reset();
+            last_stand_events.send(LastStandEvent {
+                participant: turret_owner,
+            });
+        }
+        if config.vampiric_duels {
+            let absorbed = (min_value as f64 * VAMPIRIC_ABSORB_FRACTION) as u64;
+            // A turret saved by its last stand lost the exchange, so it doesn't absorb anything.
+            if bullet_won {
+                bullet_charge.value = bullet_charge.value.saturating_add(absorbed);
+            } else if turret_won {
+                turret_charge.value = turret_charge.value.saturating_add(absorbed);
+            }
+        }
+        damage_events.send(TurretDamaged {
+            victim: turret_owner,
+            attacker: bullet_owner,
+            amount: min_value,
+            remaining: turret_charge.value,
+            charged_shot,
+            bullet_velocity: velocity.linvel,
+        });
+    }
+}
+/// How many more times each turret can lose charge to sabotage this second.
+#[derive(Resource)]
+struct SabotageBudget {
+    timer: Timer,
+    remaining: ParticipantMap<u32>,
+}
+impl Default for SabotageBudget {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(1.0, TimerMode::Repeating),
+            remaining: ParticipantMap::splat(SABOTAGE_MAX_PER_SECOND),
+        }
+    }
+}
+fn refill_sabotage_budget(mut budget: ResMut<SabotageBudget>, time: Res<Time>) {
+    if budget.timer.tick(time.delta()).just_finished() {
+        budget.remaining = ParticipantMap::splat(SABOTAGE_MAX_PER_SECOND);
+    }
+}
+/// Sabotage never takes a turret's last point of charge, so it can't eliminate anyone by itself.
+fn handle_sabotage(
+    mut events: EventReader<SabotageEvent>,
+    mut budget: ResMut<SabotageBudget>,
+    turrets: Res<ParticipantMap<Entity>>,
+    mut turret_query: Query<&mut Charge, With<Turret>>,
+) {
+    for event in events.read() {
+        if budget.remaining[event.victim] == 0 {
+            continue;
+        }
+        let Ok(mut charge) = turret_query.get_mut(turrets[event.victim]) else {
+            continue;
+        };
+        let loss = ((charge.value as f64 * SABOTAGE_FRACTION) as u64)
+            .max(1)
+            .min(charge.value.saturating_sub(1));
+        if loss > 0 {
+            budget.remaining[event.victim] -= 1;
+            charge.value -= loss;
+        }
+    }
+}
+/// Being hit holds off the boosted charge reset for a while.
+fn record_turret_hits(
+    mut events: EventReader<TurretDamaged>,
+    turret_entities: Res<ParticipantMap<Entity>>,
+    mut turrets: Query<&mut Turret>,
     time: Res<Time>,
 ) {
-    for event in collision_event_reader.read() {
-        let &CollisionEvent::Started(a, b, _) = event else {
+    for event in events.read() {
+        if let Ok(mut turret) = turrets.get_mut(turret_entities[event.victim]) {
+            turret.last_hit_timestamp = time.elapsed_seconds();
+        }
+    }
+}
+fn knock_back_turrets(
+    mut commands: Commands,
+    mut events: EventReader<TurretDamaged>,
+    turret_entities: Res<ParticipantMap<Entity>>,
+    turrets: Query<&GlobalTransform, With<Turret>>,
+    shockwave_mesh: Res<ShockwaveMesh>,
+    colors: Res<ParticipantMap<BallColor>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for event in events.read() {
+        if !event.charged_shot || event.amount == 0 {
+            continue;
+        }
+        let turret_entity = turret_entities[event.victim];
+        let Ok(turret_transform) = turrets.get(turret_entity) else {
             continue;
         };
-        let (&bullet_owner, mut bullet_charge) = if let Ok(x) = bullet_query.get_mut(a) {
-            x
-        } else if let Ok(x) = bullet_query.get_mut(b) {
-            x
-        } else {
+        let damage_level = Charge::calculate_level(event.amount) as f32;
+        commands.entity(turret_entity).insert(TurretKnockback {
+            timer: Timer::from_seconds(KNOCKBACK_DURATION, TimerMode::Once),
+            offset: event.bullet_velocity.normalize_or_zero()
+                * (damage_level * KNOCKBACK_DISTANCE_PER_LEVEL).min(KNOCKBACK_MAX_DISTANCE),
+            squash: (damage_level * KNOCKBACK_SQUASH_PER_LEVEL).min(KNOCKBACK_MAX_SQUASH),
+        });
+        commands.spawn((
+            Name::new("Shockwave"),
+            Shockwave {
+                timer: Timer::from_seconds(SHOCKWAVE_DURATION, TimerMode::Once),
+                radius: damage_level * SHOCKWAVE_RADIUS_PER_LEVEL,
+            },
+            ColorMesh2dBundle {
+                mesh: shockwave_mesh.clone(),
+                material: materials.add(ColorMaterial::from(colors[event.attacker].0)),
+                transform: Transform::from_translation(
+                    turret_transform.translation().xy().extend(SHOCKWAVE_Z),
+                )
+                .with_scale(Vec3::ZERO),
+                ..default()
+            },
+        ));
+    }
+}
+fn spawn_last_stand_effects(
+    mut commands: Commands,
+    mut events: EventReader<LastStandEvent>,
+    mut shake: ResMut<ScreenShake>,
+    turret_entities: Res<ParticipantMap<Entity>>,
+    turrets: Query<&GlobalTransform, With<Turret>>,
+    shockwave_mesh: Res<ShockwaveMesh>,
+    colors: Res<ParticipantMap<BallColor>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for event in events.read() {
+        let Ok(turret_transform) = turrets.get(turret_entities[event.participant]) else {
             continue;
         };
-        let (&turret_owner, mut turret_charge, mut turret) = if let Ok(x) = turret_query.get_mut(a)
-        {
-            x
-        } else if let Ok(x) = turret_query.get_mut(b) {
-            x
+        let position = turret_transform.translation().xy();
+        shake.start(LAST_STAND_SHAKE_DURATION, LAST_STAND_SHAKE_INTENSITY);
+        commands.spawn((
+            Name::new("Last Stand Shockwave"),
+            Shockwave {
+                timer: Timer::from_seconds(SHOCKWAVE_DURATION, TimerMode::Once),
+                radius: LAST_STAND_SHOCKWAVE_RADIUS,
+            },
+            ColorMesh2dBundle {
+                mesh: shockwave_mesh.clone(),
+                material: materials.add(ColorMaterial::from(colors[event.participant].0)),
+                transform: Transform::from_translation(position.extend(SHOCKWAVE_Z))
+                    .with_scale(Vec3::ZERO),
+                ..default()
+            },
+        ));
+        commands.spawn((
+            Name::new("Last Stand Shield"),
+            LastStandShield(event.participant),
+            ColorMesh2dBundle {
+                mesh: shockwave_mesh.clone(),
+                material: materials.add(ColorMaterial::from(LAST_STAND_SHIELD_COLOR)),
+                transform: Transform::from_translation(position.extend(SHOCKWAVE_Z))
+                    .with_scale(Vec3::splat(LAST_STAND_SHIELD_RADIUS)),
+                ..default()
+            },
+        ));
+    }
+}
+/// Counts down the shields, and takes their rings away once they're down.
+fn update_last_stand_shields(
+    mut commands: Commands,
+    time: Res<Time>,
+    turret_entities: Res<ParticipantMap<Entity>>,
+    mut last_stands: Query<&mut LastStand>,
+    shields: Query<(Entity, &LastStandShield)>,
+) {
+    for mut last_stand in &mut last_stands {
+        last_stand.shield.tick(time.delta());
+    }
+    for (entity, &LastStandShield(participant)) in &shields {
+        let shielded = last_stands
+            .get(turret_entities[participant])
+            .is_ok_and(LastStand::shielded);
+        if !shielded {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+/// Knocks the charge ball back and squashes it, then eases it back into place.
+fn animate_turret_knockback(
+    mut commands: Commands,
+    mut turrets: Query<(Entity, &mut TurretKnockback, &Charge, &ChargeBallLink)>,
+    mut transform_query: Query<&mut Transform>,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+) {
+    for (entity, mut knockback, charge, &ChargeBallLink(link)) in &mut turrets {
+        let Ok(mut ball_transform) = transform_query.get_mut(link) else {
+            continue;
+        };
+        knockback.timer.tick(time.delta());
+        let fraction = knockback.timer.fraction_remaining();
+        // Same as in `update_charge_ball`.
+        let scale = charge
+            .get_scale(config.bullet_size_cap)
+            .max(BULLET_MINIMUM_TEXT_SIZE)
+            * (1.0 - knockback.squash * fraction);
+        ball_transform.translation = (knockback.offset * fraction).extend(BULLET_BALL_Z);
+        ball_transform.scale.x = scale;
+        ball_transform.scale.y = scale;
+        if knockback.timer.finished() {
+            commands.entity(entity).remove::<TurretKnockback>();
+        }
+    }
+}
+fn animate_shockwaves(
+    mut commands: Commands,
+    mut shockwaves: Query<(
+        Entity,
+        &mut Shockwave,
+        &mut Transform,
+        &Handle<ColorMaterial>,
+    )>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    time: Res<Time>,
+) {
+    for (entity, mut shockwave, mut transform, material) in &mut shockwaves {
+        shockwave.timer.tick(time.delta());
+        if shockwave.timer.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let progress = shockwave.timer.fraction();
+        transform.scale = Vec3::splat(shockwave.radius * progress);
+        if let Some(material) = materials.get_mut(material) {
+            material.color.set_alpha(1.0 - progress);
+        }
+    }
+}
+
+fn report_bullet_impacts(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut impact_events: EventWriter<BulletImpactEvent>,
+    bullet_query: Query<(&Charge, &Velocity), With<Bullet>>,
+    target_query: Query<(Has<BattlefieldRoot>, Has<Tile>, Has<Turret>), Without<Bullet>>,
+) {
+    for event in collision_events.read() {
+        let &CollisionEvent::Started(a, b, _) = event else {
+            continue;
+        };
+        let ((charge, velocity), target) = if let Ok(x) = bullet_query.get(a) {
+            (x, b)
+        } else if let Ok(x) = bullet_query.get(b) {
+            (x, a)
         } else {
             continue;
         };
-        if turret_owner == bullet_owner {
+        if charge.value == 0 {
             continue;
         }
-        let min_value = bullet_charge.value.min(turret_charge.value);
-        bullet_charge.value -= min_value;
-        turret_charge.value -= min_value;
-        turret.last_hit_timestamp = time.elapsed_seconds();
+        let kind = match target_query.get(target) {
+            Ok((true, _, _)) => ImpactKind::Wall,
+            Ok((_, true, _)) => ImpactKind::Tile,
+            Ok((_, _, true)) => ImpactKind::Turret,
+            _ => continue,
+        };
+        impact_events.send(BulletImpactEvent {
+            kind,
+            level: charge.level,
+            speed: velocity.linvel.length(),
+        });
     }
 }
 fn handle_elimination(
@@ -855,103 +2424,524 @@ fn handle_elimination(
     mut events: EventReader<EliminationEvent>,
     mut survivor_count: ResMut<SurvivorCount>,
     mut survivors: ResMut<ParticipantMap<bool>>,
-    participant_entity_query: Query<(Entity, &Participant), (Without<Tile>, Without<Bullet>)>,
+    participant_entity_query: Query<
+        (Entity, &Participant),
+        (Without<Tile>, Without<Bullet>, Without<WorkerBall>),
+    >,
+    mut outcome: ResMut<MatchOutcome>,
+    mut placements: ResMut<Placements>,
+    stopwatch: Res<TurretStopwatch>,
+    tile_counts: Res<TileCounts>,
+    config: Res<GameConfig>,
 ) {
+    let mut eliminated = Vec::new();
     for event in events.read() {
+        if !survivors[event.participant] {
+            continue;
+        }
         survivors.set(event.participant, false);
         survivor_count.0 -= 1;
+        eliminated.push(event.participant);
         for (entity, &participant) in &participant_entity_query {
             if participant == event.participant {
                 commands.entity(entity).despawn_recursive();
             }
         }
     }
+    let time = stopwatch.0.elapsed_secs();
+    for &participant in &eliminated {
+        placements.0[participant] = Some(Placement {
+            place: survivor_count.0 + 1,
+            time,
+        });
+    }
+    if survivor_count.0 <= 1 && *outcome == MatchOutcome::Undecided {
+        *outcome = decide_outcome(&survivors, &eliminated, &tile_counts, config.tie_break);
+        if let MatchOutcome::Winner(winner) = *outcome {
+            // Everyone eliminated last comes second, including the ones a tie break winner was
+            // eliminated along with.
+            for &participant in &eliminated {
+                if let Some(placement) = &mut placements.0[participant] {
+                    placement.place = 2;
+                }
+            }
+            placements.0[winner] = Some(Placement { place: 1, time });
+        }
+    }
+}
+/// The last survivor wins. If there is none, the participants eliminated in the same frame as
+/// each other at the very end go to the tie break.
+fn decide_outcome(
+    survivors: &ParticipantMap<bool>,
+    last_eliminated: &[Participant],
+    tile_counts: &TileCounts,
+    tie_break: TieBreak,
+) -> MatchOutcome {
+    if let Some(winner) = Participant::ALL.into_iter().find(|&p| survivors[p]) {
+        return MatchOutcome::Winner(winner);
+    }
+    match tie_break {
+        TieBreak::Draw => MatchOutcome::Draw,
+        TieBreak::MostTiles => {
+            let most = last_eliminated.iter().map(|&p| tile_counts.0[p]).max();
+            let leaders = last_eliminated
+                .iter()
+                .filter(|&&p| Some(tile_counts.0[p]) == most)
+                .collect::<Vec<_>>();
+            match leaders.as_slice() {
+                &[&winner] => MatchOutcome::Winner(winner),
+                _ => MatchOutcome::Draw,
+            }
+        }
+    }
+}
+/// How the match ends if the condition is met right now, going by the participants still standing.
+fn check_victory_condition(
+    condition: VictoryCondition,
+    survivors: &ParticipantMap<bool>,
+    tile_counts: &TileCounts,
+    elapsed: f32,
+) -> Option<MatchOutcome> {
+    let standing = Participant::ALL.into_iter().filter(|&p| survivors[p]);
+    match condition {
+        VictoryCondition::Territory(percent) => {
+            let total = Participant::ALL
+                .into_iter()
+                .map(|p| tile_counts.0[p])
+                .sum::<u32>();
+            standing
+                .filter(|&p| total > 0 && tile_counts.0[p] * 100 >= total * percent as u32)
+                .max_by_key(|&p| tile_counts.0[p])
+                .map(MatchOutcome::Winner)
+        }
+        VictoryCondition::TimedScore(seconds) => {
+            if elapsed < seconds as f32 {
+                return None;
+            }
+            let most = standing.clone().map(|p| tile_counts.0[p]).max()?;
+            let leaders = standing
+                .filter(|&p| tile_counts.0[p] == most)
+                .collect::<Vec<_>>();
+            Some(match leaders.as_slice() {
+                &[winner] => MatchOutcome::Winner(winner),
+                _ => MatchOutcome::Draw,
+            })
+        }
+    }
+}
+/// Ends the match early when one of the configured victory conditions is met. Everyone still
+/// standing is placed by the tiles they own.
+fn check_victory_conditions(
+    config: Res<GameConfig>,
+    survivors: Res<ParticipantMap<bool>>,
+    tile_counts: Res<TileCounts>,
+    stopwatch: Res<TurretStopwatch>,
+    mut outcome: ResMut<MatchOutcome>,
+    mut placements: ResMut<Placements>,
+) {
+    // An elimination earlier in the frame can decide the match before the game is over.
+    if *outcome != MatchOutcome::Undecided {
+        return;
+    }
+    let time = stopwatch.0.elapsed_secs();
+    let Some(decided) = config
+        .victory_conditions
+        .iter()
+        .find_map(|&condition| check_victory_condition(condition, &survivors, &tile_counts, time))
+    else {
+        return;
+    };
+    *outcome = decided;
+    let winner = decided.winner();
+    let standing = Participant::ALL
+        .into_iter()
+        .filter(|&p| survivors[p])
+        .collect::<Vec<_>>();
+    for &participant in &standing {
+        let ahead = standing
+            .iter()
+            .filter(|&&p| Some(p) != winner && tile_counts.0[p] > tile_counts.0[participant])
+            .count() as u8;
+        let place = match winner {
+            Some(winner) if winner == participant => 1,
+            Some(_) => 2 + ahead,
+            None => 1 + ahead,
+        };
+        placements.0[participant] = Some(Placement { place, time });
+    }
 }
+/// The captures of one frame, by the cell of the [`TileGrid`] the tile is in.
+#[derive(Default)]
+struct CaptureBatch {
+    /// The tile in each cell that changed hands, and who has it now.
+    new_owners: Vec<Option<(Entity, Participant)>>,
+    captured_cells: Vec<usize>,
+}
+/// Works out every capture in the order the collisions happened, since a tile can change hands
+/// more than once in a frame, then writes only the final owner of each captured tile back.
 fn handle_bullet_tile_collision(
-    mut commands: Commands,
     mut events: EventReader<CollisionEvent>,
-    tile_colors: Res<ParticipantMap<TileColor>>,
-    ball_colors: Res<ParticipantMap<BallColor>>,
-    mut bullet_query: Query<(&Participant, &mut Charge, &Velocity), With<Bullet>>,
+    mut expired_events: EventReader<SpawnProtectionExpired>,
+    mut capture_events: EventWriter<TileCaptured>,
+    mut bullet_query: Query<
+        (&Participant, &mut Charge, &Velocity),
+        (With<Bullet>, Without<SpawnProtection>),
+    >,
     mut tile_query: Query<
-        (
-            &mut Participant,
-            &mut Sprite,
-            &mut CollisionGroups,
-            &GlobalTransform,
-        ),
+        (&mut Participant, &mut CollisionGroups, &GlobalTransform),
         (With<Tile>, Without<Bullet>),
     >,
-    effect: Res<TileHitEffect>,
+    grid: Res<TileGrid>,
+    mut batch: Local<CaptureBatch>,
+) {
+    let batch = &mut *batch;
+    // Every cell is empty again by the end of the frame, so this only grows the grid.
+    batch.new_owners.resize(grid.side_count().pow(2), None);
+    for (a, b) in started_collisions(&mut events, &mut expired_events) {
+        let (bullet, tile) = if bullet_query.contains(a) {
+            (a, b)
+        } else if bullet_query.contains(b) {
+            (b, a)
+        } else {
+            continue;
+        };
+        let Ok((&tile_owner, _, tile_transform)) = tile_query.get(tile) else {
+            continue;
+        };
+        let position = tile_transform.translation().xy();
+        let cell = grid.cell_index(position);
+        let previous_owner = batch.new_owners[cell].map_or(tile_owner, |(_, owner)| owner);
+        let Ok((&bullet_owner, mut charge, velocity)) = bullet_query.get_mut(bullet) else {
+            continue;
+        };
+        if bullet_owner == previous_owner {
+            continue;
+        }
+        if charge.value == 0 {
+            continue;
+        }
+        if batch.new_owners[cell]
+            .replace((tile, bullet_owner))
+            .is_none()
+        {
+            batch.captured_cells.push(cell);
+        }
+        charge.value -= 1;
+        capture_events.send(TileCaptured {
+            tile,
+            from: previous_owner,
+            to: bullet_owner,
+            position,
+            bullet_charge: charge.value,
+            bullet_velocity: velocity.linvel,
+        });
+    }
+    // Going by the captured tiles keeps a busy frame from going through the whole grid.
+    for cell in batch.captured_cells.drain(..) {
+        let Some((tile, new_owner)) = batch.new_owners[cell].take() else {
+            continue;
+        };
+        if let Ok((mut owner, mut collision_groups, _)) = tile_query.get_mut(tile) {
+            *owner = new_owner;
+            *collision_groups = tile_collision_groups(new_owner);
+        }
+    }
+}
+fn recolor_captured_tiles(
+    mut events: EventReader<TileCaptured>,
+    tile_colors: Res<ParticipantMap<TileColor>>,
+    mut sprites: Query<&mut Sprite, With<Tile>>,
+) {
+    for event in events.read() {
+        if let Ok(mut sprite) = sprites.get_mut(event.tile) {
+            sprite.color = tile_colors[event.to].0;
+        }
+    }
+}
+/// The capture effects that can still be shown, refilled over time, which keeps a burst of
+/// captures from spawning so many effects at once that the frame rate drops.
+#[derive(Resource)]
+struct CaptureEffectBudget {
+    effects: f32,
+    /// The elapsed time when the budget was last topped up.
+    refilled_at: f32,
+}
+impl Default for CaptureEffectBudget {
+    fn default() -> Self {
+        Self {
+            effects: CAPTURE_EFFECT_BUDGET_BURST,
+            refilled_at: 0.0,
+        }
+    }
+}
+/// The captures by one participant in one region in one frame, shown as a single effect.
+struct CaptureCluster {
+    owner: Participant,
+    position_sum: Vec2,
+    velocity_sum: Vec2,
+    count: u32,
+}
+impl CaptureCluster {
+    fn position(&self) -> Vec2 {
+        self.position_sum / self.count as f32
+    }
+    fn scale(&self) -> f32 {
+        1.0 + (self.count - 1) as f32 * CAPTURE_EFFECT_GROWTH
+    }
+}
+/// Groups the captures into clusters, biggest first, since those are the ones that matter most
+/// when the budget runs out.
+fn cluster_captures(events: impl Iterator<Item = &TileCaptured>) -> Vec<CaptureCluster> {
+    let mut clusters: Vec<((Participant, IVec2), CaptureCluster)> = Vec::new();
+    for event in events {
+        let key = (
+            event.to,
+            (event.position / CAPTURE_EFFECT_REGION_SIZE)
+                .floor()
+                .as_ivec2(),
+        );
+        match clusters.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, cluster)) => {
+                cluster.position_sum += event.position;
+                cluster.velocity_sum += event.bullet_velocity;
+                cluster.count += 1;
+            }
+            None => clusters.push((
+                key,
+                CaptureCluster {
+                    owner: event.to,
+                    position_sum: event.position,
+                    velocity_sum: event.bullet_velocity,
+                    count: 1,
+                },
+            )),
+        }
+    }
+    let mut clusters = clusters
+        .into_iter()
+        .map(|(_, cluster)| cluster)
+        .collect::<Vec<_>>();
+    clusters.sort_by_key(|cluster| std::cmp::Reverse(cluster.count));
+    clusters
+}
+/// A burst of particles at every cluster of captured tiles, or a simple flash when particles are
+/// off.
+fn spawn_capture_effects(
+    mut commands: Commands,
+    mut events: EventReader<TileCaptured>,
+    ball_colors: Res<ParticipantMap<BallColor>>,
+    effect: Option<Res<TileHitEffect>>,
+    budget: Res<EntityBudget>,
+    mut effect_budget: ResMut<CaptureEffectBudget>,
+    time: Res<Time>,
+    grid: Res<TileGrid>,
     mut effect_query: Query<(&mut EffectProperties, &mut Transform, &mut EffectSpawner)>,
     mut instance_manager: ResMut<EffectInstanceManager>,
 ) {
+    // Only runs on frames with captures, so it tops up for all the time since it last ran.
+    let now = time.elapsed_seconds();
+    effect_budget.effects = (effect_budget.effects
+        + CAPTURE_EFFECT_BUDGET_PER_SECOND * (now - effect_budget.refilled_at))
+        .min(CAPTURE_EFFECT_BUDGET_BURST);
+    effect_budget.refilled_at = now;
+    let clusters = cluster_captures(events.read());
+    // Whatever doesn't fit in the budget is dropped.
+    let affordable = (effect_budget.effects as usize).min(clusters.len());
+    effect_budget.effects -= affordable as f32;
+    for cluster in clusters.into_iter().take(affordable) {
+        let color = ball_colors[cluster.owner].0;
+        let position = cluster.position();
+        if !budget.particles {
+            let size = grid.tile_dimension() * TILE_FLASH_SCALE * cluster.scale();
+            commands.spawn((
+                Name::new("Tile Flash"),
+                TileFlash(Timer::from_seconds(TILE_FLASH_DURATION, TimerMode::Once)),
+                SpriteBundle {
+                    sprite: Sprite { color, ..default() },
+                    transform: Transform {
+                        translation: position.extend(TILE_FLASH_Z),
+                        scale: Vec3::new(size, size, 1.0),
+                        rotation: Quat::IDENTITY,
+                    },
+                    ..default()
+                },
+            ));
+            continue;
+        }
+        let Some(effect) = &effect else {
+            continue;
+        };
+        if let Some(effect_entity) = instance_manager.get() {
+            let (mut properties, mut transform, mut spawner) = effect_query
+                .get_mut(effect_entity)
+                .expect(
+                "entity returned by `InstanceManager` should have an `EffectProperties` component.",
+            );
+            properties.set_spawn_color(color);
+            properties.set_bullet_vel(cluster.velocity_sum);
+            transform.translation = position.extend(TILE_Z);
+            transform.scale = Vec3::splat(cluster.scale());
+            spawner.reset();
+        } else {
+            let entity = commands
+                .spawn(ParticleEffectBundle {
+                    effect: ParticleEffect::new(effect.0.clone()),
+                    transform: Transform::from_translation(position.extend(TILE_Z))
+                        .with_scale(Vec3::splat(cluster.scale())),
+                    ..default()
+                })
+                .insert(Name::new("Tile Hit Particle Spawner"))
+                .id();
+            instance_manager.add(entity);
+        }
+    }
+}
+/// The center of the quadrant of the battlefield the participant starts out owning.
+pub fn quadrant_center(participant: Participant) -> Vec2 {
+    const HALF: f32 = BATTLEFIELD_HALF_WIDTH / 2.0;
+    match participant {
+        Participant::A => Vec2::new(HALF, HALF),
+        Participant::B => Vec2::new(-HALF, HALF),
+        Participant::C => Vec2::new(HALF, -HALF),
+        Participant::D => Vec2::new(-HALF, -HALF),
+    }
+}
+/// The quadrant the position is in, going by who starts out owning it.
+pub fn quadrant_of(position: Vec2) -> Participant {
+    match (position.x >= 0.0, position.y >= 0.0) {
+        (true, true) => Participant::A,
+        (false, true) => Participant::B,
+        (true, false) => Participant::C,
+        (false, false) => Participant::D,
+    }
+}
+fn count_tiles(
+    mut events: EventReader<TileCaptured>,
+    mut restart_events: EventReader<RestartEvent>,
+    mut counts: ResMut<TileCounts>,
+    mut quadrant_counts: ResMut<QuadrantTileCounts>,
+) {
+    // Captures from before the restart are of tiles that are gone, and already left out of the
+    // counts it reset them to.
+    if !restart_events.is_empty() {
+        restart_events.clear();
+        events.clear();
+    }
     for event in events.read() {
-        match event {
-            &CollisionEvent::Started(a, b, _) => {
-                let (&bullet_owner, mut charge, velocity) = if let Ok(x) = bullet_query.get_mut(a) {
-                    x
-                } else if let Ok(x) = bullet_query.get_mut(b) {
-                    x
-                } else {
-                    continue;
-                };
-                let (mut tile_owner, mut sprite, mut collision_group, tile_transform) =
-                    if let Ok(x) = tile_query.get_mut(a) {
-                        x
-                    } else if let Ok(x) = tile_query.get_mut(b) {
-                        x
-                    } else {
-                        continue;
-                    };
-                if bullet_owner == *tile_owner {
-                    continue;
-                }
-                if charge.value == 0 {
-                    continue;
-                }
-                *tile_owner = bullet_owner;
-                sprite.color = tile_colors.get(bullet_owner).0;
-                *collision_group = CollisionGroups::new(
-                    collision_groups::tile(bullet_owner),
-                    collision_groups::all_bullets_except(bullet_owner)
-                        | all_new_bullets_except(bullet_owner),
-                );
-                charge.value -= 1;
-                if let Some(effect_entity) = instance_manager.get() {
-                    let (mut properties, mut transform, mut spawner) = effect_query.get_mut(effect_entity).expect("entity returned by `InstanceManager` should have an `EffectProperties` component.");
-                    properties.set_spawn_color(ball_colors.get(bullet_owner).0);
-                    properties.set_bullet_vel(velocity.linvel);
-                    transform.translation = tile_transform.translation();
-                    spawner.reset();
-                } else {
-                    let entity = commands
-                        .spawn(ParticleEffectBundle {
-                            effect: ParticleEffect::new(effect.0.clone()),
-                            transform: Transform::from_translation(tile_transform.translation()),
-                            ..default()
-                        })
-                        .insert(Name::new("Tile Hit Particle Spawner"))
-                        .id();
-                    instance_manager.add(entity);
-                }
-            }
-            CollisionEvent::Stopped(_, _, _) => (),
+        counts.0[event.from] -= 1;
+        counts.0[event.to] += 1;
+        let quadrant = &mut quadrant_counts.0[quadrant_of(event.position)];
+        quadrant[event.from] -= 1;
+        quadrant[event.to] += 1;
+    }
+}
+/// The further behind the underdog is from an equal share of the tiles among the survivors, the
+/// bigger the bonus.
+fn update_catch_up_bonus(
+    config: Res<GameConfig>,
+    counts: Res<TileCounts>,
+    survivors: Res<ParticipantMap<bool>>,
+    mut catch_up_bonus: ResMut<CatchUpBonus>,
+) {
+    let underdog = Participant::ALL
+        .into_iter()
+        .filter(|&p| survivors[p])
+        .min_by_key(|&p| counts.0[p]);
+    let survivor_tiles: u32 = Participant::ALL
+        .into_iter()
+        .filter(|&p| survivors[p])
+        .map(|p| counts.0[p])
+        .sum();
+    let survivor_count = Participant::ALL
+        .into_iter()
+        .filter(|&p| survivors[p])
+        .count();
+    let bonus = underdog
+        .filter(|_| config.catch_up && survivor_tiles > 0)
+        .and_then(|underdog| {
+            let fair_share = survivor_tiles as f32 / survivor_count as f32;
+            let deficit = 1.0 - counts.0[underdog] as f32 / fair_share;
+            let bonus = (deficit * MAX_CATCH_UP_BONUS).round() as u8;
+            (bonus > 0).then_some((underdog, bonus))
+        });
+    catch_up_bonus.set_if_neq(CatchUpBonus(bonus));
+}
+/// Gives charged shots of a high enough level a trail, reusing parked trails when there are any.
+fn attach_bullet_trails(
+    mut commands: Commands,
+    shots: Query<(Entity, &Charge, &Participant, &GlobalTransform), Added<ChargedShot>>,
+    mut parked: Query<(Entity, &mut EffectProperties), With<InactiveBulletTrail>>,
+    effect: Res<TrailEffect>,
+    colors: Res<ParticipantMap<BallColor>>,
+    config: Res<DisplayConfig>,
+    budget: Res<EntityBudget>,
+) {
+    if !budget.trails {
+        return;
+    }
+    let mut parked = parked.iter_mut();
+    for (bullet, charge, &owner, transform) in &shots {
+        if charge.level < config.bullet_trail_level {
+            continue;
+        }
+        let position = transform.translation();
+        if let Some((trail, mut properties)) = parked.next() {
+            commands
+                .entity(trail)
+                .insert(BulletTrail(bullet))
+                .remove::<InactiveBulletTrail>();
+            properties.set_spawn_color(colors.get(owner).0);
+            properties.set_position(position);
+        } else {
+            commands.spawn(BulletTrailBundle::new(
+                bullet,
+                position,
+                colors.get(owner).0,
+                effect.0.clone(),
+            ));
         }
     }
 }
-pub fn game_is_going(survivor_count: Res<SurvivorCount>) -> bool {
-    survivor_count.0 > 1
+fn update_bullet_trails(
+    mut commands: Commands,
+    mut trails: Query<(Entity, &BulletTrail, &mut EffectProperties)>,
+    transforms: Query<&GlobalTransform>,
+) {
+    for (trail, &BulletTrail(bullet), mut properties) in &mut trails {
+        if let Ok(transform) = transforms.get(bullet) {
+            properties.set_position(transform.translation());
+        } else {
+            // Like the worker ball trails, these are parked rather than despawned.
+            commands
+                .entity(trail)
+                .insert(InactiveBulletTrail)
+                .remove::<BulletTrail>();
+            properties.set_spawn_color(LinearRgba::NONE);
+            properties.set_position(BULLET_TRAIL_PARKING_POSITION);
+        }
+    }
 }
 fn cleanup_particle_emitters(mut instance_manager: ResMut<EffectInstanceManager>) {
     instance_manager.reset();
 }
 fn restart(
     mut commands: Commands,
-    mut survivor_count: ResMut<SurvivorCount>,
-    mut survivors: ResMut<ParticipantMap<bool>>,
+    (mut survivor_count, mut survivors, mut outcome, mut placements): (
+        ResMut<SurvivorCount>,
+        ResMut<ParticipantMap<bool>>,
+        ResMut<MatchOutcome>,
+        ResMut<Placements>,
+    ),
     mut turrets: ResMut<ParticipantMap<Entity>>,
     mut stopwatch: ResMut<TurretStopwatch>,
+    mut boundary: ResMut<ArenaBoundary>,
+    (mut grid, mut tile_counts, mut quadrant_counts): (
+        ResMut<TileGrid>,
+        ResMut<TileCounts>,
+        ResMut<QuadrantTileCounts>,
+    ),
+    config: Res<GameConfig>,
+    tuning: Res<Tuning>,
     colors: Res<ParticipantMap<TileColor>>,
     materials: Res<ParticipantMap<Handle<ColorMaterial>>>,
     ball_mesh: Res<BulletMesh>,
@@ -959,11 +2949,10 @@ fn restart(
     garbage: Query<Entity, Or<(With<Bullet>, With<NewBullet>, With<Turret>)>>,
     root: Query<Entity, With<BattlefieldRoot>>,
 ) {
-    survivor_count.0 = 4;
-    survivors.a = true;
-    survivors.b = true;
-    survivors.c = true;
-    survivors.d = true;
+    *survivor_count = SurvivorCount::seated(&config.participants);
+    *survivors = config.participants;
+    *outcome = MatchOutcome::Undecided;
+    *placements = Placements::default();
     for entity in garbage.iter() {
         commands.entity(entity).despawn_recursive();
     }
@@ -971,12 +2960,488 @@ fn restart(
     for &tile in tile_root_children.iter() {
         commands.entity(tile).despawn_recursive();
     }
-    setup_tiles(&mut commands, tile_root_entity, &colors);
+    *grid = TileGrid::new(&config);
+    setup_tiles(&mut commands, tile_root_entity, &colors, *grid);
+    *quadrant_counts = QuadrantTileCounts::new(*grid);
+    *tile_counts = quadrant_counts.totals();
     *turrets = setup_turrets(
         &mut commands,
         root.single(),
         ball_mesh.0.clone(),
         &materials,
+        config.participants,
+        tuning.turret_position,
     );
     stopwatch.0.reset();
+    *boundary = ArenaBoundary::default();
+}
+pub fn snapshot_battlefield(
+    tile_root: Query<&Children, With<TileRoot>>,
+    tiles: Query<&Participant, With<Tile>>,
+    turrets: Res<ParticipantMap<Entity>>,
+    charges: Query<&Charge, With<Turret>>,
+    survivors: Res<ParticipantMap<bool>>,
+    stopwatch: Res<TurretStopwatch>,
+    boundary: Res<ArenaBoundary>,
+) -> BattlefieldSnapshot {
+    BattlefieldSnapshot {
+        tile_owners: tiles.iter_many(tile_root.single()).copied().collect(),
+        charges: turrets.map(|turret| charges.get(turret).map_or(0, Charge::value)),
+        survivors: *survivors,
+        turret_time: stopwatch.0.elapsed_secs(),
+        arena_half_width: boundary.half_width,
+    }
+}
+/// Puts the battlefield back into the state of the snapshot, if there is one.
+pub fn restore_battlefield(
+    In(snapshot): In<Option<BattlefieldSnapshot>>,
+    mut commands: Commands,
+    tile_root: Query<&Children, With<TileRoot>>,
+    mut tiles: Query<(&mut Participant, &mut Sprite, &mut CollisionGroups), With<Tile>>,
+    mut tile_counts: ResMut<TileCounts>,
+    mut quadrant_counts: ResMut<QuadrantTileCounts>,
+    colors: Res<ParticipantMap<TileColor>>,
+    turrets: Res<ParticipantMap<Entity>>,
+    mut charges: Query<&mut Charge, With<Turret>>,
+    mut survivors: ResMut<ParticipantMap<bool>>,
+    mut survivor_count: ResMut<SurvivorCount>,
+    mut stopwatch: ResMut<TurretStopwatch>,
+    mut boundary: ResMut<ArenaBoundary>,
+    participant_entity_query: Query<(Entity, &Participant), (Without<Tile>, Without<Bullet>)>,
+) {
+    let Some(snapshot) = snapshot else {
+        return;
+    };
+    let tile_root = tile_root.single();
+    if snapshot.tile_owners.len() == tile_root.len() {
+        let mut tile_iter = tiles.iter_many_mut(tile_root);
+        let mut owners = snapshot.tile_owners.iter();
+        while let (Some((mut owner, mut sprite, mut collision_groups)), Some(&new_owner)) =
+            (tile_iter.fetch_next(), owners.next())
+        {
+            *owner = new_owner;
+            sprite.color = colors[new_owner].0;
+            *collision_groups = tile_collision_groups(new_owner);
+        }
+        // The tiles are spawned a row of all four quadrants at a time, in the order of
+        // `Participant::ALL`.
+        *quadrant_counts = QuadrantTileCounts::default();
+        for (i, &owner) in snapshot.tile_owners.iter().enumerate() {
+            quadrant_counts.0[Participant::ALL[i % 4]][owner] += 1;
+        }
+        *tile_counts = quadrant_counts.totals();
+    } else {
+        warn!("The saved tiles don't fit the current tile grid, so they're left as they are");
+    }
+    for participant in Participant::ALL {
+        if let Ok(mut charge) = charges.get_mut(turrets[participant]) {
+            *charge = Charge::from_value(snapshot.charges[participant]);
+        }
+    }
+    *survivors = snapshot.survivors;
+    survivor_count.0 = Participant::ALL
+        .into_iter()
+        .filter(|&p| survivors[p])
+        .count() as u8;
+    for (entity, &participant) in &participant_entity_query {
+        if !survivors[participant] {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+    stopwatch
+        .0
+        .set_elapsed(Duration::from_secs_f32(snapshot.turret_time));
+    boundary.half_width = snapshot.arena_half_width;
+}
+fn shrinking_arena_enabled(config: Res<GameConfig>) -> bool {
+    config.shrinking_arena
+}
+fn overheat_enabled(config: Res<GameConfig>) -> bool {
+    config.overheat
+}
+fn bullet_size_capped(config: Res<GameConfig>) -> bool {
+    config.bullet_size_cap.is_some()
+}
+/// Draws a pulsing ring around the bullets that grew past the size cap, wider the further past it
+/// they are, to make up for them not looking any bigger.
+fn draw_bullet_auras(
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    mutators: Res<MutatorEffects>,
+    colors: Res<ParticipantMap<BallColor>>,
+    bullets: Query<(&Charge, &GlobalTransform, &Participant), With<Bullet>>,
+) {
+    let pulse = (time.elapsed_seconds() * BULLET_AURA_PULSE_RATE * 2.0 * PI).sin() * 0.5 + 0.5;
+    for (charge, transform, &owner) in &bullets {
+        let size_level = charge.size_level(config.bullet_size_cap);
+        if charge.level <= size_level {
+            continue;
+        }
+        let radius = charge.get_scale(config.bullet_size_cap) * mutators.bullet_size_factor;
+        let reach = (charge.level - size_level) as f32 * BULLET_AURA_SIZE_PER_LEVEL * radius;
+        gizmos.circle_2d(
+            transform.translation().xy(),
+            radius + reach * (0.5 + 0.5 * pulse),
+            colors[owner]
+                .0
+                .with_alpha(BULLET_AURA_ALPHA * (1.0 - 0.5 * pulse)),
+        );
+    }
+}
+fn shrink_arena(stopwatch: Res<TurretStopwatch>, mut boundary: ResMut<ArenaBoundary>) {
+    let shrink_time = stopwatch.0.elapsed_secs() - ARENA_SHRINK_DELAY;
+    if shrink_time <= 0.0 {
+        return;
+    }
+    boundary.half_width =
+        (BATTLEFIELD_HALF_WIDTH - shrink_time * ARENA_SHRINK_SPEED).max(ARENA_MIN_HALF_WIDTH);
+}
+/// Moves the walls, turrets, and bullets to match the current [`ArenaBoundary`], and kills the
+/// tiles that are left outside of it.
+fn apply_arena_boundary(
+    mut commands: Commands,
+    boundary: Res<ArenaBoundary>,
+    grid: Res<TileGrid>,
+    mut root: Query<&mut Collider, With<BattlefieldRoot>>,
+    mut turrets: Query<(&mut Transform, &Participant), (With<Turret>, Without<Tile>)>,
+    mut bullets: Query<&mut Transform, (With<Bullet>, Without<Tile>, Without<Turret>)>,
+    mut tiles: Query<
+        (Entity, &Transform, &mut Sprite, &mut CollisionGroups),
+        (With<Tile>, Without<DeadTile>),
+    >,
+    mut alive_rings: Local<Option<usize>>,
+    tuning: Res<Tuning>,
+) {
+    *root.single_mut() = boundary_collider(boundary.half_width);
+
+    let turret_position = tuning.turret_position * boundary.ratio();
+    for (mut transform, &participant) in &mut turrets {
+        let sign = quadrant_center(participant).signum();
+        transform.translation.x = sign.x * turret_position;
+        transform.translation.y = sign.y * turret_position;
+    }
+
+    for mut transform in &mut bullets {
+        let translation = &mut transform.translation;
+        translation.x = translation
+            .x
+            .clamp(-boundary.half_width, boundary.half_width);
+        translation.y = translation
+            .y
+            .clamp(-boundary.half_width, boundary.half_width);
+    }
+
+    // Tiles are laid out in square rings around the center, so we only need to go through them
+    // when the boundary crosses into a new ring.
+    let tile_dimension = grid.tile_dimension();
+    let rings = (boundary.half_width / tile_dimension).floor() as usize;
+    let previous_rings = alive_rings.replace(rings).unwrap_or(grid.count);
+    if rings >= previous_rings {
+        return;
+    }
+    for (entity, transform, mut sprite, mut collision_groups) in &mut tiles {
+        let distance = transform
+            .translation
+            .x
+            .abs()
+            .max(transform.translation.y.abs());
+        if (distance / tile_dimension) as usize >= rings {
+            sprite.color = DEAD_TILE_COLOR;
+            *collision_groups = CollisionGroups::new(Group::NONE, Group::NONE);
+            commands.entity(entity).insert(DeadTile);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_rapier2d::rapier::geometry::CollisionEventFlags;
+
+    use super::*;
+
+    /// An app with only `count_tiles`, over a grid of tiles that each start out with the
+    /// participant whose quadrant they're in.
+    fn tile_count_app(grid: TileGrid) -> App {
+        let mut app = App::new();
+        app.add_event::<TileCaptured>()
+            .add_event::<RestartEvent>()
+            .add_systems(Update, count_tiles);
+        let quadrant_counts = QuadrantTileCounts::new(grid);
+        app.insert_resource(quadrant_counts.totals())
+            .insert_resource(quadrant_counts);
+        let half = grid.count as i32;
+        for x in -half..half {
+            for y in -half..half {
+                let position = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+                app.world_mut().spawn((
+                    Tile,
+                    quadrant_of(position),
+                    Transform::from_translation(position.extend(0.0)),
+                ));
+            }
+        }
+        app
+    }
+    /// Hands the tile at the position over to `to`, the way `handle_bullet_tile_collision` does.
+    fn capture(app: &mut App, position: Vec2, to: Participant) {
+        let world = app.world_mut();
+        let (tile, from) = world
+            .query_filtered::<(Entity, &Participant, &Transform), With<Tile>>()
+            .iter(world)
+            .find(|(_, _, transform)| transform.translation.xy() == position)
+            .map(|(tile, &from, _)| (tile, from))
+            .expect("there's a tile at the position");
+        *world.get_mut::<Participant>(tile).unwrap() = to;
+        world.send_event(TileCaptured {
+            tile,
+            from,
+            to,
+            position,
+            bullet_charge: 1,
+            bullet_velocity: Vec2::ZERO,
+        });
+    }
+    fn assert_counts_match_tiles(app: &mut App) {
+        let world = app.world_mut();
+        let mut actual = QuadrantTileCounts::default();
+        for (&owner, transform) in world
+            .query_filtered::<(&Participant, &Transform), With<Tile>>()
+            .iter(world)
+        {
+            actual.0[quadrant_of(transform.translation.xy())][owner] += 1;
+        }
+        assert_eq!(world.resource::<QuadrantTileCounts>().0, actual.0);
+        assert_eq!(world.resource::<TileCounts>().0, actual.totals().0);
+    }
+
+    #[test]
+    fn tile_counts_follow_captures() {
+        let mut app = tile_count_app(TileGrid { count: 4 });
+        capture(&mut app, Vec2::new(0.5, 0.5), Participant::B);
+        capture(&mut app, Vec2::new(-3.5, -0.5), Participant::A);
+        app.update();
+        assert_counts_match_tiles(&mut app);
+        capture(&mut app, Vec2::new(1.5, -2.5), Participant::D);
+        app.update();
+        assert_counts_match_tiles(&mut app);
+    }
+    #[test]
+    fn tile_counts_follow_recaptures() {
+        let mut app = tile_count_app(TileGrid { count: 4 });
+        let position = Vec2::new(2.5, 1.5);
+        // Taken and taken back in the same frame, then passed around over the next ones.
+        capture(&mut app, position, Participant::C);
+        capture(&mut app, position, Participant::A);
+        app.update();
+        assert_counts_match_tiles(&mut app);
+        for to in [
+            Participant::B,
+            Participant::C,
+            Participant::D,
+            Participant::B,
+        ] {
+            capture(&mut app, position, to);
+            app.update();
+            assert_counts_match_tiles(&mut app);
+        }
+    }
+    #[test]
+    fn tile_counts_ignore_same_owner_captures() {
+        let mut app = tile_count_app(TileGrid { count: 4 });
+        capture(&mut app, Vec2::new(-0.5, 0.5), Participant::B);
+        capture(&mut app, Vec2::new(0.5, 0.5), Participant::B);
+        capture(&mut app, Vec2::new(0.5, 0.5), Participant::B);
+        app.update();
+        assert_counts_match_tiles(&mut app);
+        let counts = app.world().resource::<TileCounts>();
+        assert_eq!(counts.0[Participant::A], 15);
+        assert_eq!(counts.0[Participant::B], 17);
+    }
+    #[test]
+    fn everyone_eliminated_at_once_is_a_draw() {
+        let tile_counts = TileCounts(ParticipantMap::new(10, 20, 30, 40));
+        let outcome = decide_outcome(
+            &ParticipantMap::default(),
+            &Participant::ALL,
+            &tile_counts,
+            TieBreak::Draw,
+        );
+        assert_eq!(outcome, MatchOutcome::Draw);
+    }
+    #[test]
+    fn most_tiles_picks_the_last_eliminated_with_the_most_tiles() {
+        // D owns the most tiles, but went out before the others.
+        let tile_counts = TileCounts(ParticipantMap::new(10, 30, 20, 40));
+        let outcome = decide_outcome(
+            &ParticipantMap::default(),
+            &[Participant::A, Participant::B, Participant::C],
+            &tile_counts,
+            TieBreak::MostTiles,
+        );
+        assert_eq!(outcome, MatchOutcome::Winner(Participant::B));
+    }
+    #[test]
+    fn most_tiles_tie_is_a_draw() {
+        let tile_counts = TileCounts(ParticipantMap::new(25, 25, 10, 40));
+        let outcome = decide_outcome(
+            &ParticipantMap::default(),
+            &[Participant::A, Participant::B, Participant::C],
+            &tile_counts,
+            TieBreak::MostTiles,
+        );
+        assert_eq!(outcome, MatchOutcome::Draw);
+    }
+    #[test]
+    fn last_survivor_wins_regardless_of_tie_break() {
+        let survivors = ParticipantMap::new(false, false, true, false);
+        for tie_break in [TieBreak::Draw, TieBreak::MostTiles] {
+            let outcome = decide_outcome(
+                &survivors,
+                &[Participant::A],
+                &TileCounts(ParticipantMap::new(40, 0, 0, 0)),
+                tie_break,
+            );
+            assert_eq!(outcome, MatchOutcome::Winner(Participant::C));
+        }
+    }
+    /// A bullet of A that just ran into something of B, with its spawn protection still on.
+    fn protected_bullet_app(target: impl Bundle) -> (App, Entity, Entity) {
+        let mut app = App::new();
+        app.add_event::<CollisionEvent>()
+            .add_event::<SpawnProtectionExpired>()
+            .add_event::<TileCaptured>()
+            .add_event::<TurretDamaged>()
+            .add_event::<LastStandEvent>()
+            .init_resource::<GameConfig>()
+            .insert_resource(TileGrid { count: 4 })
+            .add_systems(
+                Update,
+                (handle_bullet_tile_collision, handle_bullet_turret_collision).chain(),
+            );
+        let bullet = app
+            .world_mut()
+            .spawn((
+                Bullet,
+                Participant::A,
+                Charge::from_value(5),
+                Velocity::zero(),
+                SpawnProtection::default(),
+            ))
+            .id();
+        let target = app.world_mut().spawn(target).id();
+        app.world_mut().send_event(CollisionEvent::Started(
+            bullet,
+            target,
+            CollisionEventFlags::empty(),
+        ));
+        app.update();
+        (app, bullet, target)
+    }
+    /// Takes the protection off the bullet the way `expire_spawn_protection` does, with it still
+    /// touching the target.
+    fn expire_protection(app: &mut App, bullet: Entity, target: Entity) {
+        app.world_mut()
+            .entity_mut(bullet)
+            .remove::<SpawnProtection>();
+        app.world_mut().send_event(SpawnProtectionExpired {
+            bullet,
+            touching: vec![target],
+        });
+        app.update();
+    }
+    fn tile(owner: Participant) -> impl Bundle {
+        (
+            Tile,
+            owner,
+            tile_collision_groups(owner),
+            GlobalTransform::default(),
+        )
+    }
+    fn turret(owner: Participant) -> impl Bundle {
+        (
+            Turret::default(),
+            owner,
+            Charge::from_value(10),
+            LastStand::default(),
+        )
+    }
+
+    #[test]
+    fn protected_bullets_leave_tiles_alone() {
+        let (app, bullet, tile) = protected_bullet_app(tile(Participant::B));
+        assert_eq!(app.world().get::<Participant>(tile), Some(&Participant::B));
+        assert_eq!(app.world().get::<Charge>(bullet).unwrap().value(), 5);
+        assert!(app.world().resource::<Events<TileCaptured>>().is_empty());
+    }
+    #[test]
+    fn expired_bullets_capture_the_tiles_they_are_on() {
+        let (mut app, bullet, tile) = protected_bullet_app(tile(Participant::B));
+        expire_protection(&mut app, bullet, tile);
+        assert_eq!(app.world().get::<Participant>(tile), Some(&Participant::A));
+        assert_eq!(app.world().get::<Charge>(bullet).unwrap().value(), 4);
+        assert_eq!(app.world().resource::<Events<TileCaptured>>().len(), 1);
+    }
+    #[test]
+    fn protected_bullets_leave_turrets_alone() {
+        let (app, bullet, turret) = protected_bullet_app(turret(Participant::B));
+        assert_eq!(app.world().get::<Charge>(turret).unwrap().value(), 10);
+        assert_eq!(app.world().get::<Charge>(bullet).unwrap().value(), 5);
+        assert!(app.world().resource::<Events<TurretDamaged>>().is_empty());
+    }
+    #[test]
+    fn expired_bullets_hit_the_turrets_they_are_on() {
+        let (mut app, bullet, turret) = protected_bullet_app(turret(Participant::B));
+        expire_protection(&mut app, bullet, turret);
+        assert_eq!(app.world().get::<Charge>(turret).unwrap().value(), 5);
+        assert_eq!(app.world().get::<Charge>(bullet).unwrap().value(), 0);
+        assert_eq!(app.world().resource::<Events<TurretDamaged>>().len(), 1);
+    }
+    #[test]
+    fn distance_policy_phases_in_out_of_the_corner() {
+        let turret_position = 300.0;
+        let range = 2.0 * (BATTLEFIELD_HALF_WIDTH - turret_position);
+        let edge = BATTLEFIELD_HALF_WIDTH - range;
+        for half_width in [BATTLEFIELD_HALF_WIDTH, BATTLEFIELD_HALF_WIDTH - 100.0] {
+            let edge = edge - (BATTLEFIELD_HALF_WIDTH - half_width);
+            let phases_in = |position: Vec2| {
+                can_phase_in(
+                    BulletPhasePolicy::Distance,
+                    position,
+                    half_width,
+                    turret_position,
+                    Duration::ZERO,
+                )
+            };
+            assert!(!phases_in(Vec2::new(edge + 1.0, edge + 1.0)));
+            assert!(!phases_in(Vec2::new(-edge - 1.0, edge + 1.0)));
+            assert!(phases_in(Vec2::new(edge - 1.0, edge + 1.0)));
+            assert!(phases_in(Vec2::new(edge + 1.0, -edge + 1.0)));
+        }
+    }
+    #[test]
+    fn timed_policy_phases_in_once_old_enough() {
+        let phases_in = |millis| {
+            can_phase_in(
+                BulletPhasePolicy::Timed(100),
+                Vec2::splat(BATTLEFIELD_HALF_WIDTH),
+                BATTLEFIELD_HALF_WIDTH,
+                300.0,
+                Duration::from_millis(millis),
+            )
+        };
+        assert!(!phases_in(99));
+        assert!(phases_in(100));
+        assert!(phases_in(101));
+    }
+    #[test]
+    fn contact_policy_phases_in_right_away() {
+        assert!(can_phase_in(
+            BulletPhasePolicy::Contact,
+            Vec2::splat(BATTLEFIELD_HALF_WIDTH),
+            BATTLEFIELD_HALF_WIDTH,
+            300.0,
+            Duration::ZERO,
+        ));
+    }
 }
@@ -0,0 +1,369 @@
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, Write},
+    iter::Peekable,
+    str::Chars,
+    sync::{
+        mpsc::{self, Receiver},
+        Mutex,
+    },
+};
+
+use bevy::prelude::*;
+
+use crate::{
+    battlefield::{EliminationEvent, RestartEvent, SurvivorCount, TurretDamaged},
+    config::{GameConfig, MatchSeed},
+    obs::json_string,
+    panel_plugin::{TriggerEvent, TriggerType},
+    restart::RestartRequestEvent,
+    speed::SetSpeedEvent,
+    state::AppState,
+    stats::{MatchFinishedEvent, MatchStats},
+    utils::{Participant, ParticipantMap},
+};
+
+// Constants {{{
+
+/// Set this environment variable to turn on the bridge.
+pub const EVENT_BRIDGE_VAR: &str = "EVENT_BRIDGE";
+
+// }}}
+
+/// Lets other programs follow and steer the game without linking to it. Gameplay events are
+/// written to stdout as JSON lines, like `{"event":"eliminated","participant":"green",
+/// "survivors":2}`, and commands are read from stdin the same way:
+///
+/// - `{"command":"trigger","participant":"red","trigger":"multiply","factor":2}`, with
+///   `burst_shot` and `charged_shot` as the other triggers, which take no factor, and `penalty`,
+///   which takes a `percent` instead
+/// - `{"command":"set_timescale","speed":2.0}`
+/// - `{"command":"restart"}`, with `"rematch":true` to replay the same seed
+///
+/// Commands that can't be carried out get an `error` event back with the reason.
+pub struct EventBridgePlugin;
+impl Plugin for EventBridgePlugin {
+    fn build(&self, app: &mut App) {
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in io::stdin().lock().lines() {
+                let Ok(line) = line else {
+                    return;
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                if sender.send(BridgeCommand::parse(&line)).is_err() {
+                    return;
+                }
+            }
+        });
+        app.insert_resource(CommandInbox(Mutex::new(receiver)))
+            .add_systems(
+                OnEnter(AppState::Playing),
+                send_match_start.run_if(run_once()),
+            )
+            .add_systems(
+                Update,
+                (
+                    run_commands,
+                    send_match_start.run_if(on_event::<RestartEvent>()),
+                    send_triggers.run_if(on_event::<TriggerEvent>()),
+                    send_turret_damage.run_if(on_event::<TurretDamaged>()),
+                    send_eliminations.run_if(on_event::<EliminationEvent>()),
+                    send_match_end.run_if(on_event::<MatchFinishedEvent>()),
+                ),
+            );
+    }
+}
+
+/// The commands read off stdin, or why they couldn't be.
+#[derive(Resource)]
+struct CommandInbox(Mutex<Receiver<Result<BridgeCommand, String>>>);
+#[derive(Debug, Clone, Copy)]
+enum BridgeCommand {
+    Trigger {
+        participant: Participant,
+        trigger_type: TriggerType,
+    },
+    SetTimescale(f32),
+    Restart {
+        rematch: bool,
+    },
+}
+impl BridgeCommand {
+    fn parse(line: &str) -> Result<Self, String> {
+        let object = parse_object(line)?;
+        let string = |key: &str| match object.get(key) {
+            Some(JsonValue::String(value)) => Ok(value.as_str()),
+            _ => Err(format!("`{}` must be a string", key)),
+        };
+        let number = |key: &str| match object.get(key) {
+            Some(&JsonValue::Number(value)) => Ok(value),
+            _ => Err(format!("`{}` must be a number", key)),
+        };
+        match string("command")? {
+            "trigger" => {
+                let name = string("participant")?;
+                let participant = Participant::ALL
+                    .into_iter()
+                    .find(|participant| participant.to_string().eq_ignore_ascii_case(name))
+                    .ok_or_else(|| format!("there's no participant named `{}`", name))?;
+                let trigger_type = match string("trigger")? {
+                    "multiply" => {
+                        let factor = number("factor")?;
+                        if factor.fract() != 0.0 || !(1.0..=u8::MAX as f64).contains(&factor) {
+                            return Err(format!("{} isn't a factor from 1 to 255", factor));
+                        }
+                        TriggerType::Multiply(factor as u8)
+                    }
+                    "burst_shot" => TriggerType::BurstShot,
+                    "charged_shot" => TriggerType::ChargedShot,
+                    "penalty" => {
+                        let percent = number("percent")?;
+                        if percent.fract() != 0.0 || !(1.0..=100.0).contains(&percent) {
+                            return Err(format!("{} isn't a percentage from 1 to 100", percent));
+                        }
+                        TriggerType::Penalty(percent as u8)
+                    }
+                    other => return Err(format!("there's no trigger named `{}`", other)),
+                };
+                Ok(Self::Trigger {
+                    participant,
+                    trigger_type,
+                })
+            }
+            "set_timescale" => {
+                let speed = number("speed")?;
+                if !speed.is_finite() || speed <= 0.0 {
+                    return Err(format!("{} isn't a speed above 0", speed));
+                }
+                Ok(Self::SetTimescale(speed as f32))
+            }
+            "restart" => {
+                let rematch = match object.get("rematch") {
+                    None => false,
+                    Some(&JsonValue::Bool(rematch)) => rematch,
+                    _ => return Err("`rematch` must be true or false".to_string()),
+                };
+                Ok(Self::Restart { rematch })
+            }
+            other => Err(format!("there's no command named `{}`", other)),
+        }
+    }
+}
+#[derive(Debug, Clone)]
+enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+}
+
+/// Parses a JSON object with only strings, numbers, booleans and nulls in it, which is as much as
+/// the commands need.
+fn parse_object(text: &str) -> Result<HashMap<String, JsonValue>, String> {
+    let mut chars = text.chars().peekable();
+    let mut object = HashMap::new();
+    skip_whitespace(&mut chars);
+    expect(&mut chars, '{')?;
+    skip_whitespace(&mut chars);
+    if chars.next_if_eq(&'}').is_none() {
+        loop {
+            skip_whitespace(&mut chars);
+            let key = parse_string(&mut chars)?;
+            skip_whitespace(&mut chars);
+            expect(&mut chars, ':')?;
+            skip_whitespace(&mut chars);
+            let value = parse_value(&mut chars)?;
+            object.insert(key, value);
+            skip_whitespace(&mut chars);
+            match chars.next() {
+                Some(',') => continue,
+                Some('}') => break,
+                _ => return Err("expected `,` or `}` after a value".to_string()),
+            }
+        }
+    }
+    skip_whitespace(&mut chars);
+    if chars.next().is_some() {
+        return Err("there's more after the object".to_string());
+    }
+    Ok(object)
+}
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+fn expect(chars: &mut Peekable<Chars>, expected: char) -> Result<(), String> {
+    match chars.next() {
+        Some(c) if c == expected => Ok(()),
+        _ => Err(format!("expected `{}`", expected)),
+    }
+}
+fn parse_string(chars: &mut Peekable<Chars>) -> Result<String, String> {
+    expect(chars, '"')?;
+    let mut string = String::new();
+    loop {
+        match chars.next().ok_or("a string isn't closed")? {
+            '"' => return Ok(string),
+            '\\' => string.push(match chars.next().ok_or("a string isn't closed")? {
+                'n' => '\n',
+                't' => '\t',
+                'r' => '\r',
+                'b' => '\u{8}',
+                'f' => '\u{c}',
+                'u' => {
+                    let code = chars.by_ref().take(4).collect::<String>();
+                    u32::from_str_radix(&code, 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .ok_or_else(|| format!("`\\u{}` isn't a character", code))?
+                }
+                c => c,
+            }),
+            c => string.push(c),
+        }
+    }
+}
+fn parse_value(chars: &mut Peekable<Chars>) -> Result<JsonValue, String> {
+    if chars.peek() == Some(&'"') {
+        return parse_string(chars).map(JsonValue::String);
+    }
+    let mut word = String::new();
+    while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || "+-.".contains(*c)) {
+        word.push(c);
+    }
+    match word.as_str() {
+        "null" => Ok(JsonValue::Null),
+        "true" => Ok(JsonValue::Bool(true)),
+        "false" => Ok(JsonValue::Bool(false)),
+        _ => word
+            .parse()
+            .map(JsonValue::Number)
+            .map_err(|_| format!("`{}` isn't a value", word)),
+    }
+}
+/// The other end going away isn't worth stopping the game for, so write errors are ignored.
+fn send(line: String) {
+    let _ = writeln!(io::stdout().lock(), "{}", line);
+}
+fn participant_name(participant: Participant) -> String {
+    json_string(&participant.to_string().to_lowercase())
+}
+fn run_commands(
+    inbox: Res<CommandInbox>,
+    state: Res<State<AppState>>,
+    config: Res<GameConfig>,
+    survivors: Res<ParticipantMap<bool>>,
+    mut trigger_events: EventWriter<TriggerEvent>,
+    mut speed_events: EventWriter<SetSpeedEvent>,
+    mut restart_events: EventWriter<RestartRequestEvent>,
+) {
+    for command in inbox.0.lock().unwrap().try_iter() {
+        let result = command.and_then(|command| match command {
+            BridgeCommand::Trigger { .. } if *state.get() != AppState::Playing => {
+                Err("triggers only work while a match is being played".to_string())
+            }
+            BridgeCommand::Trigger { participant, .. } if !survivors[participant] => {
+                Err(format!("{} is out of the match", participant))
+            }
+            BridgeCommand::Trigger {
+                participant,
+                trigger_type,
+            } => {
+                trigger_events.send(TriggerEvent {
+                    participant,
+                    trigger_type,
+                    zone: None,
+                });
+                Ok(())
+            }
+            BridgeCommand::SetTimescale(_) if config.deterministic => {
+                Err("the speed can't be changed in deterministic mode".to_string())
+            }
+            BridgeCommand::SetTimescale(speed) => {
+                speed_events.send(SetSpeedEvent { speed });
+                Ok(())
+            }
+            BridgeCommand::Restart { rematch } => {
+                restart_events.send(RestartRequestEvent {
+                    rematch,
+                    seed: None,
+                });
+                Ok(())
+            }
+        });
+        if let Err(error) = result {
+            send(format!(
+                "{{\"event\":\"error\",\"message\":{}}}",
+                json_string(&error)
+            ));
+        }
+    }
+}
+fn send_match_start(mut events: EventReader<RestartEvent>, config: Res<GameConfig>) {
+    events.clear();
+    let participants = Participant::ALL
+        .into_iter()
+        .filter(|&participant| config.participants[participant])
+        .map(participant_name)
+        .collect::<Vec<_>>();
+    send(format!(
+        "{{\"event\":\"match_started\",\"participants\":[{}]}}",
+        participants.join(",")
+    ));
+}
+fn send_triggers(mut events: EventReader<TriggerEvent>) {
+    for event in events.read() {
+        let trigger = match event.trigger_type {
+            TriggerType::Multiply(factor) => format!("\"multiply\",\"factor\":{}", factor),
+            TriggerType::BurstShot => "\"burst_shot\"".to_string(),
+            TriggerType::ChargedShot => "\"charged_shot\"".to_string(),
+            TriggerType::Penalty(percent) => format!("\"penalty\",\"percent\":{}", percent),
+        };
+        send(format!(
+            "{{\"event\":\"trigger\",\"participant\":{},\"trigger\":{}}}",
+            participant_name(event.participant),
+            trigger
+        ));
+    }
+}
+fn send_turret_damage(mut events: EventReader<TurretDamaged>) {
+    for event in events.read() {
+        send(format!(
+            "{{\"event\":\"turret_damaged\",\"attacker\":{},\"defender\":{},\"damage\":{},\
+             \"remaining\":{}}}",
+            participant_name(event.attacker),
+            participant_name(event.victim),
+            event.amount,
+            event.remaining
+        ));
+    }
+}
+fn send_eliminations(
+    mut events: EventReader<EliminationEvent>,
+    survivor_count: Res<SurvivorCount>,
+) {
+    for event in events.read() {
+        send(format!(
+            "{{\"event\":\"eliminated\",\"participant\":{},\"survivors\":{}}}",
+            participant_name(event.participant),
+            survivor_count.0
+        ));
+    }
+}
+fn send_match_end(
+    mut events: EventReader<MatchFinishedEvent>,
+    stats: Res<MatchStats>,
+    seed: Res<MatchSeed>,
+) {
+    for event in events.read() {
+        let winner = event
+            .winner
+            .map_or_else(|| "null".to_string(), participant_name);
+        send(format!(
+            "{{\"event\":\"match_finished\",\"winner\":{},\"duration\":{},\"seed\":{}}}",
+            winner, stats.elapsed, seed.0
+        ));
+    }
+}
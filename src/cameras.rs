@@ -0,0 +1,126 @@
+use bevy::{
+    core_pipeline::bloom::{BloomPrefilterSettings, BloomSettings},
+    prelude::*,
+    render::camera::ScalingMode,
+    render::view::RenderLayers,
+};
+
+use crate::{
+    config::DisplayConfig,
+    panel_plugin::PanelRootSide,
+    ui::{camera_center, play_area_size},
+};
+
+// Constants {{{
+
+/// Everything that isn't explicitly put on another layer ends up on the battlefield's.
+pub const BATTLEFIELD_LAYER: usize = 0;
+pub const LEFT_PANEL_LAYER: usize = 1;
+pub const RIGHT_PANEL_LAYER: usize = 2;
+/// Nothing in the world is on this layer, so the UI camera only draws the UI.
+const UI_LAYER: usize = 3;
+
+/// Only colors brighter than this bloom, which leaves everything but the glowing charge balls
+/// alone.
+const BLOOM_THRESHOLD: f32 = 1.0;
+
+// }}}
+
+/// Draws the battlefield, each of the panels, and the UI with their own cameras, so that each view
+/// can be zoomed and moved around independently.
+pub struct CamerasPlugin;
+impl Plugin for CamerasPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_cameras)
+            .add_systems(PostUpdate, propagate_render_layers);
+    }
+}
+
+#[derive(Component, Clone, Copy)]
+pub struct BattlefieldCamera;
+#[derive(Component, Clone, Copy)]
+pub struct PanelCamera(pub PanelRootSide);
+#[derive(Component, Clone, Copy)]
+pub struct UiCamera;
+/// Marker for entities whose [`RenderLayers`] should be copied to all of their descendants.
+#[derive(Component, Clone, Copy)]
+pub struct PropagateRenderLayers;
+
+pub fn panel_render_layers(side: PanelRootSide) -> RenderLayers {
+    match side {
+        PanelRootSide::Left => RenderLayers::layer(LEFT_PANEL_LAYER),
+        PanelRootSide::Right => RenderLayers::layer(RIGHT_PANEL_LAYER),
+    }
+}
+fn spawn_cameras(mut commands: Commands, config: Res<DisplayConfig>) {
+    let play_area_size = play_area_size(config.layout);
+    let camera_bundle = |order: isize| Camera2dBundle {
+        camera: Camera {
+            order,
+            // Only the first camera clears the screen, the rest draw on top of it.
+            clear_color: if order == 0 {
+                ClearColorConfig::Default
+            } else {
+                ClearColorConfig::None
+            },
+            // Cameras drawing on top of each other have to agree on HDR.
+            hdr: config.bloom,
+            ..default()
+        },
+        projection: OrthographicProjection {
+            far: 1000.0,
+            near: -1000.0,
+            scaling_mode: ScalingMode::AutoMin {
+                min_width: play_area_size.x,
+                min_height: play_area_size.y,
+            },
+            ..default()
+        },
+        transform: Transform::from_translation(camera_center(config.layout).extend(0.0)),
+        ..default()
+    };
+    let mut battlefield_camera = commands.spawn((
+        Name::new("Battlefield Camera"),
+        BattlefieldCamera,
+        camera_bundle(0),
+        RenderLayers::layer(BATTLEFIELD_LAYER),
+    ));
+    if config.bloom {
+        battlefield_camera.insert(BloomSettings {
+            prefilter_settings: BloomPrefilterSettings {
+                threshold: BLOOM_THRESHOLD,
+                threshold_softness: 0.2,
+            },
+            ..BloomSettings::OLD_SCHOOL
+        });
+    }
+    for (order, side) in [(1, PanelRootSide::Left), (2, PanelRootSide::Right)] {
+        commands.spawn((
+            Name::new(format!("{:?} Panel Camera", side)),
+            PanelCamera(side),
+            camera_bundle(order),
+            panel_render_layers(side),
+        ));
+    }
+    commands.spawn((
+        Name::new("UI Camera"),
+        UiCamera,
+        IsDefaultUiCamera,
+        camera_bundle(3),
+        RenderLayers::layer(UI_LAYER),
+    ));
+}
+fn propagate_render_layers(
+    mut commands: Commands,
+    roots: Query<(Entity, &RenderLayers), With<PropagateRenderLayers>>,
+    children: Query<&Children>,
+    layers: Query<Option<&RenderLayers>>,
+) {
+    for (root, root_layers) in &roots {
+        for descendant in children.iter_descendants(root) {
+            if layers.get(descendant).ok().flatten() != Some(root_layers) {
+                commands.entity(descendant).insert(root_layers.clone());
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use std::{collections::VecDeque, time::Duration};
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+
+use crate::{
+    battlefield::{quadrant_center, Charge, RestartEvent, BATTLEFIELD_HALF_WIDTH},
+    utils::{BallColor, Participant, ParticipantMap},
+};
+
+// Constants {{{
+
+/// Seconds between samples of the turret charges.
+const SAMPLE_INTERVAL: f32 = 0.25;
+/// The number of samples kept, which makes for the last 30 seconds.
+const HISTORY_LENGTH: usize = 120;
+
+const SPARKLINE_WIDTH: f32 = 120.0;
+const SPARKLINE_HEIGHT: f32 = 30.0;
+/// The distance from the side of the battlefield to the end of the sparkline nearest to it, which
+/// keeps it clear of the turret.
+const SPARKLINE_HORIZONTAL_INSET: f32 = 90.0;
+/// The distance from the top or bottom of the battlefield to the middle of the sparkline.
+const SPARKLINE_VERTICAL_INSET: f32 = 30.0;
+const SPARKLINE_BACKGROUND_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.4);
+
+// }}}
+
+/// Keeps track of each turret's charge over time and draws it as a small sparkline next to its
+/// corner, which shows the rhythm of multiplying and releasing.
+pub struct ChargeHistoryPlugin;
+impl Plugin for ChargeHistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ChargeHistory>().add_systems(
+            Update,
+            (
+                clear_charge_history.run_if(on_event::<RestartEvent>()),
+                sample_charges.run_if(on_timer(Duration::from_secs_f32(SAMPLE_INTERVAL))),
+                draw_sparklines,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// The most recent turret charges, oldest first.
+#[derive(Resource, Default)]
+struct ChargeHistory(ParticipantMap<VecDeque<u64>>);
+
+fn clear_charge_history(mut history: ResMut<ChargeHistory>) {
+    for participant in Participant::ALL {
+        history.0[participant].clear();
+    }
+}
+/// Eliminated participants stop being sampled, so their sparkline freezes.
+fn sample_charges(
+    mut history: ResMut<ChargeHistory>,
+    turrets: Res<ParticipantMap<Entity>>,
+    charges: Query<&Charge>,
+) {
+    for participant in Participant::ALL {
+        let Ok(charge) = charges.get(turrets[participant]) else {
+            continue;
+        };
+        let samples = &mut history.0[participant];
+        if samples.len() == HISTORY_LENGTH {
+            samples.pop_front();
+        }
+        samples.push_back(charge.value());
+    }
+}
+fn draw_sparklines(
+    mut gizmos: Gizmos,
+    history: Res<ChargeHistory>,
+    colors: Res<ParticipantMap<BallColor>>,
+) {
+    for participant in Participant::ALL {
+        let samples = &history.0[participant];
+        if samples.len() < 2 {
+            continue;
+        }
+        let corner = quadrant_center(participant).signum();
+        let center = Vec2::new(
+            corner.x
+                * (BATTLEFIELD_HALF_WIDTH - SPARKLINE_HORIZONTAL_INSET - SPARKLINE_WIDTH / 2.0),
+            corner.y * (BATTLEFIELD_HALF_WIDTH - SPARKLINE_VERTICAL_INSET),
+        );
+        gizmos.rect_2d(
+            center,
+            0.0,
+            Vec2::new(SPARKLINE_WIDTH, SPARKLINE_HEIGHT),
+            SPARKLINE_BACKGROUND_COLOR,
+        );
+        // Charges multiply, so they are plotted on a log scale to keep small values readable.
+        let heights: Vec<f32> = samples
+            .iter()
+            .map(|&value| (value as f32 + 1.0).log2())
+            .collect();
+        let max_height = heights.iter().copied().fold(1.0, f32::max);
+        let origin = center - Vec2::new(SPARKLINE_WIDTH, SPARKLINE_HEIGHT) / 2.0;
+        let step = SPARKLINE_WIDTH / (HISTORY_LENGTH - 1) as f32;
+        // New samples come in on the right, like a scrolling chart.
+        let first_x = (HISTORY_LENGTH - samples.len()) as f32 * step;
+        gizmos.linestrip_2d(
+            heights.iter().enumerate().map(|(i, &height)| {
+                origin
+                    + Vec2::new(
+                        first_x + i as f32 * step,
+                        height / max_height * SPARKLINE_HEIGHT,
+                    )
+            }),
+            colors[participant].0,
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use bevy::prelude::*;
+
+use crate::{
+    cameras::{BattlefieldCamera, PanelCamera},
+    panel_plugin::{PanelRootSide, TriggerEvent},
+    utils::{BallColor, ParticipantMap},
+};
+
+// Constants {{{
+
+/// How long a spark takes to get from the trigger zone to the turret.
+const SPARK_DURATION: f32 = 0.6;
+const SPARK_SIZE: f32 = 12.0;
+/// How far the middle of the arc bulges out from a straight line, relative to its length.
+const ARC_BULGE: f32 = 0.25;
+
+// }}}
+
+/// Sends a spark flying from the trigger zone a marble landed in to the turret it belongs to, so
+/// it's clear what set off the turret.
+pub struct ChargeTransferPlugin;
+impl Plugin for ChargeTransferPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                spawn_sparks.run_if(on_event::<TriggerEvent>()),
+                move_sparks.run_if(any_with_component::<Spark>),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// A UI node flying along a quadratic Bézier curve, in logical pixels.
+#[derive(Component)]
+struct Spark {
+    timer: Timer,
+    start: Vec2,
+    control: Vec2,
+    end: Vec2,
+}
+
+fn spawn_sparks(
+    mut commands: Commands,
+    mut events: EventReader<TriggerEvent>,
+    turrets: Res<ParticipantMap<Entity>>,
+    colors: Res<ParticipantMap<BallColor>>,
+    transforms: Query<&GlobalTransform>,
+    battlefield_camera: Query<(&Camera, &GlobalTransform), With<BattlefieldCamera>>,
+    panel_cameras: Query<(&Camera, &GlobalTransform, &PanelCamera)>,
+) {
+    let Ok((battlefield_camera, battlefield_camera_transform)) = battlefield_camera.get_single()
+    else {
+        return;
+    };
+    for event in events.read() {
+        let side = PanelRootSide::for_participant(event.participant);
+        let Some((panel_camera, panel_camera_transform, _)) = panel_cameras
+            .iter()
+            .find(|(_, _, &PanelCamera(camera_side))| camera_side == side)
+        else {
+            continue;
+        };
+        let (Some(Ok(zone)), Ok(turret)) = (
+            event.zone.map(|zone| transforms.get(zone)),
+            transforms.get(turrets[event.participant]),
+        ) else {
+            continue;
+        };
+        let (Some(start), Some(end)) = (
+            panel_camera.world_to_viewport(panel_camera_transform, zone.translation()),
+            battlefield_camera
+                .world_to_viewport(battlefield_camera_transform, turret.translation()),
+        ) else {
+            continue;
+        };
+        // Bulge upward, which is toward negative y in the viewport.
+        let perpendicular = (end - start).perp();
+        let perpendicular = if perpendicular.y > 0.0 {
+            -perpendicular
+        } else {
+            perpendicular
+        };
+        commands.spawn((
+            Name::new(format!("Charge Spark: {}", event.participant)),
+            Spark {
+                timer: Timer::from_seconds(SPARK_DURATION, TimerMode::Once),
+                start,
+                control: start.lerp(end, 0.5) + perpendicular * ARC_BULGE,
+                end,
+            },
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Px(SPARK_SIZE),
+                    height: Val::Px(SPARK_SIZE),
+                    ..default()
+                },
+                background_color: colors[event.participant].0.into(),
+                border_radius: BorderRadius::MAX,
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Global(1),
+                ..default()
+            },
+        ));
+    }
+}
+fn move_sparks(
+    mut commands: Commands,
+    time: Res<Time>,
+    ui_scale: Res<UiScale>,
+    mut sparks: Query<(Entity, &mut Spark, &mut Style, &mut Visibility)>,
+) {
+    for (entity, mut spark, mut style, mut visibility) in &mut sparks {
+        if spark.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        // Ease in, so the spark speeds up toward the turret.
+        let t = spark.timer.fraction().powi(2);
+        let position = spark
+            .start
+            .lerp(spark.control, t)
+            .lerp(spark.control.lerp(spark.end, t), t);
+        // `Val::Px` gets multiplied by the UI scale, so it has to be divided out here.
+        let position = position / ui_scale.0;
+        style.left = Val::Px(position.x - SPARK_SIZE / 2.0);
+        style.top = Val::Px(position.y - SPARK_SIZE / 2.0);
+        *visibility = Visibility::Inherited;
+    }
+}
@@ -0,0 +1,84 @@
+use bevy::window::WindowMode;
+
+use crate::{
+    config::GameConfig,
+    utils::{Participant, ParticipantMap},
+};
+
+// Constants {{{
+
+const USAGE: &str = "Usage: multiply_or_release [--windowed | --fullscreen] [--seed <u64>] \
+                     [--speed <f32>] [--participants <2-4>]";
+
+// }}}
+
+/// The options the game can be launched with when it's not running a subcommand. They take
+/// precedence over the environment variables.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LaunchOptions {
+    pub window_mode: Option<WindowMode>,
+    pub seed: Option<u64>,
+    /// How many times as fast as usual the simulation starts out running.
+    pub speed: Option<f32>,
+    /// How many participants take part, starting from the first.
+    pub participants: Option<usize>,
+}
+impl LaunchOptions {
+    /// Prints the usage and exits if any of the arguments aren't valid.
+    pub fn parse(args: &[String]) -> Self {
+        Self::try_parse(args).unwrap_or_else(|error| {
+            eprintln!("{}\n{}", error, USAGE);
+            std::process::exit(2);
+        })
+    }
+    fn try_parse(args: &[String]) -> Result<Self, String> {
+        let mut options = Self::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value =
+                |name: &str| args.next().ok_or_else(|| format!("{} needs a value", name));
+            match arg.as_str() {
+                "--windowed" => options.window_mode = Some(WindowMode::Windowed),
+                "--fullscreen" => options.window_mode = Some(WindowMode::BorderlessFullscreen),
+                "--seed" => {
+                    let seed = value(arg)?;
+                    options.seed = Some(
+                        seed.parse()
+                            .map_err(|_| format!("Invalid seed `{}`", seed))?,
+                    );
+                }
+                "--speed" => {
+                    let speed = value(arg)?;
+                    match speed.parse::<f32>() {
+                        Ok(speed) if speed > 0.0 => options.speed = Some(speed),
+                        _ => return Err(format!("Invalid speed `{}`", speed)),
+                    }
+                }
+                "--participants" => {
+                    let count = value(arg)?;
+                    match count.parse::<usize>() {
+                        Ok(count) if (2..=Participant::ALL.len()).contains(&count) => {
+                            options.participants = Some(count);
+                        }
+                        _ => return Err(format!("Invalid participant count `{}`", count)),
+                    }
+                }
+                _ => return Err(format!("Unknown argument `{}`", arg)),
+            }
+        }
+        Ok(options)
+    }
+    /// Sets the options that are part of the rules.
+    pub fn apply(&self, config: &mut GameConfig) {
+        if let Some(seed) = self.seed {
+            config.seed = Some(seed);
+        }
+        if let Some(count) = self.participants {
+            let mut participants = ParticipantMap::splat(false);
+            for participant in Participant::ALL.into_iter().take(count) {
+                participants[participant] = true;
+            }
+            config.participants = participants;
+        }
+    }
+}
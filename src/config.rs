@@ -0,0 +1,503 @@
+use std::{fs, io::ErrorKind};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    battlefield::{
+        BATTLEFIELD_HALF_WIDTH, DEFAULT_BOOSTED_TURRET_CHARGE_VALUE,
+        DEFAULT_BURST_SHOT_BULLET_SPEED, DEFAULT_CHARGED_SHOT_BULLET_SPEED,
+        DEFAULT_MULTI_SHOT_CHARGE_OFFSET, DEFAULT_TILE_COUNT, DEFAULT_TURRET_BOOST_COOLDOWN,
+        DEFAULT_TURRET_POSITION,
+    },
+    panel_plugin::{TriggerType, DEFAULT_WORKER_BALL_SPAWN_INTERVAL},
+    utils::{Participant, ParticipantMap},
+};
+
+/// Charged shots from this level on leave a trail by default.
+const DEFAULT_BULLET_TRAIL_LEVEL: u64 = 8;
+const DEFAULT_READABLE_CHARGE_TEXT_SIZE: f32 = 6.0;
+/// The smallest tiles can get, in pixels. Any smaller and there are too many of them to simulate.
+const MIN_TILE_SIZE: f32 = 2.0;
+/// Far enough from the walls that the barrels don't poke through them.
+const MIN_TURRET_WALL_DISTANCE: f32 = 20.0;
+
+/// Options that change how a match plays out.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameConfig {
+    /// Slowly contract the battlefield boundary over the course of the match to force a finish.
+    pub shrinking_arena: bool,
+    /// Every now and then, shake up all the worker balls in the panels.
+    pub earthquakes: bool,
+    /// Have every participant draw their worker ball spawn positions from the same random sequence,
+    /// so the outcome depends on the physics rather than on who got luckier draws.
+    pub mirror_spawns: bool,
+    /// The seed for the worker ball spawn positions. A random one is picked when `None`.
+    pub seed: Option<u64>,
+    /// The number of tiles along each side of a participant's starting quadrant. Takes effect when
+    /// the next match starts.
+    pub tile_count: usize,
+    /// Potato mode for integrated GPUs: fewer tiles, a cap on the number of bullets, no worker ball
+    /// trails, and simple flashes instead of particle effects. Takes effect when the next match
+    /// starts.
+    pub low_spec: bool,
+    /// Give the participant with the least territory a bigger multiplier on the Multiply zones, the
+    /// further behind they are.
+    pub catch_up: bool,
+    /// How a turret releases its charge on a burst shot.
+    pub burst_pattern: BurstPattern,
+    /// Each bullet of a burst shot is aimed at a random enemy tile, give or take a little, rather
+    /// than along the barrel, so bursts rain down over the enemy territory.
+    pub tile_targeting: bool,
+    /// When a bullet hits a turret, whichever of them survives the exchange absorbs part of the
+    /// charge the other lost.
+    pub vampiric_duels: bool,
+    /// Who wins when the last participants are all eliminated at the same time.
+    pub tie_break: TieBreak,
+    /// When worker balls of different participants bump into each other, each of them knocks a
+    /// little charge off the other's turret.
+    pub ball_sabotage: bool,
+    /// Once a match, a hit that would take a turret's last charge leaves it at 1 instead, and
+    /// shields it for a moment.
+    pub last_stand: bool,
+    /// When bullets leaving the same turret start colliding with each other.
+    pub bullet_phase: BulletPhasePolicy,
+    /// Both release zones only hand the turret a release, and the turret picks between a burst
+    /// shot and a charged shot itself, going by its charge and the enemy bullets closing in.
+    pub shot_ai: bool,
+    /// Turrets that get a lot of releases in a short time overheat, and turn and fire slower
+    /// until they've cooled down.
+    pub overheat: bool,
+    /// Each panel gets a chute in both of its bottom corners that now and then sends a worker ball
+    /// through to the other panel. It keeps triggering for its owner over there, and goes back
+    /// home after its next trigger.
+    pub chained_panels: bool,
+    /// The charge level past which bullets and charge balls stop growing. Bullets past it get
+    /// denser instead, so they hit as hard as they would have, and get a pulsing aura to show it.
+    pub bullet_size_cap: Option<u64>,
+    /// Who takes part in the match. The quadrants of the ones sitting it out are open territory for
+    /// the others from the start. Takes effect when the next match starts.
+    pub participants: ParticipantMap<bool>,
+    /// Twists on the rules for the match, in the order of [`Mutator::ALL`]. Takes effect when the
+    /// next match starts.
+    pub mutators: Vec<Mutator>,
+    /// Ways to win besides being the last one standing, checked in this order as the match goes
+    /// on.
+    pub victory_conditions: Vec<VictoryCondition>,
+    /// Advance the simulation by the same amount every frame, rather than by however long the
+    /// frame took, so the same seed always plays out the same way. Speed controls are turned off
+    /// along with it. Builds with the `deterministic` feature also get the same physics on every
+    /// platform.
+    pub deterministic: bool,
+}
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            shrinking_arena: false,
+            earthquakes: false,
+            mirror_spawns: false,
+            seed: None,
+            tile_count: DEFAULT_TILE_COUNT,
+            low_spec: false,
+            catch_up: false,
+            burst_pattern: BurstPattern::default(),
+            tile_targeting: false,
+            vampiric_duels: false,
+            tie_break: TieBreak::default(),
+            ball_sabotage: false,
+            last_stand: true,
+            bullet_phase: BulletPhasePolicy::default(),
+            shot_ai: false,
+            overheat: false,
+            chained_panels: false,
+            bullet_size_cap: None,
+            participants: ParticipantMap::splat(true),
+            mutators: Vec::new(),
+            victory_conditions: Vec::new(),
+            deterministic: cfg!(feature = "deterministic"),
+        }
+    }
+}
+impl GameConfig {
+    /// Checks for options that can't be played with, which can only come from presets, match codes
+    /// and launch options, and describes every one of them.
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut problems = Vec::new();
+        if self.tile_count == 0 {
+            problems.push("tile_count must be at least 1".to_string());
+        } else if BATTLEFIELD_HALF_WIDTH / (self.tile_count as f32) < MIN_TILE_SIZE {
+            problems.push(format!(
+                "tile_count is {}, but at most {} tiles fit along a quadrant {} pixels wide",
+                self.tile_count,
+                (BATTLEFIELD_HALF_WIDTH / MIN_TILE_SIZE) as usize,
+                BATTLEFIELD_HALF_WIDTH
+            ));
+        }
+        let seated = Participant::ALL
+            .into_iter()
+            .filter(|&p| self.participants[p])
+            .count();
+        if seated < 2 {
+            problems.push(format!(
+                "participants has {} taking part, but a match needs at least 2",
+                seated
+            ));
+        }
+        for (i, mutator) in self.mutators.iter().enumerate() {
+            if self.mutators[..i].contains(mutator) {
+                problems.push(format!("mutators lists {} more than once", mutator.name()));
+            }
+        }
+        for condition in &self.victory_conditions {
+            match *condition {
+                VictoryCondition::Territory(percent) if !(1..=100).contains(&percent) => {
+                    problems.push(format!(
+                        "Territory({}) must be between 1 and 100 percent",
+                        percent
+                    ));
+                }
+                VictoryCondition::TimedScore(0) => {
+                    problems.push("TimedScore(0) would end the match as it starts".to_string());
+                }
+                _ => (),
+            }
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+    /// A hash of all the options except for the seed, stable across runs and platforms, to tell
+    /// apart matches played with different rules.
+    pub fn rules_hash(&self) -> u64 {
+        let rules = Self {
+            seed: None,
+            ..self.clone()
+        };
+        // FNV-1a
+        format!("{:?}", rules)
+            .bytes()
+            .fold(0xcbf29ce484222325, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+            })
+    }
+}
+/// Balance numbers that can be tweaked without recompiling, read from a RON file when the game
+/// starts. Unlike the [`GameConfig`], they aren't part of match codes, so replaying a match the
+/// same way takes the same file.
+#[derive(Debug, Clone, Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Tuning {
+    /// How far the turrets sit from the center of the battlefield, along both axes.
+    pub turret_position: f32,
+    /// How long after getting hit a turret releases without a boosted charge, in seconds.
+    pub boost_cooldown: f32,
+    /// The time between worker balls spawning in the panels, in seconds.
+    pub worker_ball_spawn_interval: f32,
+    /// The trigger zones along the bottom of each panel, from left to right. The conveyors and the
+    /// gate above them stay over the outermost fifths of the panel, where the release zones are by
+    /// default.
+    pub trigger_zones: Vec<ZoneTuning>,
+}
+impl Default for Tuning {
+    fn default() -> Self {
+        let zone = |width, effect| ZoneTuning { width, effect };
+        Self {
+            turret_position: DEFAULT_TURRET_POSITION,
+            boost_cooldown: DEFAULT_TURRET_BOOST_COOLDOWN,
+            worker_ball_spawn_interval: DEFAULT_WORKER_BALL_SPAWN_INTERVAL,
+            trigger_zones: vec![
+                zone(1.0, ZoneEffect::BurstShot(ReleaseTuning::burst_shot())),
+                zone(0.85, ZoneEffect::Multiply(2)),
+                zone(0.4, ZoneEffect::Multiply(4)),
+                zone(0.5, ZoneEffect::Penalty(25)),
+                zone(0.4, ZoneEffect::Multiply(4)),
+                zone(0.85, ZoneEffect::Multiply(2)),
+                zone(1.0, ZoneEffect::ChargedShot(ReleaseTuning::charged_shot())),
+            ],
+        }
+    }
+}
+impl Tuning {
+    /// How releases that didn't come from a zone fire, like the ones sent over the event bridge.
+    /// They go by the first zone of the same kind.
+    pub fn release(&self, trigger_type: TriggerType) -> ReleaseTuning {
+        let zone = self
+            .trigger_zones
+            .iter()
+            .find_map(|zone| match (zone.effect, trigger_type) {
+                (ZoneEffect::BurstShot(release), TriggerType::BurstShot)
+                | (ZoneEffect::ChargedShot(release), TriggerType::ChargedShot) => Some(release),
+                _ => None,
+            });
+        zone.unwrap_or_else(|| match trigger_type {
+            TriggerType::ChargedShot => ReleaseTuning::charged_shot(),
+            _ => ReleaseTuning::burst_shot(),
+        })
+    }
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut problems = Vec::new();
+        let max_turret_position = BATTLEFIELD_HALF_WIDTH - MIN_TURRET_WALL_DISTANCE;
+        if !(0.0..=max_turret_position).contains(&self.turret_position) {
+            problems.push(format!(
+                "turret_position is {}, but has to be between 0 and {} to keep the turrets inside \
+                 the battlefield",
+                self.turret_position, max_turret_position
+            ));
+        }
+        if self.worker_ball_spawn_interval.is_nan() || self.worker_ball_spawn_interval <= 0.0 {
+            problems.push(format!(
+                "worker_ball_spawn_interval is {}, but has to be above 0",
+                self.worker_ball_spawn_interval
+            ));
+        }
+        if self.trigger_zones.is_empty() {
+            problems.push("trigger_zones has to have at least one zone".to_string());
+        }
+        for (i, zone) in self.trigger_zones.iter().enumerate() {
+            if !zone.width.is_finite() || zone.width <= 0.0 {
+                problems.push(format!(
+                    "trigger zone {} is {} wide, but has to be wider than 0",
+                    i + 1,
+                    zone.width
+                ));
+            }
+            match zone.effect {
+                ZoneEffect::Multiply(0) => {
+                    problems.push(format!(
+                        "trigger zone {} multiplies by 0, which would wipe out the charge",
+                        i + 1
+                    ));
+                }
+                ZoneEffect::Multiply(_) => (),
+                ZoneEffect::Penalty(percent) if !(1..=100).contains(&percent) => {
+                    problems.push(format!(
+                        "trigger zone {} takes away {}% of the charge, but has to take from 1 to \
+                         100%",
+                        i + 1,
+                        percent
+                    ));
+                }
+                ZoneEffect::Penalty(_) => (),
+                ZoneEffect::BurstShot(release) | ZoneEffect::ChargedShot(release) => {
+                    if release.speed.is_nan() || release.speed <= 0.0 {
+                        problems.push(format!(
+                            "trigger zone {} fires at {}, but has to fire faster than 0",
+                            i + 1,
+                            release.speed
+                        ));
+                    }
+                    if release.boosted_charge == 0 {
+                        problems.push(format!(
+                            "trigger zone {} leaves a boosted charge of 0, but it has to be at \
+                             least 1",
+                            i + 1
+                        ));
+                    }
+                }
+            }
+        }
+        if self.boost_cooldown.is_nan() || self.boost_cooldown < 0.0 {
+            problems.push(format!(
+                "boost_cooldown is {}, but can't be negative",
+                self.boost_cooldown
+            ));
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+    /// Options the file leaves out get their default values, and so do all of them if there's no
+    /// file.
+    pub fn load(path: &str) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => ron::from_str(&text).map_err(|error| error.to_string()),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error.to_string()),
+        }
+    }
+}
+/// One of the trigger zones along the bottom of each panel.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ZoneTuning {
+    /// How much of the width of the panel the zone takes up, relative to the other zones.
+    pub width: f32,
+    pub effect: ZoneEffect,
+}
+/// What a worker ball landing in a trigger zone does to its turret.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum ZoneEffect {
+    /// Multiplies the turret's charge by this much.
+    Multiply(u8),
+    /// Releases the turret's charge as a burst of bullets.
+    BurstShot(ReleaseTuning),
+    /// Releases the turret's charge as a single bullet.
+    ChargedShot(ReleaseTuning),
+    /// Takes this percentage of the turret's charge away, but always leaves it at least 1.
+    Penalty(u8),
+}
+impl ZoneEffect {
+    pub fn trigger_type(self) -> TriggerType {
+        match self {
+            Self::Multiply(factor) => TriggerType::Multiply(factor),
+            Self::BurstShot(_) => TriggerType::BurstShot,
+            Self::ChargedShot(_) => TriggerType::ChargedShot,
+            Self::Penalty(percent) => TriggerType::Penalty(percent),
+        }
+    }
+}
+/// How a release zone has the turret fire its charge. With the shot AI on, this goes for whichever
+/// shot the turret picks.
+#[derive(Debug, Clone, Copy, PartialEq, Component, Reflect, Serialize, Deserialize)]
+pub struct ReleaseTuning {
+    /// How fast the bullets leave the barrel, in pixels per second.
+    pub speed: f32,
+    /// The charge the turret starts over from after releasing, unless it was hit within the boost
+    /// cooldown.
+    pub boosted_charge: u64,
+    /// How many less than the level of the charge left each bullet of a sequential burst shot is
+    /// worth.
+    pub burst_level_offset: u64,
+}
+impl ReleaseTuning {
+    fn burst_shot() -> Self {
+        Self {
+            speed: DEFAULT_BURST_SHOT_BULLET_SPEED,
+            boosted_charge: DEFAULT_BOOSTED_TURRET_CHARGE_VALUE,
+            burst_level_offset: DEFAULT_MULTI_SHOT_CHARGE_OFFSET,
+        }
+    }
+    fn charged_shot() -> Self {
+        Self {
+            speed: DEFAULT_CHARGED_SHOT_BULLET_SPEED,
+            ..Self::burst_shot()
+        }
+    }
+}
+/// How a turret releases its charge when one of its marbles lands in the burst shot zone.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BurstPattern {
+    /// One bullet at a time, each worth a bit more the more charge is left, until it runs out.
+    #[default]
+    Sequential,
+    /// All of the charge at once, split evenly between this many bullets fanned out around the
+    /// barrel.
+    Fan(u8),
+    /// Bullets worth this much each, fired at a steady rate until the charge runs out.
+    Salvo(u64),
+}
+/// When a bullet that was just fired stops passing through the other bullets leaving the same
+/// turret. It never happens while the bullet is touching something, so it doesn't get stuck inside
+/// another one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BulletPhasePolicy {
+    /// Once the bullet is out of the corner it was fired from.
+    #[default]
+    Distance,
+    /// Once the bullet has been around for this many milliseconds.
+    Timed(u32),
+    /// As soon as the bullet isn't touching anything.
+    Contact,
+}
+/// A way for a match to be won before only one participant is left. Being the last one standing
+/// always wins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum VictoryCondition {
+    /// Owning at least this many percent of all the tiles.
+    Territory(u8),
+    /// Owning the most tiles once this many seconds are up. It's a draw if that's tied.
+    TimedScore(u32),
+}
+/// A twist on the rules that can be turned on for a match on its own, and shows on the HUD while
+/// it's on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Mutator {
+    /// Worker balls fall at half the speed.
+    LowGravity,
+    /// Bullets are twice as wide, but no heavier.
+    GiantBullets,
+    /// The Multiply zones multiply by twice as much.
+    DoubleMultiply,
+    /// Turrets always fire with a boosted charge, even right after getting hit.
+    NoBoostCooldown,
+}
+impl Mutator {
+    pub const ALL: [Self; 4] = [
+        Self::LowGravity,
+        Self::GiantBullets,
+        Self::DoubleMultiply,
+        Self::NoBoostCooldown,
+    ];
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::LowGravity => "Low Gravity",
+            Self::GiantBullets => "Giant Bullets",
+            Self::DoubleMultiply => "Double Multiply",
+            Self::NoBoostCooldown => "No Boost Cooldown",
+        }
+    }
+}
+/// How to settle a match where nobody is left standing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TieBreak {
+    #[default]
+    Draw,
+    /// Whoever of the last participants owned the most tiles wins, and it's a draw if that's tied
+    /// too.
+    MostTiles,
+}
+/// Options that only change how the game is presented.
+#[derive(Debug, Clone, Resource)]
+pub struct DisplayConfig {
+    /// Multiplies the size of the UI, on top of the automatic scaling with the window size.
+    pub ui_scale: f32,
+    /// Where the panels go relative to the battlefield.
+    pub layout: ScreenLayout,
+    /// Make the big charge balls glow. Needs HDR rendering, which is turned off along with it.
+    pub bloom: bool,
+    /// The lowest charge level of charged shots that leave a trail behind them.
+    pub bullet_trail_level: u64,
+    /// The smallest font size the charge in a ball is written out in full. When it would have to
+    /// be smaller to fit, it's written as a power of two instead, like `2^20`.
+    pub readable_charge_text_size: f32,
+    /// Put a light shadow behind the charge in every ball, so it stands out on busy backgrounds.
+    pub charge_text_shadow: bool,
+}
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        Self {
+            ui_scale: 1.0,
+            layout: ScreenLayout::default(),
+            bloom: true,
+            bullet_trail_level: DEFAULT_BULLET_TRAIL_LEVEL,
+            readable_charge_text_size: DEFAULT_READABLE_CHARGE_TEXT_SIZE,
+            charge_text_shadow: false,
+        }
+    }
+}
+/// Where the panels go relative to the battlefield.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScreenLayout {
+    /// One panel on each side of the battlefield, for 16:9 screens.
+    #[default]
+    Landscape,
+    /// Both panels below the battlefield, for streaming in a vertical 9:16 format.
+    Portrait,
+}
+impl std::str::FromStr for ScreenLayout {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "landscape" => Ok(Self::Landscape),
+            "portrait" => Ok(Self::Portrait),
+            _ => Err(format!("unknown layout `{}`", s)),
+        }
+    }
+}
+/// The seed the current match was started with.
+#[derive(Debug, Clone, Copy, Resource)]
+pub struct MatchSeed(pub u64);
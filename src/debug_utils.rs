@@ -6,13 +6,15 @@ use rand::{distributions::Uniform, prelude::*};
 
 use crate::{
     battlefield::{EliminationEvent, BATTLEFIELD_HALF_WIDTH},
-    panel_plugin::{TriggerEvent, TriggerType},
+    panel_plugin::{MatchRng, TriggerEvent, TriggerType},
     utils::{BallColor, Participant, ParticipantMap, TileHitEffect},
 };
 
 pub struct DebugUtilsPlugin;
 impl Plugin for DebugUtilsPlugin {
     fn build(&self, app: &mut App) {
+        // The gameplay components register their reflection in their own plugins, so they show up
+        // as editable in the inspector.
         // app.add_plugins(bevy_inspector_egui::quick::WorldInspectorPlugin::new())
         // app.add_plugins(bevy_rapier2d::render::RapierDebugRenderPlugin::default())
         app.insert_resource(AutoTimer::default())
@@ -33,11 +35,11 @@ fn auto_hanabi(
     time: Res<Time>,
     effect: Res<TileHitEffect>,
     colors: Res<ParticipantMap<BallColor>>,
+    mut rng: ResMut<MatchRng>,
 ) {
     timer.tick(time.delta());
     if timer.just_finished() {
         let dist = Uniform::new_inclusive(-BATTLEFIELD_HALF_WIDTH, BATTLEFIELD_HALF_WIDTH);
-        let mut rng = thread_rng();
         let x = rng.sample(dist);
         let y = rng.sample(dist);
         let p = match rng.sample(Uniform::new(0, 4)) {
@@ -88,11 +90,13 @@ fn auto_fire(mut writer: EventWriter<TriggerEvent>, mut timer: ResMut<AutoTimer>
                 writer.send(TriggerEvent {
                     participant,
                     trigger_type: TriggerType::Multiply(4),
+                    zone: None,
                 });
             }
             writer.send(TriggerEvent {
                 participant,
                 trigger_type: TriggerType::BurstShot,
+                zone: None,
             });
         }
     }
@@ -107,11 +111,7 @@ fn auto_multiply(
         writer.send(TriggerEvent {
             participant: Participant::A,
             trigger_type: TriggerType::Multiply(4),
+            zone: None,
         });
     }
 }
-fn print_trigger_events(mut events: EventReader<TriggerEvent>) {
-    for event in events.read() {
-        println!("{:#?}", event);
-    }
-}
@@ -0,0 +1,198 @@
+#![allow(clippy::type_complexity)]
+
+use bevy::prelude::*;
+
+use crate::{
+    battlefield::{quadrant_center, Charge, RestartEvent, SurvivorCount, BATTLEFIELD_HALF_WIDTH},
+    cameras::BattlefieldCamera,
+    config::DisplayConfig,
+    ui::{camera_center, PlayAreaNode},
+    utils::{BallColor, Participant, ParticipantMap},
+};
+
+// Constants {{{
+
+const DIM_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.5);
+/// Above the tiles, but below the bullets and turrets.
+const DIM_Z: f32 = 1.0;
+
+/// How far the camera pans from its usual center toward the midpoint of the two survivors.
+const CAMERA_PAN_FACTOR: f32 = 0.25;
+const CAMERA_ZOOM_SCALE: f32 = 0.9;
+/// Higher values make the camera reach its target faster.
+const CAMERA_SMOOTHING: f32 = 2.0;
+
+const CHARGE_BAR_WIDTH: f32 = 40.0;
+const CHARGE_BAR_HEIGHT: f32 = 16.0;
+const CHARGE_BAR_TOP: f32 = 2.0;
+const CHARGE_BAR_BORDER_COLOR: Color = Color::BLACK;
+
+// }}}
+
+pub struct FinalDuelPlugin;
+impl Plugin for FinalDuelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                enter_final_duel.run_if(resource_changed::<SurvivorCount>),
+                update_charge_bar.run_if(resource_exists::<FinalDuel>),
+                update_camera,
+                exit_final_duel.run_if(on_event::<RestartEvent>()),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Present while only two participants remain, or after the game ended in a final duel.
+#[derive(Resource, Clone, Copy)]
+struct FinalDuel([Participant; 2]);
+/// Marker for every entity spawned for the final duel presentation.
+#[derive(Component, Clone, Copy)]
+struct FinalDuelPresentation;
+#[derive(Component, Clone, Copy)]
+struct ChargeBarSegment(Participant);
+
+fn enter_final_duel(
+    mut commands: Commands,
+    survivor_count: Res<SurvivorCount>,
+    survivors: Res<ParticipantMap<bool>>,
+    colors: Res<ParticipantMap<BallColor>>,
+    duel: Option<Res<FinalDuel>>,
+) {
+    if survivor_count.0 != 2 || duel.is_some() {
+        return;
+    }
+    let &[a, b] = Participant::ALL
+        .into_iter()
+        .filter(|&p| survivors[p])
+        .collect::<Vec<_>>()
+        .as_slice()
+    else {
+        return;
+    };
+    commands.insert_resource(FinalDuel([a, b]));
+
+    for participant in Participant::ALL.into_iter().filter(|&p| !survivors[p]) {
+        commands.spawn((
+            Name::new(format!("Final Duel Dim: {}", participant)),
+            FinalDuelPresentation,
+            SpriteBundle {
+                sprite: Sprite {
+                    color: DIM_COLOR,
+                    ..default()
+                },
+                transform: Transform {
+                    translation: quadrant_center(participant).extend(DIM_Z),
+                    scale: Vec3::new(BATTLEFIELD_HALF_WIDTH, BATTLEFIELD_HALF_WIDTH, 1.0),
+                    rotation: Quat::IDENTITY,
+                },
+                ..default()
+            },
+        ));
+    }
+
+    let play_area = commands
+        .spawn((
+            Name::new("Final Duel UI"),
+            FinalDuelPresentation,
+            PlayAreaNode,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .id();
+    commands
+        .spawn((
+            Name::new("Final Duel Charge Bar"),
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Percent(CHARGE_BAR_TOP),
+                    left: Val::Percent((100.0 - CHARGE_BAR_WIDTH) / 2.0),
+                    width: Val::Percent(CHARGE_BAR_WIDTH),
+                    height: Val::Px(CHARGE_BAR_HEIGHT),
+                    border: UiRect::all(Val::Px(2.0)),
+                    flex_direction: FlexDirection::Row,
+                    ..default()
+                },
+                border_color: CHARGE_BAR_BORDER_COLOR.into(),
+                ..default()
+            },
+        ))
+        .set_parent(play_area)
+        .with_children(|parent| {
+            for participant in [a, b] {
+                parent.spawn((
+                    ChargeBarSegment(participant),
+                    NodeBundle {
+                        style: Style {
+                            width: Val::Percent(50.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        background_color: colors[participant].0.into(),
+                        ..default()
+                    },
+                ));
+            }
+        });
+}
+fn update_charge_bar(
+    duel: Res<FinalDuel>,
+    turrets: Res<ParticipantMap<Entity>>,
+    charge_query: Query<&Charge>,
+    mut segments: Query<(&mut Style, &ChargeBarSegment)>,
+) {
+    let charge_of = |participant: Participant| {
+        charge_query
+            .get(turrets[participant])
+            .map_or(0.0, |charge| charge.value() as f64)
+    };
+    let FinalDuel([a, b]) = *duel;
+    let (charge_a, charge_b) = (charge_of(a), charge_of(b));
+    let total = charge_a + charge_b;
+    for (mut style, &ChargeBarSegment(participant)) in &mut segments {
+        let share = if total == 0.0 {
+            0.5
+        } else if participant == a {
+            charge_a / total
+        } else {
+            charge_b / total
+        };
+        style.width = Val::Percent(share as f32 * 100.0);
+    }
+}
+fn update_camera(
+    duel: Option<Res<FinalDuel>>,
+    mut camera: Query<(&mut Transform, &mut OrthographicProjection), With<BattlefieldCamera>>,
+    config: Res<DisplayConfig>,
+    time: Res<Time>,
+) {
+    let center = camera_center(config.layout);
+    let (target_translation, target_scale) = match duel {
+        Some(duel) => {
+            let FinalDuel([a, b]) = *duel;
+            let midpoint = (quadrant_center(a) + quadrant_center(b)) / 2.0;
+            (center.lerp(midpoint, CAMERA_PAN_FACTOR), CAMERA_ZOOM_SCALE)
+        }
+        None => (center, 1.0),
+    };
+    let (mut transform, mut projection) = camera.single_mut();
+    let t = 1.0 - (-CAMERA_SMOOTHING * time.delta_seconds()).exp();
+    let translation = transform.translation.xy().lerp(target_translation, t);
+    transform.translation.x = translation.x;
+    transform.translation.y = translation.y;
+    projection.scale = projection.scale.lerp(target_scale, t);
+}
+fn exit_final_duel(mut commands: Commands, garbage: Query<Entity, With<FinalDuelPresentation>>) {
+    commands.remove_resource::<FinalDuel>();
+    for entity in garbage.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
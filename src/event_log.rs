@@ -0,0 +1,152 @@
+use std::{fs::File, sync::Mutex};
+
+use bevy::{
+    log::{BoxedLayer, Level},
+    prelude::*,
+};
+use tracing_subscriber::{filter::Targets, Layer};
+
+use crate::{
+    battlefield::{EliminationEvent, RestartEvent, SurvivorCount, TileCaptured, TurretDamaged},
+    panel_plugin::TriggerEvent,
+    stats::{MatchFinishedEvent, MatchStats},
+    utils::{Participant, ParticipantMap},
+};
+
+// Constants {{{
+
+/// The `tracing` target of every gameplay event.
+pub const GAME_EVENTS_TARGET: &str = "game_events";
+/// The environment variable holding the path of the file to write gameplay events to as JSON lines.
+pub const EVENT_LOG_VAR: &str = "GAME_EVENT_LOG";
+/// Tile captures are too frequent to log one by one, so they're summed up over this many seconds.
+const CAPTURE_REPORT_INTERVAL: f32 = 1.0;
+
+// }}}
+
+/// Emits the gameplay events through `tracing`. Frequent events are logged at the debug level and
+/// rare ones at the info level.
+pub struct EventLogPlugin;
+impl Plugin for EventLogPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(CaptureReport {
+            timer: Timer::from_seconds(CAPTURE_REPORT_INTERVAL, TimerMode::Repeating),
+            captures: default(),
+        })
+        .add_systems(
+            Update,
+            (
+                log_triggers.run_if(on_event::<TriggerEvent>()),
+                log_captures,
+                log_turret_damage.run_if(on_event::<TurretDamaged>()),
+                log_eliminations.run_if(on_event::<EliminationEvent>()),
+                log_match_end.run_if(on_event::<MatchFinishedEvent>()),
+                log_restarts.run_if(on_event::<RestartEvent>()),
+            ),
+        );
+    }
+}
+
+/// A layer for [`bevy::log::LogPlugin::custom_layer`] that writes the gameplay events to the file
+/// named by [`EVENT_LOG_VAR`], if it's set.
+pub fn json_file_layer(_app: &mut App) -> Option<BoxedLayer> {
+    let path = std::env::var(EVENT_LOG_VAR).ok()?;
+    let file = match File::create(&path) {
+        Ok(file) => file,
+        Err(error) => {
+            eprintln!("Failed to create the event log {}: {}", path, error);
+            return None;
+        }
+    };
+    let layer = tracing_subscriber::fmt::layer()
+        .json()
+        .with_writer(Mutex::new(file))
+        .with_filter(Targets::new().with_target(GAME_EVENTS_TARGET, Level::DEBUG));
+    Some(Box::new(layer))
+}
+
+#[derive(Resource)]
+struct CaptureReport {
+    timer: Timer,
+    captures: ParticipantMap<u64>,
+}
+
+fn log_triggers(mut events: EventReader<TriggerEvent>) {
+    for event in events.read() {
+        debug!(
+            target: GAME_EVENTS_TARGET,
+            participant = %event.participant,
+            trigger = %event.trigger_type.to_string().replace('\n', " "),
+            "trigger"
+        );
+    }
+}
+fn log_captures(
+    mut report: ResMut<CaptureReport>,
+    mut events: EventReader<TileCaptured>,
+    time: Res<Time>,
+) {
+    for event in events.read() {
+        report.captures[event.to] += 1;
+    }
+    if !report.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    for participant in Participant::ALL {
+        let captures = std::mem::take(&mut report.captures[participant]);
+        if captures > 0 {
+            debug!(
+                target: GAME_EVENTS_TARGET,
+                participant = %participant,
+                captures,
+                interval = CAPTURE_REPORT_INTERVAL,
+                "tiles captured"
+            );
+        }
+    }
+}
+fn log_turret_damage(mut events: EventReader<TurretDamaged>) {
+    for event in events.read() {
+        debug!(
+            target: GAME_EVENTS_TARGET,
+            attacker = %event.attacker,
+            defender = %event.victim,
+            damage = event.amount,
+            remaining = event.remaining,
+            "turret damaged"
+        );
+    }
+}
+fn log_eliminations(
+    mut events: EventReader<EliminationEvent>,
+    survivor_count: Res<SurvivorCount>,
+    stats: Res<MatchStats>,
+) {
+    for event in events.read() {
+        info!(
+            target: GAME_EVENTS_TARGET,
+            participant = %event.participant,
+            survivors = survivor_count.0,
+            elapsed = stats.elapsed,
+            "participant eliminated"
+        );
+    }
+}
+fn log_match_end(mut events: EventReader<MatchFinishedEvent>, stats: Res<MatchStats>) {
+    for event in events.read() {
+        let winner = event
+            .winner
+            .map_or_else(|| "none".to_string(), |winner| winner.to_string());
+        info!(
+            target: GAME_EVENTS_TARGET,
+            winner,
+            duration = stats.elapsed,
+            "match finished"
+        );
+    }
+}
+fn log_restarts(mut events: EventReader<RestartEvent>) {
+    for _ in events.read() {
+        info!(target: GAME_EVENTS_TARGET, "match restarted");
+    }
+}
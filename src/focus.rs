@@ -0,0 +1,209 @@
+use bevy::{input::InputSystem, prelude::*, ui::UiSystem};
+
+// Constants {{{
+
+const FOCUS_OUTLINE_COLOR: Color = Color::srgb(1.0, 0.85, 0.2);
+const FOCUS_OUTLINE_WIDTH: f32 = 4.0;
+const FOCUS_OUTLINE_OFFSET: f32 = 4.0;
+/// How much more being off to the side counts against a button than being further along, when
+/// picking the next one to move the focus to.
+const SIDEWAYS_PENALTY: f32 = 2.0;
+
+// }}}
+
+/// Lets the buttons marked [`Focusable`] be used without a mouse. The arrow keys or the d-pad move
+/// the focus to the nearest button in that direction, and Enter or the south face button presses
+/// the focused one, which the button handlers can't tell apart from a click. The focused button
+/// gets an outline, and hovering a button with the mouse focuses it too.
+pub struct FocusNavigationPlugin;
+impl Plugin for FocusNavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Focus>()
+            .add_systems(
+                PreUpdate,
+                (release_pressed, follow_hover, move_focus, press_focused)
+                    .chain()
+                    .after(InputSystem)
+                    .after(UiSystem::Focus),
+            )
+            .add_systems(Update, (add_focus_outlines, draw_focus_outline).chain());
+    }
+}
+
+/// Marker for buttons that can be moved to with the keyboard or a gamepad.
+#[derive(Component, Clone, Copy, Default)]
+pub struct Focusable;
+#[derive(Resource, Default)]
+struct Focus {
+    focused: Option<Entity>,
+    /// The button pressed from the keyboard or a gamepad last frame, to let go of.
+    pressed: Option<Entity>,
+}
+#[derive(Clone, Copy)]
+enum FocusMove {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+impl FocusMove {
+    /// In UI coordinates, where y goes down.
+    fn vector(self) -> Vec2 {
+        match self {
+            Self::Up => Vec2::NEG_Y,
+            Self::Down => Vec2::Y,
+            Self::Left => Vec2::NEG_X,
+            Self::Right => Vec2::X,
+        }
+    }
+}
+
+fn just_pressed(
+    keys: &ButtonInput<KeyCode>,
+    gamepads: &Gamepads,
+    gamepad_buttons: &ButtonInput<GamepadButton>,
+    key: KeyCode,
+    button: GamepadButtonType,
+) -> bool {
+    keys.just_pressed(key)
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad_buttons.just_pressed(GamepadButton::new(gamepad, button)))
+}
+/// Buttons that are hidden, or inside a node that isn't displayed, can't take the focus.
+fn can_focus(node: &Node, visibility: &InheritedVisibility) -> bool {
+    visibility.get() && node.size() != Vec2::ZERO
+}
+fn release_pressed(mut focus: ResMut<Focus>, mut interactions: Query<&mut Interaction>) {
+    let Some(pressed) = focus.pressed.take() else {
+        return;
+    };
+    if let Ok(mut interaction) = interactions.get_mut(pressed) {
+        if *interaction == Interaction::Pressed {
+            *interaction = Interaction::None;
+        }
+    }
+}
+fn follow_hover(
+    mut focus: ResMut<Focus>,
+    buttons: Query<(Entity, &Interaction), (Changed<Interaction>, With<Focusable>)>,
+) {
+    for (entity, interaction) in &buttons {
+        if *interaction == Interaction::Hovered {
+            focus.focused = Some(entity);
+        }
+    }
+}
+/// With nothing focused, the top left button that can be gets the focus.
+fn move_focus(
+    mut focus: ResMut<Focus>,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    buttons: Query<(Entity, &Node, &GlobalTransform, &InheritedVisibility), With<Focusable>>,
+) {
+    let candidates = buttons
+        .iter()
+        .filter(|(_, node, _, visibility)| can_focus(node, visibility))
+        .map(|(entity, _, transform, _)| (entity, transform.translation().xy()))
+        .collect::<Vec<_>>();
+    let current = focus
+        .focused
+        .and_then(|focused| candidates.iter().find(|(entity, _)| *entity == focused));
+    let Some(&(current, position)) = current else {
+        focus.focused = candidates
+            .iter()
+            .min_by(|(_, a), (_, b)| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)))
+            .map(|&(entity, _)| entity);
+        return;
+    };
+    let direction = [
+        (FocusMove::Up, KeyCode::ArrowUp, GamepadButtonType::DPadUp),
+        (
+            FocusMove::Down,
+            KeyCode::ArrowDown,
+            GamepadButtonType::DPadDown,
+        ),
+        (
+            FocusMove::Left,
+            KeyCode::ArrowLeft,
+            GamepadButtonType::DPadLeft,
+        ),
+        (
+            FocusMove::Right,
+            KeyCode::ArrowRight,
+            GamepadButtonType::DPadRight,
+        ),
+    ]
+    .into_iter()
+    .find(|&(_, key, button)| just_pressed(&keys, &gamepads, &gamepad_buttons, key, button));
+    let Some((direction, ..)) = direction else {
+        return;
+    };
+    let along = direction.vector();
+    let next = candidates
+        .iter()
+        .filter(|&&(entity, _)| entity != current)
+        .filter_map(|&(entity, other)| {
+            let offset = other - position;
+            let distance = offset.dot(along);
+            (distance > 0.0).then(|| {
+                let sideways = offset.perp_dot(along).abs();
+                (entity, distance + sideways * SIDEWAYS_PENALTY)
+            })
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b));
+    if let Some((next, _)) = next {
+        focus.focused = Some(next);
+    }
+}
+fn press_focused(
+    mut focus: ResMut<Focus>,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    mut buttons: Query<(&mut Interaction, &Node, &InheritedVisibility), With<Focusable>>,
+) {
+    let pressed = just_pressed(
+        &keys,
+        &gamepads,
+        &gamepad_buttons,
+        KeyCode::Enter,
+        GamepadButtonType::South,
+    );
+    let Some(focused) = focus.focused.filter(|_| pressed) else {
+        return;
+    };
+    if let Ok((mut interaction, node, visibility)) = buttons.get_mut(focused) {
+        if can_focus(node, visibility) {
+            *interaction = Interaction::Pressed;
+            focus.pressed = Some(focused);
+        }
+    }
+}
+/// The outline stays on every focusable button and only changes color, which is cheaper than
+/// adding and removing it.
+fn add_focus_outlines(mut commands: Commands, buttons: Query<Entity, Added<Focusable>>) {
+    for entity in &buttons {
+        commands.entity(entity).insert(Outline::new(
+            Val::Px(FOCUS_OUTLINE_WIDTH),
+            Val::Px(FOCUS_OUTLINE_OFFSET),
+            Color::NONE,
+        ));
+    }
+}
+fn draw_focus_outline(
+    focus: Res<Focus>,
+    mut outlines: Query<(Entity, &mut Outline), With<Focusable>>,
+) {
+    for (entity, mut outline) in &mut outlines {
+        let color = if focus.focused == Some(entity) {
+            FOCUS_OUTLINE_COLOR
+        } else {
+            Color::NONE
+        };
+        if outline.color != color {
+            outline.color = color;
+        }
+    }
+}
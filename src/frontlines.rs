@@ -0,0 +1,151 @@
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+        texture::ImageSampler,
+    },
+};
+
+use crate::{
+    battlefield::{RestartEvent, TileCaptured, TileGrid, BATTLEFIELD_HALF_WIDTH},
+    state::AppState,
+};
+
+// Constants {{{
+
+const FRONTLINES_KEY: KeyCode = KeyCode::KeyF;
+/// Above the tiles, the quadrant tint and the capture flashes.
+const FRONTLINES_Z: f32 = -0.4;
+/// How opaque the most contested tiles are. Tiles that never changed hands are left clear.
+const MAX_ALPHA: f32 = 0.9;
+
+// }}}
+
+/// A view of how many times each tile changed hands over the match, toggled with F once the match
+/// is over, which picks out where the frontlines were.
+pub struct FrontlinesPlugin;
+impl Plugin for FrontlinesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FlipCounts>()
+            .add_systems(Startup, setup_overlay)
+            .add_systems(
+                Update,
+                (
+                    reset_flip_counts.run_if(on_event::<RestartEvent>()),
+                    count_flips.run_if(on_event::<TileCaptured>()),
+                    toggle_overlay.run_if(in_state(AppState::GameOver)),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// The number of times each tile changed hands, row by row from the top left of the battlefield.
+#[derive(Resource, Default)]
+struct FlipCounts {
+    side: usize,
+    counts: Vec<u32>,
+}
+#[derive(Component, Clone, Copy)]
+struct FrontlinesOverlay;
+
+fn setup_overlay(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let mut image = Image::new_fill(
+        Extent3d::default(),
+        TextureDimension::D2,
+        &[0; 4],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    // Each tile is a single pixel, which has to stay a crisp square when stretched.
+    image.sampler = ImageSampler::nearest();
+    commands.spawn((
+        Name::new("Frontlines Overlay"),
+        FrontlinesOverlay,
+        SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(Vec2::splat(BATTLEFIELD_HALF_WIDTH * 2.0)),
+                ..default()
+            },
+            texture: images.add(image),
+            transform: Transform::from_xyz(0.0, 0.0, FRONTLINES_Z),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+    ));
+}
+fn reset_flip_counts(
+    mut counts: ResMut<FlipCounts>,
+    mut overlay: Query<&mut Visibility, With<FrontlinesOverlay>>,
+) {
+    *counts = FlipCounts::default();
+    for mut visibility in &mut overlay {
+        *visibility = Visibility::Hidden;
+    }
+}
+fn count_flips(
+    mut events: EventReader<TileCaptured>,
+    mut counts: ResMut<FlipCounts>,
+    grid: Res<TileGrid>,
+) {
+    let side = grid.side_count();
+    if counts.side != side {
+        *counts = FlipCounts {
+            side,
+            counts: vec![0; side * side],
+        };
+    }
+    let dimension = grid.tile_dimension();
+    for event in events.read() {
+        let column = ((event.position.x + BATTLEFIELD_HALF_WIDTH) / dimension) as usize;
+        let row = ((BATTLEFIELD_HALF_WIDTH - event.position.y) / dimension) as usize;
+        counts.counts[row.min(side - 1) * side + column.min(side - 1)] += 1;
+    }
+}
+/// Paints the counts into the overlay's texture every time it's shown, going from a faint blue for
+/// tiles that changed hands once to a solid red for the most contested ones.
+fn toggle_overlay(
+    keys: Res<ButtonInput<KeyCode>>,
+    counts: Res<FlipCounts>,
+    mut images: ResMut<Assets<Image>>,
+    mut overlay: Query<(&mut Visibility, &Handle<Image>), With<FrontlinesOverlay>>,
+) {
+    if !keys.just_pressed(FRONTLINES_KEY) {
+        return;
+    }
+    let Ok((mut visibility, handle)) = overlay.get_single_mut() else {
+        return;
+    };
+    if *visibility != Visibility::Hidden {
+        *visibility = Visibility::Hidden;
+        return;
+    }
+    let Some(image) = images.get_mut(handle) else {
+        return;
+    };
+    let side = counts.side.max(1) as u32;
+    image.resize(Extent3d {
+        width: side,
+        height: side,
+        depth_or_array_layers: 1,
+    });
+    image.data.fill(0);
+    let max = counts
+        .counts
+        .iter()
+        .copied()
+        .max()
+        .unwrap_or_default()
+        .max(1) as f32;
+    for (pixel, &count) in image.data.chunks_exact_mut(4).zip(&counts.counts) {
+        let heat = count as f32 / max;
+        let color = if count == 0 {
+            Color::NONE
+        } else {
+            Color::hsla(240.0 * (1.0 - heat), 1.0, 0.5, MAX_ALPHA * heat.sqrt())
+        };
+        pixel.copy_from_slice(&color.to_srgba().to_u8_array());
+    }
+    *visibility = Visibility::Inherited;
+}
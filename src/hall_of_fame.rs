@@ -0,0 +1,299 @@
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+};
+
+use crate::{
+    focus::Focusable,
+    results_db::{self, Champion},
+    state::AppState,
+    utils::{ParticipantMap, TileColor},
+};
+
+// Constants {{{
+
+const BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.85);
+const TITLE: &str = "Hall of Fame";
+const TITLE_FONT_SIZE: f32 = 64.0;
+const ENTRY_FONT_SIZE: f32 = 28.0;
+const ENTRY_WIDTH: f32 = 720.0;
+const ENTRY_HEIGHT: f32 = 72.0;
+const ENTRY_BACKGROUND: Color = Color::srgb(0.1, 0.1, 0.1);
+const GALLERY_HEIGHT: f32 = 560.0;
+const BUTTON_FONT_SIZE: f32 = 32.0;
+const BUTTON_WIDTH: f32 = 320.0;
+const BUTTON_HEIGHT: f32 = 60.0;
+const BACK_KEY: KeyCode = KeyCode::Escape;
+const NORMAL_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
+const HOVERED_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
+/// Pixels scrolled per line of mouse wheel movement.
+const SCROLL_LINE_HEIGHT: f32 = 40.0;
+/// Pixels scrolled per second while `PageUp` or `PageDown` is held.
+const SCROLL_SPEED: f32 = 600.0;
+
+// }}}
+
+/// Shows every match winner recorded in the results database, the latest first, as a gallery
+/// that scrolls with the mouse wheel or `PageUp` and `PageDown`.
+pub struct HallOfFamePlugin;
+impl Plugin for HallOfFamePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::HallOfFame), spawn_hall_of_fame)
+            .add_systems(OnExit(AppState::HallOfFame), despawn_hall_of_fame)
+            .add_systems(
+                Update,
+                (scroll_gallery, handle_back).run_if(in_state(AppState::HallOfFame)),
+            );
+    }
+}
+
+#[derive(Component, Clone, Copy)]
+struct HallOfFame;
+/// The column of champions, moved up inside the clipped node around it to scroll.
+#[derive(Component, Clone, Copy, Default)]
+struct Gallery {
+    offset: f32,
+}
+#[derive(Component, Clone, Copy)]
+struct BackButton;
+
+/// Days since the Unix epoch to a year, month and day, from Howard Hinnant's `civil_from_days`.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month as u32, day)
+}
+fn entry_text(champion: &Champion) -> String {
+    let (year, month, day) = civil_from_days((champion.finished_at / 86_400) as i64);
+    let seconds = champion.duration as u32;
+    let biggest_shot = champion
+        .biggest_shot
+        .map_or_else(|| "-".to_string(), |shot| shot.to_string());
+    // `Participant` writes its name straight out, ignoring the padding.
+    let name = champion.participant.to_string();
+    format!(
+        "{:<7} {:04}-{:02}-{:02}   {:02}:{:02}   biggest shot {}",
+        name,
+        year,
+        month,
+        day,
+        seconds / 60,
+        seconds % 60,
+        biggest_shot
+    )
+}
+fn text_style(font_size: f32) -> TextStyle {
+    TextStyle {
+        font: default(),
+        font_size,
+        color: Color::srgb(0.9, 0.9, 0.9),
+    }
+}
+fn spawn_hall_of_fame(mut commands: Commands, tile_colors: Res<ParticipantMap<TileColor>>) {
+    let champions = results_db::champions();
+    commands
+        .spawn((
+            Name::new("Hall of Fame"),
+            HallOfFame,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(16.0),
+                    ..default()
+                },
+                background_color: BACKGROUND.into(),
+                z_index: ZIndex::Global(2),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                TITLE,
+                TextStyle {
+                    font: default(),
+                    font_size: TITLE_FONT_SIZE,
+                    color: Color::WHITE,
+                },
+            ));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(ENTRY_WIDTH),
+                        max_height: Val::Px(GALLERY_HEIGHT),
+                        overflow: Overflow::clip_y(),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent
+                        .spawn((
+                            Gallery::default(),
+                            NodeBundle {
+                                style: Style {
+                                    width: Val::Percent(100.0),
+                                    flex_direction: FlexDirection::Column,
+                                    row_gap: Val::Px(8.0),
+                                    ..default()
+                                },
+                                ..default()
+                            },
+                        ))
+                        .with_children(|parent| match &champions {
+                            Ok(champions) if champions.is_empty() => {
+                                parent.spawn(TextBundle::from_section(
+                                    "No champions yet",
+                                    text_style(ENTRY_FONT_SIZE),
+                                ));
+                            }
+                            Ok(champions) => {
+                                for champion in champions {
+                                    spawn_entry(
+                                        parent,
+                                        champion,
+                                        tile_colors[champion.participant].0,
+                                    );
+                                }
+                            }
+                            Err(error) => {
+                                parent.spawn(TextBundle::from_section(
+                                    format!("Couldn't read the results: {}", error),
+                                    text_style(ENTRY_FONT_SIZE),
+                                ));
+                            }
+                        });
+                });
+            parent
+                .spawn((
+                    BackButton,
+                    Focusable,
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(BUTTON_WIDTH),
+                            height: Val::Px(BUTTON_HEIGHT),
+                            border: UiRect::all(Val::Px(5.0)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        border_color: BorderColor(Color::BLACK),
+                        border_radius: BorderRadius::MAX,
+                        background_color: NORMAL_BUTTON.into(),
+                        ..default()
+                    },
+                ))
+                .with_children(|button| {
+                    button.spawn(TextBundle::from_section(
+                        "Back",
+                        text_style(BUTTON_FONT_SIZE),
+                    ));
+                });
+        });
+}
+fn spawn_entry(parent: &mut ChildBuilder, champion: &Champion, color: Color) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                min_height: Val::Px(ENTRY_HEIGHT),
+                border: UiRect::left(Val::Px(ENTRY_HEIGHT / 3.0)),
+                padding: UiRect::horizontal(Val::Px(16.0)),
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            border_color: BorderColor(color),
+            background_color: ENTRY_BACKGROUND.into(),
+            ..default()
+        })
+        .with_children(|entry| {
+            entry.spawn(TextBundle::from_section(
+                entry_text(champion),
+                TextStyle {
+                    font: default(),
+                    font_size: ENTRY_FONT_SIZE,
+                    color,
+                },
+            ));
+        });
+}
+fn despawn_hall_of_fame(mut commands: Commands, hall_of_fame: Query<Entity, With<HallOfFame>>) {
+    for entity in &hall_of_fame {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+/// The virtual clock is paused in here, so holding a key scrolls by real time.
+fn scroll_gallery(
+    mut wheel_events: EventReader<MouseWheel>,
+    keys: Res<ButtonInput<KeyCode>>,
+    time: Res<Time<Real>>,
+    mut galleries: Query<(&mut Gallery, &mut Style, &Node, &Parent)>,
+    nodes: Query<&Node>,
+) {
+    let mut scroll = 0.0;
+    for event in wheel_events.read() {
+        scroll += match event.unit {
+            MouseScrollUnit::Line => event.y * SCROLL_LINE_HEIGHT,
+            MouseScrollUnit::Pixel => event.y,
+        };
+    }
+    if keys.pressed(KeyCode::PageUp) {
+        scroll += SCROLL_SPEED * time.delta_seconds();
+    }
+    if keys.pressed(KeyCode::PageDown) {
+        scroll -= SCROLL_SPEED * time.delta_seconds();
+    }
+    for (mut gallery, mut style, node, parent) in &mut galleries {
+        let Ok(container) = nodes.get(parent.get()) else {
+            continue;
+        };
+        let max_scroll = (node.size().y - container.size().y).max(0.0);
+        let offset = (gallery.offset + scroll).clamp(-max_scroll, 0.0);
+        if offset != gallery.offset {
+            gallery.offset = offset;
+            style.top = Val::Px(offset);
+        }
+    }
+}
+fn handle_back(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut buttons: Query<
+        (&Interaction, &mut BackgroundColor, &mut BorderColor),
+        (Changed<Interaction>, With<BackButton>),
+    >,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let mut back = keys.just_pressed(BACK_KEY);
+    for (&interaction, mut color, mut border_color) in &mut buttons {
+        match interaction {
+            Interaction::Pressed | Interaction::Hovered => {
+                *color = HOVERED_BUTTON.into();
+                border_color.0 = Color::WHITE;
+            }
+            Interaction::None => {
+                *color = NORMAL_BUTTON.into();
+                border_color.0 = Color::BLACK;
+            }
+        }
+        back |= interaction == Interaction::Pressed;
+    }
+    if back {
+        next_state.set(AppState::MainMenu);
+    }
+}
@@ -0,0 +1,188 @@
+use std::time::Duration;
+
+use bevy::{
+    app::{Plugins, PluginsState},
+    prelude::*,
+    state::app::StatesPlugin,
+    time::TimeUpdateStrategy,
+};
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    battlefield::{BattlefieldPlugin, MatchOutcome, TileCounts},
+    config::{DisplayConfig, GameConfig, Tuning},
+    panel_plugin::PanelPlugin,
+    state::{AppState, AppStatePlugin},
+    stats::{MatchStats, MatchStatsPlugin},
+    utils::{Participant, ParticipantMap, UtilsPlugin},
+};
+
+// Constants {{{
+
+/// The amount of simulated time that passes with every update of a headless app.
+pub const HEADLESS_TIMESTEP: f32 = 1.0 / 60.0;
+/// Matches that haven't ended after this many seconds of simulated time are given up on.
+const MAX_MATCH_DURATION: f32 = 1200.0;
+const USAGE: &str = "Usage: multiply_or_release headless <match count> [first seed]";
+
+// }}}
+
+/// Advances time by [`HEADLESS_TIMESTEP`] every update, and has the physics take exactly one step
+/// of that length, so nothing depends on how long the updates actually took.
+pub struct LockstepPlugin;
+impl Plugin for LockstepPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+            HEADLESS_TIMESTEP,
+        )))
+        .add_systems(Startup, setup_fixed_timestep);
+    }
+}
+/// Builds an app that runs the battlefield and the panels without a window, rendering, audio, or
+/// particle effects. Every call to [`App::update`] advances the simulation by
+/// [`HEADLESS_TIMESTEP`], regardless of how long the update actually took.
+pub fn headless_app<M>(config: GameConfig, tuning: Tuning, plugins: impl Plugins<M>) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        TransformPlugin,
+        HierarchyPlugin,
+    ))
+    .init_asset::<Mesh>()
+    .init_asset::<ColorMaterial>()
+    .insert_resource(config)
+    .insert_resource(tuning)
+    .init_resource::<DisplayConfig>()
+    .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+    .add_plugins((
+        StatesPlugin,
+        AppStatePlugin {
+            initial_state: AppState::Playing,
+        },
+    ))
+    .add_plugins((UtilsPlugin, PanelPlugin, BattlefieldPlugin, LockstepPlugin))
+    .add_plugins(plugins);
+    while app.plugins_state() == PluginsState::Adding {
+        bevy::tasks::tick_global_task_pools_on_main_thread();
+    }
+    app.finish();
+    app.cleanup();
+    app
+}
+fn setup_fixed_timestep(mut rapier_config: ResMut<RapierConfiguration>) {
+    rapier_config.timestep_mode = TimestepMode::Fixed {
+        dt: HEADLESS_TIMESTEP,
+        substeps: 1,
+    };
+}
+/// Parses the arguments of the `headless` subcommand into the match count and the first seed, or
+/// prints the usage and exits if they aren't valid.
+pub fn parse_args(args: &[String]) -> (u64, u64) {
+    let parse = |arg: Option<&String>, default: Option<u64>| match arg {
+        Some(arg) => arg.parse().ok(),
+        None => default,
+    };
+    let (Some(match_count), Some(first_seed)) =
+        (parse(args.first(), None), parse(args.get(1), Some(0)))
+    else {
+        eprintln!("{}", USAGE);
+        std::process::exit(2);
+    };
+    (match_count, first_seed)
+}
+/// Runs the `headless` subcommand: `match_count` matches with consecutive seeds and the rules and
+/// balance numbers the game would have started with, each simulated as fast as it can be. A CSV
+/// row with the winner and stats of each match goes to stdout as soon as it's over, and a summary
+/// of them all to stderr at the end.
+pub fn run(match_count: u64, first_seed: u64, config: &GameConfig, tuning: &Tuning) {
+    let max_frames = (MAX_MATCH_DURATION / HEADLESS_TIMESTEP) as u64;
+    let mut header = vec![
+        "seed".to_string(),
+        "winner".to_string(),
+        "duration".to_string(),
+    ];
+    for participant in Participant::ALL {
+        let name = participant.to_string().to_lowercase();
+        for stat in ["tiles", "trigger_hits", "damage_dealt", "eliminated_at"] {
+            header.push(format!("{}_{}", name, stat));
+        }
+    }
+    println!("{}", header.join(","));
+    let mut wins = ParticipantMap::<u64>::default();
+    let mut draws = 0;
+    let mut unfinished = 0;
+    let mut total_duration = 0.0;
+    for seed in first_seed..first_seed + match_count {
+        let config = GameConfig {
+            seed: Some(seed),
+            ..config.clone()
+        };
+        let mut app = headless_app(config, tuning.clone(), MatchStatsPlugin);
+        let mut frames = 0;
+        while frames < max_frames
+            && *app.world().resource::<State<AppState>>().get() != AppState::GameOver
+        {
+            app.update();
+            frames += 1;
+        }
+        let world = app.world();
+        let stats = world.resource::<MatchStats>();
+        let tile_counts = &world.resource::<TileCounts>().0;
+        let winner = match *world.resource::<MatchOutcome>() {
+            MatchOutcome::Undecided => {
+                unfinished += 1;
+                "unfinished".to_string()
+            }
+            MatchOutcome::Draw => {
+                draws += 1;
+                total_duration += stats.elapsed;
+                "draw".to_string()
+            }
+            MatchOutcome::Winner(winner) => {
+                wins[winner] += 1;
+                total_duration += stats.elapsed;
+                winner.to_string().to_lowercase()
+            }
+        };
+        let mut row = vec![seed.to_string(), winner, format!("{:.2}", stats.elapsed)];
+        for participant in Participant::ALL {
+            row.push(tile_counts[participant].to_string());
+            row.push(stats.trigger_hits[participant].to_string());
+            row.push(stats.damage_dealt[participant].to_string());
+            row.push(
+                stats.eliminated_at[participant]
+                    .map_or_else(String::new, |time| format!("{:.2}", time)),
+            );
+        }
+        println!("{}", row.join(","));
+    }
+
+    let finished = match_count - unfinished;
+    eprintln!(
+        "Matches: {} ({} finished, {} unfinished)",
+        match_count, finished, unfinished
+    );
+    if finished == 0 {
+        return;
+    }
+    eprintln!(
+        "Average match length: {:.1}s",
+        total_duration / finished as f32
+    );
+    for participant in Participant::ALL {
+        let name = participant.to_string();
+        eprintln!(
+            "  {:<7} {:>7} wins ({:.1}%)",
+            name,
+            wins[participant],
+            wins[participant] as f32 / finished as f32 * 100.0
+        );
+    }
+    eprintln!(
+        "  {:<7} {:>7} ({:.1}%)",
+        "Draws",
+        draws,
+        draws as f32 / finished as f32 * 100.0
+    );
+}
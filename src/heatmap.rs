@@ -0,0 +1,111 @@
+use bevy::{prelude::*, render::view::RenderLayers, utils::HashMap};
+
+use crate::{
+    cameras::{LEFT_PANEL_LAYER, RIGHT_PANEL_LAYER},
+    panel_plugin::{PanelRoot, WorkerBall, ARENA_WIDTH, TRIGGER_LINE_Y},
+};
+
+// Constants {{{
+
+const HEATMAP_KEY: KeyCode = KeyCode::KeyT;
+/// Six bins to each of the five trigger zones, so the bins line up with the zones.
+const BIN_COUNT: usize = 30;
+const BIN_WIDTH: f32 = ARENA_WIDTH / BIN_COUNT as f32;
+/// How high the bars for the most crossed bin go above the trigger line.
+const MAX_BAR_HEIGHT: f32 = 120.0;
+const HEATMAP_ALPHA: f32 = 0.8;
+
+// }}}
+
+/// An analyst overlay, toggled with T, showing where worker balls have crossed into the trigger
+/// zones since the game started. Used to check that the pegs send marbles to each zone as often as
+/// intended.
+pub struct TriggerHeatmapPlugin;
+impl Plugin for TriggerHeatmapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_gizmo_group::<HeatmapGizmos>()
+            .init_resource::<TriggerHeatmap>()
+            .add_systems(Startup, setup_heatmap_gizmos)
+            .add_systems(
+                Update,
+                (
+                    toggle_heatmap,
+                    record_crossings,
+                    draw_heatmap.run_if(heatmap_enabled),
+                ),
+            );
+    }
+}
+
+#[derive(Default, Reflect, GizmoConfigGroup)]
+struct HeatmapGizmos;
+/// Kept across matches, so the counts keep getting more reliable the longer the game runs.
+#[derive(Resource, Default)]
+struct TriggerHeatmap {
+    /// The crossings in each bin, from left to right, for each panel root.
+    bins: HashMap<Entity, [u32; BIN_COUNT]>,
+    /// How high up each worker ball was, relative to its panel, last frame.
+    last_heights: HashMap<Entity, f32>,
+}
+
+/// The overlay is drawn over the panels, which would cover it up if it were drawn on the
+/// battlefield's layer.
+fn setup_heatmap_gizmos(mut config_store: ResMut<GizmoConfigStore>) {
+    let (config, _) = config_store.config_mut::<HeatmapGizmos>();
+    config.enabled = false;
+    config.render_layers = RenderLayers::from_layers(&[LEFT_PANEL_LAYER, RIGHT_PANEL_LAYER]);
+}
+fn toggle_heatmap(keys: Res<ButtonInput<KeyCode>>, mut config_store: ResMut<GizmoConfigStore>) {
+    if keys.just_pressed(HEATMAP_KEY) {
+        let (config, _) = config_store.config_mut::<HeatmapGizmos>();
+        config.enabled = !config.enabled;
+    }
+}
+fn heatmap_enabled(config_store: Res<GizmoConfigStore>) -> bool {
+    config_store.config::<HeatmapGizmos>().0.enabled
+}
+fn record_crossings(
+    mut heatmap: ResMut<TriggerHeatmap>,
+    balls: Query<(Entity, &Transform, &Parent), With<WorkerBall>>,
+    mut removed: RemovedComponents<WorkerBall>,
+) {
+    let heatmap = heatmap.as_mut();
+    for entity in removed.read() {
+        heatmap.last_heights.remove(&entity);
+    }
+    for (entity, transform, parent) in &balls {
+        let position = transform.translation.xy();
+        let last_height = heatmap.last_heights.insert(entity, position.y);
+        if last_height.is_some_and(|y| y > TRIGGER_LINE_Y) && position.y <= TRIGGER_LINE_Y {
+            let bin = ((position.x + ARENA_WIDTH / 2.0) / BIN_WIDTH) as usize;
+            heatmap.bins.entry(parent.get()).or_insert([0; BIN_COUNT])[bin.min(BIN_COUNT - 1)] += 1;
+        }
+    }
+}
+/// A bar for each bin, as tall as its share of the busiest bin, going from blue for the quiet bins
+/// to red for the busy ones.
+fn draw_heatmap(
+    mut gizmos: Gizmos<HeatmapGizmos>,
+    heatmap: Res<TriggerHeatmap>,
+    roots: Query<&GlobalTransform, With<PanelRoot>>,
+) {
+    for (&root, bins) in &heatmap.bins {
+        let Ok(root_transform) = roots.get(root) else {
+            continue;
+        };
+        let origin =
+            root_transform.translation().xy() + Vec2::new(-ARENA_WIDTH / 2.0, TRIGGER_LINE_Y);
+        let max = bins.iter().copied().max().unwrap_or_default().max(1) as f32;
+        for (i, &count) in bins.iter().enumerate() {
+            let heat = count as f32 / max;
+            let height = heat * MAX_BAR_HEIGHT;
+            let center = origin + Vec2::new((i as f32 + 0.5) * BIN_WIDTH, height / 2.0);
+            gizmos.rect_2d(
+                center,
+                0.0,
+                Vec2::new(BIN_WIDTH, height),
+                Color::hsla(240.0 * (1.0 - heat), 1.0, 0.5, HEATMAP_ALPHA),
+            );
+        }
+    }
+}
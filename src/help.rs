@@ -0,0 +1,227 @@
+use bevy::prelude::*;
+
+use crate::{
+    panel_plugin::{TRIGGER_ZONE_COLOR_0, TRIGGER_ZONE_COLOR_1, TRIGGER_ZONE_COLOR_2},
+    utils::{BallColor, Participant, ParticipantMap, TileColor},
+};
+
+// Constants {{{
+
+const HELP_KEY: KeyCode = KeyCode::KeyH;
+
+const HELP_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.85);
+const HELP_TITLE_FONT_SIZE: f32 = 48.0;
+const HELP_HEADING_FONT_SIZE: f32 = 28.0;
+const HELP_BODY_FONT_SIZE: f32 = 20.0;
+const HELP_WIDTH: f32 = 900.0;
+/// The width of the column the diagrams are drawn in.
+const DIAGRAM_WIDTH: f32 = 180.0;
+const DIAGRAM_UNIT: f32 = 20.0;
+
+// }}}
+
+/// An overlay explaining the rules of the simulation, toggled with H.
+pub struct HelpPlugin;
+impl Plugin for HelpPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_help)
+            .add_systems(Update, toggle_help);
+    }
+}
+
+#[derive(Component, Clone, Copy)]
+struct HelpOverlay;
+
+fn toggle_help(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut overlay: Query<&mut Visibility, With<HelpOverlay>>,
+) {
+    if !keys.just_pressed(HELP_KEY) {
+        return;
+    }
+    for mut visibility in &mut overlay {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Inherited,
+            _ => Visibility::Hidden,
+        };
+    }
+}
+fn text(value: &str, font_size: f32) -> TextBundle {
+    TextBundle::from_section(
+        value,
+        TextStyle {
+            font: default(),
+            font_size,
+            color: Color::WHITE,
+        },
+    )
+}
+/// A small colored box for the diagrams, optionally round and labeled.
+fn shape(parent: &mut ChildBuilder, color: Color, size: Vec2, round: bool, label: &str) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Px(size.x),
+                height: Val::Px(size.y),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: color.into(),
+            border_radius: if round {
+                BorderRadius::MAX
+            } else {
+                BorderRadius::ZERO
+            },
+            ..default()
+        })
+        .with_children(|shape| {
+            if !label.is_empty() {
+                shape.spawn(TextBundle::from_section(
+                    label,
+                    TextStyle {
+                        font: default(),
+                        font_size: DIAGRAM_UNIT * 0.6,
+                        color: Color::BLACK,
+                    },
+                ));
+            }
+        });
+}
+/// A row with a diagram on the left and the explanation next to it.
+fn section(
+    parent: &mut ChildBuilder,
+    heading: &str,
+    body: &str,
+    diagram: impl FnOnce(&mut ChildBuilder),
+) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                column_gap: Val::Px(24.0),
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|row| {
+            row.spawn(NodeBundle {
+                style: Style {
+                    width: Val::Px(DIAGRAM_WIDTH),
+                    flex_shrink: 0.0,
+                    column_gap: Val::Px(DIAGRAM_UNIT / 4.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(diagram);
+            row.spawn(NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(4.0),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|column| {
+                column.spawn(text(heading, HELP_HEADING_FONT_SIZE));
+                column.spawn(text(body, HELP_BODY_FONT_SIZE));
+            });
+        });
+}
+fn setup_help(
+    mut commands: Commands,
+    ball_colors: Res<ParticipantMap<BallColor>>,
+    tile_colors: Res<ParticipantMap<TileColor>>,
+) {
+    let unit = Vec2::splat(DIAGRAM_UNIT);
+    let zone = Vec2::new(DIAGRAM_UNIT * 1.6, DIAGRAM_UNIT);
+    let a = Participant::A;
+    let b = Participant::B;
+    commands
+        .spawn((
+            Name::new("Help Overlay"),
+            HelpOverlay,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: HELP_BACKGROUND.into(),
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Global(1),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(HELP_WIDTH),
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(20.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(text("How It Works", HELP_TITLE_FONT_SIZE));
+                    section(
+                        parent,
+                        "The Panels",
+                        "Nobody is playing: marbles drop through the pins in the side panels, and \
+                        each one belongs to the turret of the same color.",
+                        |diagram| {
+                            shape(diagram, ball_colors[a].0, unit, true, "");
+                            shape(diagram, ball_colors[b].0, unit, true, "");
+                        },
+                    );
+                    section(
+                        parent,
+                        "Trigger Zones",
+                        "Where a marble lands decides what its turret does: multiply its charge \
+                        by 2 or 4, fire it all as one big charged shot, or release it as a burst \
+                        of small shots.",
+                        |diagram| {
+                            shape(diagram, TRIGGER_ZONE_COLOR_2, zone, false, "burst");
+                            shape(diagram, TRIGGER_ZONE_COLOR_1, zone, false, "x2");
+                            shape(diagram, TRIGGER_ZONE_COLOR_0, zone, false, "x4");
+                            shape(diagram, TRIGGER_ZONE_COLOR_1, zone, false, "x2");
+                            shape(diagram, TRIGGER_ZONE_COLOR_2, zone, false, "big");
+                        },
+                    );
+                    section(
+                        parent,
+                        "Capturing Tiles",
+                        "Each enemy tile a shot runs into turns to the shooter's color and costs \
+                        the shot one charge. The shot is gone once it runs out.",
+                        |diagram| {
+                            shape(diagram, ball_colors[a].0, unit, true, "3");
+                            shape(diagram, tile_colors[a].0, unit, false, "");
+                            shape(diagram, tile_colors[b].0, unit, false, "");
+                        },
+                    );
+                    section(
+                        parent,
+                        "Charge Duels and Eliminations",
+                        "When a shot hits an enemy turret, both lose the smaller of their two \
+                        charges. A turret left with no charge is eliminated, and the last one \
+                        standing wins.",
+                        |diagram| {
+                            shape(diagram, ball_colors[a].0, unit * 1.5, true, "8");
+                            shape(diagram, ball_colors[b].0, unit * 2.0, true, "5");
+                        },
+                    );
+                    parent.spawn(text(
+                        "Esc: pause    Space: quick pause    R: restart    1-5: speed    0: auto speed    T: trigger heatmap    P: trajectories    F: frontlines    H: close this help",
+                        HELP_BODY_FONT_SIZE,
+                    ));
+                });
+        });
+}
@@ -0,0 +1,106 @@
+use bevy::{
+    ecs::archetype::Archetype,
+    prelude::*,
+    utils::{get_short_name, HashMap},
+};
+
+use crate::battlefield::RestartEvent;
+
+// Constants {{{
+
+/// Frames to wait after a restart before counting, for the restart systems that only see the event
+/// the frame after it's sent, and for their commands to be applied.
+const SETTLE_FRAMES: u32 = 3;
+
+// }}}
+
+/// Counts the entities of every archetype once the game has started, and again after every
+/// restart, and warns about any archetype that has more entities than after the last count, or
+/// any child whose parent is gone. Restarting rebuilds a lot of hierarchies by hand, so charge
+/// balls, particle emitters and platform children are easy to leave behind.
+pub struct LeakDetectorPlugin;
+impl Plugin for LeakDetectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(LeakCheck {
+            frames_left: Some(SETTLE_FRAMES),
+            counts: HashMap::new(),
+        })
+        .add_systems(
+            Update,
+            schedule_leak_check.run_if(on_event::<RestartEvent>()),
+        )
+        .add_systems(Last, check_for_leaks);
+    }
+}
+
+#[derive(Resource)]
+struct LeakCheck {
+    /// Frames until the next count, if one is due.
+    frames_left: Option<u32>,
+    /// The number of entities in each archetype at the last count, keyed by the names of its
+    /// components.
+    counts: HashMap<String, usize>,
+}
+
+fn archetype_name(world: &World, archetype: &Archetype) -> String {
+    let mut names = archetype
+        .components()
+        .filter_map(|id| world.components().get_name(id))
+        .map(get_short_name)
+        .collect::<Vec<_>>();
+    names.sort();
+    names.join(", ")
+}
+fn schedule_leak_check(mut events: EventReader<RestartEvent>, mut check: ResMut<LeakCheck>) {
+    events.clear();
+    check.frames_left = Some(SETTLE_FRAMES);
+}
+fn check_for_leaks(world: &mut World) {
+    let mut check = world.resource_mut::<LeakCheck>();
+    match check.frames_left {
+        Some(0) => check.frames_left = None,
+        Some(ref mut frames_left) => {
+            *frames_left -= 1;
+            return;
+        }
+        None => return,
+    }
+    let mut counts = HashMap::<String, usize>::new();
+    for archetype in world
+        .archetypes()
+        .iter()
+        .filter(|archetype| !archetype.is_empty())
+    {
+        *counts.entry(archetype_name(world, archetype)).or_default() += archetype.len();
+    }
+    let check = world.resource::<LeakCheck>();
+    // The first count only sets what the following ones are held to.
+    if !check.counts.is_empty() {
+        for (name, &count) in &counts {
+            let before = check.counts.get(name).copied().unwrap_or_default();
+            if count > before {
+                warn!(
+                    "{} more entities with [{}] than after the last restart",
+                    count - before,
+                    name
+                );
+            }
+        }
+    }
+    let mut children = world.query::<(Entity, &Parent)>();
+    let orphans = children
+        .iter(world)
+        .filter(|(_, parent)| world.get_entity(parent.get()).is_none())
+        .map(|(entity, _)| entity)
+        .collect::<Vec<_>>();
+    for orphan in orphans {
+        let name = world
+            .entities()
+            .get(orphan)
+            .and_then(|location| world.archetypes().get(location.archetype_id))
+            .map(|archetype| archetype_name(world, archetype))
+            .unwrap_or_default();
+        warn!("{} outlived its parent, with [{}]", orphan, name);
+    }
+    world.resource_mut::<LeakCheck>().counts = counts;
+}
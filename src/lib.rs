@@ -0,0 +1,79 @@
+//! The simulation behind Multiply or Release as Bevy plugins, for embedding it in other apps.
+//!
+//! [`BattlefieldPlugin`] and [`PanelPlugin`] run a match. They need [`GameConfig`] and
+//! [`DisplayConfig`] inserted, Rapier's physics plugin, and [`AppStatePlugin`] to go through the
+//! match with. They use the [`Tuning`] inserted before them, or the default one.
+//! [`headless::headless_app`] builds the smallest app that runs them, without a window. The game
+//! adds [`UIPlugin`] and the other plugins of these modules on top.
+
+#[cfg(not(target_arch = "wasm32"))]
+pub mod achievements;
+pub mod audio;
+pub mod audit;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod autosave;
+pub mod battlefield;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod bridge;
+pub mod cameras;
+pub mod charge_history;
+pub mod charge_transfer;
+pub mod cli;
+pub mod collision_groups;
+pub mod config;
+pub mod debug_utils;
+pub mod duel;
+pub mod event_log;
+pub mod focus;
+pub mod frontlines;
+#[cfg(feature = "sqlite")]
+pub mod hall_of_fame;
+pub mod headless;
+pub mod heatmap;
+pub mod help;
+#[cfg(debug_assertions)]
+pub mod leaks;
+pub mod main_menu;
+pub mod match_clock;
+pub mod match_code;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod metrics;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod montage;
+pub mod music;
+pub mod mutators;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod obs;
+pub mod panel_plugin;
+pub mod participant_cards;
+pub mod pause_menu;
+pub mod platform;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod predictions;
+pub mod presets;
+pub mod pressure_hud;
+pub mod quadrant_tint;
+pub mod restart;
+#[cfg(feature = "sqlite")]
+pub mod results_db;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod smoke;
+pub mod speed;
+pub mod state;
+pub mod stats;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod stress;
+pub mod territory;
+pub mod threats;
+pub mod timeline;
+pub mod trajectory;
+pub mod tutorial;
+pub mod ui;
+pub mod utils;
+
+pub use battlefield::BattlefieldPlugin;
+pub use config::{DisplayConfig, GameConfig, Tuning};
+pub use panel_plugin::PanelPlugin;
+pub use state::{AppState, AppStatePlugin};
+pub use ui::UIPlugin;
+pub use utils::UtilsPlugin;
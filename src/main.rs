@@ -1,53 +1,337 @@
-use battlefield::BattlefieldPlugin;
-use bevy::{prelude::*, render::camera::ScalingMode};
+use bevy::{log::LogPlugin, prelude::*};
 use bevy_hanabi::prelude::*;
 use bevy_rapier2d::prelude::*;
-use panel_plugin::PanelPlugin;
-use ui::UIPlugin;
-use utils::{Participant, UtilsPlugin};
-
-mod battlefield;
-mod collision_groups;
-mod debug_utils;
-mod panel_plugin;
-mod ui;
-mod utils;
+#[cfg(debug_assertions)]
+use multiply_or_release::leaks;
+#[cfg(not(target_arch = "wasm32"))]
+use multiply_or_release::{
+    achievements, autosave, bridge, metrics, montage, obs, predictions, smoke, stress,
+};
+use multiply_or_release::{
+    audio::{GameAudioPlugin, SoundVolumes},
+    audit,
+    battlefield::BattlefieldPlugin,
+    cameras::CamerasPlugin,
+    charge_history::ChargeHistoryPlugin,
+    charge_transfer::ChargeTransferPlugin,
+    cli::LaunchOptions,
+    config::{DisplayConfig, GameConfig, ScreenLayout, Tuning},
+    duel::FinalDuelPlugin,
+    event_log::{self, EventLogPlugin},
+    focus::FocusNavigationPlugin,
+    frontlines::FrontlinesPlugin,
+    headless::{self, LockstepPlugin},
+    heatmap::TriggerHeatmapPlugin,
+    help::HelpPlugin,
+    main_menu::MainMenuPlugin,
+    match_clock::MatchClockPlugin,
+    match_code,
+    music::MusicPlugin,
+    mutators::MutatorsPlugin,
+    panel_plugin::PanelPlugin,
+    participant_cards::ParticipantCardsPlugin,
+    pause_menu::PauseMenuPlugin,
+    platform,
+    presets::Presets,
+    pressure_hud::BulletPressurePlugin,
+    quadrant_tint::QuadrantTintPlugin,
+    restart::RestartPlugin,
+    speed::SimulationSpeedPlugin,
+    state::{AppState, AppStatePlugin},
+    stats::MatchStatsPlugin,
+    territory::TerritoryRingPlugin,
+    threats::ThreatIndicatorPlugin,
+    timeline::TerritoryTimelinePlugin,
+    trajectory::TrajectoryPreviewPlugin,
+    tutorial,
+    ui::UIPlugin,
+    utils::{Participant, UtilsPlugin},
+};
+#[cfg(feature = "sqlite")]
+use multiply_or_release::{hall_of_fame::HallOfFamePlugin, results_db};
 
 const WINDOW_TITLE: &str = "Multiply or Release";
+/// The environment variable holding the UI scale multiplier.
+const UI_SCALE_VAR: &str = "UI_SCALE";
+/// The environment variable holding the screen layout, `landscape` or `portrait`.
+const LAYOUT_VAR: &str = "LAYOUT";
+/// The environment variable holding the volume of each kind of sound, like `triggers=0.5`.
+const SOUND_VOLUME_VAR: &str = "SOUND_VOLUME";
+/// Set this environment variable to play in potato mode.
+const LOW_SPEC_VAR: &str = "LOW_SPEC";
+/// Set this environment variable to turn on deterministic mode.
+const DETERMINISTIC_VAR: &str = "DETERMINISTIC";
+/// Set this environment variable to start with the guided tutorial.
+const TUTORIAL_VAR: &str = "TUTORIAL";
+/// Set this environment variable to turn off bloom.
+const NO_BLOOM_VAR: &str = "NO_BLOOM";
+/// The environment variable holding the lowest charge level of charged shots that leave a trail.
+const BULLET_TRAIL_LEVEL_VAR: &str = "BULLET_TRAIL_LEVEL";
+/// The environment variable holding the smallest font size the charges in balls are written out
+/// in full.
+const READABLE_TEXT_SIZE_VAR: &str = "READABLE_TEXT_SIZE";
+/// Set this environment variable to put a shadow behind the charges in balls.
+const TEXT_SHADOW_VAR: &str = "TEXT_SHADOW";
+/// The environment variable holding the name of the preset to start with.
+const PRESET_VAR: &str = "PRESET";
+/// The environment variable holding a match code to replay.
+const MATCH_CODE_VAR: &str = "MATCH_CODE";
+/// The environment variable holding the path of the file with the balance numbers.
+const TUNING_VAR: &str = "TUNING";
+const DEFAULT_TUNING_PATH: &str = "tuning.ron";
+const PORTRAIT_WINDOW_SIZE: Vec2 = Vec2::new(540.0, 960.0);
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    #[cfg(not(target_arch = "wasm32"))]
+    let mut montage_clips = None;
+    #[cfg(not(target_arch = "wasm32"))]
+    let mut stress_test = None;
+    #[cfg(not(target_arch = "wasm32"))]
+    let mut smoke_test = false;
+    let mut headless_matches = None;
+    let mut launch_options = LaunchOptions::default();
+    match args.first().map(String::as_str) {
+        Some("audit") => {
+            audit::run_cli(&args[1..]);
+            return;
+        }
+        Some("headless") => headless_matches = Some(headless::parse_args(&args[1..])),
+        #[cfg(not(target_arch = "wasm32"))]
+        Some("montage") => montage_clips = Some(montage::run_cli(&args[1..])),
+        #[cfg(not(target_arch = "wasm32"))]
+        Some("stress") => stress_test = Some(stress::run_cli(&args[1..])),
+        #[cfg(not(target_arch = "wasm32"))]
+        Some("smoke") => smoke_test = true,
+        #[cfg(feature = "sqlite")]
+        Some("results") => {
+            results_db::run_cli();
+            return;
+        }
+        _ => launch_options = LaunchOptions::parse(&args),
+    }
+    let mut config = GameConfig {
+        low_spec: std::env::var_os(LOW_SPEC_VAR).is_some(),
+        ..default()
+    };
+    if std::env::var_os(DETERMINISTIC_VAR).is_some() {
+        config.deterministic = true;
+    }
+    let presets = Presets::load();
+    if let Ok(name) = std::env::var(PRESET_VAR) {
+        match presets.find(&name) {
+            Some(preset) => config = preset.apply(&config),
+            None => eprintln!("Invalid {}: no preset named `{}`", PRESET_VAR, name),
+        }
+    }
+    if let Ok(code) = std::env::var(MATCH_CODE_VAR) {
+        match match_code::decode(&code) {
+            Ok(code_config) => config = code_config,
+            Err(error) => eprintln!("Invalid {}: {}", MATCH_CODE_VAR, error),
+        }
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(clips) = &montage_clips {
+        config.seed = Some(clips[0].seed);
+        config.deterministic = true;
+    }
+    launch_options.apply(&mut config);
+    let tuning_path = std::env::var(TUNING_VAR).unwrap_or_else(|_| DEFAULT_TUNING_PATH.into());
+    let tuning = Tuning::load(&tuning_path).unwrap_or_else(|error| {
+        eprintln!("Invalid tuning file {}: {}", tuning_path, error);
+        Tuning::default()
+    });
+    let problems = [config.validate(), tuning.validate()]
+        .into_iter()
+        .filter_map(Result::err)
+        .flatten()
+        .collect::<Vec<_>>();
+    if !problems.is_empty() {
+        eprintln!("The game can't start with these options:");
+        for problem in problems {
+            eprintln!("- {}", problem);
+        }
+        std::process::exit(2);
+    }
+    if let Some((match_count, first_seed)) = headless_matches {
+        headless::run(match_count, first_seed, &config, &tuning);
+        return;
+    }
+    let mut display_config = DisplayConfig {
+        bloom: std::env::var_os(NO_BLOOM_VAR).is_none(),
+        charge_text_shadow: std::env::var_os(TEXT_SHADOW_VAR).is_some(),
+        ..default()
+    };
+    if let Ok(scale) = std::env::var(UI_SCALE_VAR) {
+        match scale.parse::<f32>() {
+            Ok(scale) if scale.is_finite() && scale > 0.0 => display_config.ui_scale = scale,
+            Ok(scale) => eprintln!("Invalid {}: {} isn't above 0", UI_SCALE_VAR, scale),
+            Err(error) => eprintln!("Invalid {}: {}", UI_SCALE_VAR, error),
+        }
+    }
+    if let Ok(level) = std::env::var(BULLET_TRAIL_LEVEL_VAR) {
+        match level.parse() {
+            Ok(level) => display_config.bullet_trail_level = level,
+            Err(error) => eprintln!("Invalid {}: {}", BULLET_TRAIL_LEVEL_VAR, error),
+        }
+    }
+    if let Ok(size) = std::env::var(READABLE_TEXT_SIZE_VAR) {
+        match size.parse::<f32>() {
+            Ok(size) if size.is_finite() && size > 0.0 => {
+                display_config.readable_charge_text_size = size;
+            }
+            Ok(size) => eprintln!("Invalid {}: {} isn't above 0", READABLE_TEXT_SIZE_VAR, size),
+            Err(error) => eprintln!("Invalid {}: {}", READABLE_TEXT_SIZE_VAR, error),
+        }
+    }
+    if let Ok(layout) = std::env::var(LAYOUT_VAR) {
+        match layout.parse() {
+            Ok(layout) => display_config.layout = layout,
+            Err(error) => eprintln!("Invalid {}: {}", LAYOUT_VAR, error),
+        }
+    }
+    let mut sound_volumes = SoundVolumes::default();
+    if let Ok(volumes) = std::env::var(SOUND_VOLUME_VAR) {
+        match volumes.parse() {
+            Ok(volumes) => sound_volumes = volumes,
+            Err(error) => eprintln!("Invalid {}: {}", SOUND_VOLUME_VAR, error),
+        }
+    }
+    let mut window = Window {
+        title: WINDOW_TITLE.to_string(),
+        mode: launch_options
+            .window_mode
+            .unwrap_or(bevy::window::WindowMode::Windowed),
+        // Only does anything in browsers, where the canvas should follow the size of the page.
+        fit_canvas_to_parent: true,
+        ..default()
+    };
+    if display_config.layout == ScreenLayout::Portrait {
+        window.resolution = PORTRAIT_WINDOW_SIZE.into();
+    }
     let window_plugin = WindowPlugin {
-        primary_window: Some(Window {
-            title: WINDOW_TITLE.to_string(),
-            mode: bevy::window::WindowMode::Windowed,
-            ..default()
-        }),
+        primary_window: Some(window),
+        ..default()
+    };
+    let mut log_plugin = LogPlugin {
+        custom_layer: event_log::json_file_layer,
         ..default()
     };
-    App::new()
-        .add_plugins(DefaultPlugins.set(window_plugin))
+    if std::env::var_os(event_log::EVENT_LOG_VAR).is_some() {
+        // The filter applies to every layer, so the console gets the debug level events too.
+        log_plugin.filter += &format!(",{}=debug", event_log::GAME_EVENTS_TARGET);
+    }
+    let deterministic = config.deterministic;
+    let tutorial = std::env::var_os(TUTORIAL_VAR).is_some();
+    // Recordings, the stress test and the tutorial go straight into the match.
+    let skip_menu = tutorial;
+    #[cfg(not(target_arch = "wasm32"))]
+    let skip_menu = skip_menu || montage_clips.is_some() || stress_test.is_some();
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins.set(window_plugin).set(log_plugin))
+        .insert_resource(config)
+        .insert_resource(display_config)
+        .insert_resource(tuning)
+        .insert_resource(presets)
+        .insert_resource(sound_volumes)
         .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
-        .add_plugins(HanabiPlugin)
-        .add_plugins((UtilsPlugin, PanelPlugin, BattlefieldPlugin, UIPlugin))
-        // .add_plugins(debug_utils::DebugUtilsPlugin)
-        .add_systems(Startup, setup)
-        .run();
-}
-
-fn setup(mut commands: Commands) {
-    commands.spawn((
-        Name::new("Camera"),
-        Camera2dBundle {
-            projection: OrthographicProjection {
-                far: 1000.0,
-                near: -1000.0,
-                scaling_mode: ScalingMode::AutoMin {
-                    min_width: 1280.0,
-                    min_height: 720.0,
+        .add_plugins((
+            UtilsPlugin,
+            PanelPlugin,
+            BattlefieldPlugin,
+            UIPlugin,
+            GameAudioPlugin,
+            FinalDuelPlugin,
+            MatchStatsPlugin,
+            EventLogPlugin,
+            CamerasPlugin,
+            TerritoryRingPlugin,
+            ChargeHistoryPlugin,
+            AppStatePlugin {
+                initial_state: if skip_menu {
+                    AppState::Playing
+                } else {
+                    AppState::MainMenu
                 },
-                ..default()
             },
-            ..default()
-        },
-    ));
+            PauseMenuPlugin,
+            RestartPlugin,
+            SimulationSpeedPlugin {
+                initial_speed: launch_options.speed.unwrap_or(1.0),
+            },
+        ))
+        .add_plugins((
+            HelpPlugin,
+            MatchClockPlugin,
+            BulletPressurePlugin,
+            ChargeTransferPlugin,
+            TriggerHeatmapPlugin,
+            QuadrantTintPlugin,
+            TrajectoryPreviewPlugin,
+            TerritoryTimelinePlugin,
+            FrontlinesPlugin,
+            ThreatIndicatorPlugin,
+            MutatorsPlugin,
+            ParticipantCardsPlugin,
+            FocusNavigationPlugin,
+            MainMenuPlugin,
+            MusicPlugin,
+        ));
+    // app.add_plugins(multiply_or_release::debug_utils::DebugUtilsPlugin);
+    #[cfg(debug_assertions)]
+    app.add_plugins(leaks::LeakDetectorPlugin);
+    if deterministic {
+        app.add_plugins(LockstepPlugin);
+    }
+    if platform::particles_supported() {
+        app.add_plugins(HanabiPlugin);
+    }
+    if tutorial {
+        app.add_plugins(tutorial::TutorialPlugin);
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    // Recording a montage or running a test shouldn't offer to resume a match, or count toward
+    // achievements.
+    if let Some(clips) = montage_clips {
+        app.add_plugins(montage::MontagePlugin { clips });
+    } else if let Some(stress_test) = stress_test {
+        app.add_plugins(stress_test);
+    } else if smoke_test {
+        app.add_plugins(smoke::SmokeTestPlugin);
+    } else {
+        app.add_plugins((autosave::AutosavePlugin, achievements::AchievementsPlugin));
+    }
+    #[cfg(feature = "sqlite")]
+    app.add_plugins((results_db::ResultsDbPlugin, HallOfFamePlugin));
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Ok(address) = std::env::var(metrics::METRICS_ADDRESS_VAR) {
+        match address.parse() {
+            Ok(address) => {
+                app.add_plugins(metrics::MetricsPlugin { address });
+            }
+            Err(error) => error!("Invalid {}: {}", metrics::METRICS_ADDRESS_VAR, error),
+        }
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    if std::env::var_os(bridge::EVENT_BRIDGE_VAR).is_some() {
+        app.add_plugins(bridge::EventBridgePlugin);
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Ok(address) = std::env::var(obs::OBS_ADDRESS_VAR) {
+        app.add_plugins(obs::ObsPlugin { address });
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Ok(address) = std::env::var(predictions::PREDICTIONS_ADDRESS_VAR) {
+        match address.parse() {
+            Ok(address) => {
+                app.add_plugins(predictions::PredictionsPlugin { address });
+            }
+            Err(error) => error!(
+                "Invalid {}: {}",
+                predictions::PREDICTIONS_ADDRESS_VAR,
+                error
+            ),
+        }
+    }
+    app.run();
 }
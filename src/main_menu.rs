@@ -0,0 +1,151 @@
+use bevy::{app::AppExit, prelude::*};
+
+use crate::{focus::Focusable, state::AppState};
+
+// Constants {{{
+
+const MENU_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.7);
+const MENU_TITLE: &str = "Multiply or Release";
+const MENU_TITLE_FONT_SIZE: f32 = 72.0;
+const MENU_BUTTON_FONT_SIZE: f32 = 32.0;
+const MENU_BUTTON_WIDTH: f32 = 320.0;
+const MENU_BUTTON_HEIGHT: f32 = 60.0;
+const NORMAL_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
+const HOVERED_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
+
+// }}}
+
+/// The menu the game starts in, over the first match held still until it's started.
+pub struct MainMenuPlugin;
+impl Plugin for MainMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::MainMenu), spawn_main_menu)
+            .add_systems(OnExit(AppState::MainMenu), despawn_main_menu)
+            .add_systems(
+                Update,
+                handle_menu_buttons.run_if(in_state(AppState::MainMenu)),
+            );
+    }
+}
+
+#[derive(Component, Clone, Copy)]
+struct MainMenu;
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum MenuButton {
+    Play,
+    #[cfg(feature = "sqlite")]
+    HallOfFame,
+    Quit,
+}
+
+fn spawn_main_menu(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Main Menu"),
+            MainMenu,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(16.0),
+                    ..default()
+                },
+                background_color: MENU_BACKGROUND.into(),
+                z_index: ZIndex::Global(2),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                MENU_TITLE,
+                TextStyle {
+                    font: default(),
+                    font_size: MENU_TITLE_FONT_SIZE,
+                    color: Color::WHITE,
+                },
+            ));
+            let buttons = [
+                (MenuButton::Play, "Play"),
+                #[cfg(feature = "sqlite")]
+                (MenuButton::HallOfFame, "Hall of Fame"),
+                (MenuButton::Quit, "Quit"),
+            ];
+            for (button, label) in buttons {
+                parent
+                    .spawn((
+                        button,
+                        Focusable,
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(MENU_BUTTON_WIDTH),
+                                height: Val::Px(MENU_BUTTON_HEIGHT),
+                                border: UiRect::all(Val::Px(5.0)),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            border_color: BorderColor(Color::BLACK),
+                            border_radius: BorderRadius::MAX,
+                            background_color: NORMAL_BUTTON.into(),
+                            ..default()
+                        },
+                    ))
+                    .with_children(|button| {
+                        button.spawn(TextBundle::from_section(
+                            label,
+                            TextStyle {
+                                font: default(),
+                                font_size: MENU_BUTTON_FONT_SIZE,
+                                color: Color::srgb(0.9, 0.9, 0.9),
+                            },
+                        ));
+                    });
+            }
+        });
+}
+fn despawn_main_menu(mut commands: Commands, menu: Query<Entity, With<MainMenu>>) {
+    for entity in &menu {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+fn handle_menu_buttons(
+    mut interaction_query: Query<
+        (
+            &Interaction,
+            &MenuButton,
+            &mut BackgroundColor,
+            &mut BorderColor,
+        ),
+        Changed<Interaction>,
+    >,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut exit_events: EventWriter<AppExit>,
+) {
+    for (&interaction, &button, mut color, mut border_color) in &mut interaction_query {
+        match interaction {
+            Interaction::Pressed | Interaction::Hovered => {
+                *color = HOVERED_BUTTON.into();
+                border_color.0 = Color::WHITE;
+            }
+            Interaction::None => {
+                *color = NORMAL_BUTTON.into();
+                border_color.0 = Color::BLACK;
+            }
+        }
+        if interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            MenuButton::Play => next_state.set(AppState::Playing),
+            #[cfg(feature = "sqlite")]
+            MenuButton::HallOfFame => next_state.set(AppState::HallOfFame),
+            MenuButton::Quit => {
+                exit_events.send(AppExit::Success);
+            }
+        }
+    }
+}
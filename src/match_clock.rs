@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+
+use crate::{
+    battlefield::{ArenaBoundary, SurvivorCount},
+    stats::MatchStats,
+    ui::PlayAreaNode,
+};
+
+// Constants {{{
+
+const CLOCK_FONT_SIZE: f32 = 28.0;
+const CLOCK_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.8);
+
+// }}}
+
+/// Shows how long the match has been going, and what stage it's in.
+pub struct MatchClockPlugin;
+impl Plugin for MatchClockPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_clock)
+            .add_systems(Update, update_clock);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MatchPhase {
+    Early,
+    /// The arena has started closing in.
+    SuddenDeath,
+    FinalDuel,
+    Over,
+}
+impl MatchPhase {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Early => "Early Game",
+            Self::SuddenDeath => "Sudden Death",
+            Self::FinalDuel => "Final Duel",
+            Self::Over => "Match Over",
+        }
+    }
+}
+#[derive(Component, Clone, Copy)]
+struct ClockText;
+
+fn setup_clock(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Match Clock"),
+            PlayAreaNode,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    justify_content: JustifyContent::Center,
+                    padding: UiRect::top(Val::Px(8.0)),
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                ClockText,
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: default(),
+                        font_size: CLOCK_FONT_SIZE,
+                        color: CLOCK_COLOR,
+                    },
+                ),
+            ));
+        });
+}
+fn update_clock(
+    stats: Res<MatchStats>,
+    survivor_count: Res<SurvivorCount>,
+    boundary: Res<ArenaBoundary>,
+    mut text: Query<&mut Text, With<ClockText>>,
+) {
+    let phase = if stats.finished || survivor_count.0 <= 1 {
+        MatchPhase::Over
+    } else if survivor_count.0 == 2 {
+        MatchPhase::FinalDuel
+    } else if boundary.is_shrinking() {
+        MatchPhase::SuddenDeath
+    } else {
+        MatchPhase::Early
+    };
+    let seconds = stats.elapsed as u32;
+    let value = format!("{:02}:{:02}  {}", seconds / 60, seconds % 60, phase.name());
+    for mut text in &mut text {
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! Short codes that pin down the seed and rules of a match, so it can be replayed on another
+//! machine.
+
+use crate::{
+    config::{BulletPhasePolicy, BurstPattern, GameConfig, Mutator, TieBreak, VictoryCondition},
+    utils::{Participant, ParticipantMap},
+};
+
+// Constants {{{
+
+/// Crockford's base 32, which leaves out the letters that are easy to mix up with digits.
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+/// The code is split into groups of this many characters to make it easier to read out.
+const GROUP_LENGTH: usize = 5;
+
+const SHRINKING_ARENA: u64 = 1 << 0;
+const EARTHQUAKES: u64 = 1 << 1;
+const MIRROR_SPAWNS: u64 = 1 << 2;
+const LOW_SPEC: u64 = 1 << 3;
+const CATCH_UP: u64 = 1 << 4;
+const VAMPIRIC_DUELS: u64 = 1 << 5;
+const MOST_TILES_TIE_BREAK: u64 = 1 << 6;
+const BALL_SABOTAGE: u64 = 1 << 7;
+const LAST_STAND: u64 = 1 << 8;
+const SHOT_AI: u64 = 1 << 9;
+const DETERMINISTIC: u64 = 1 << 10;
+const OVERHEAT: u64 = 1 << 11;
+/// Set for each participant sitting the match out.
+const SITTING_OUT: ParticipantMap<u64> = ParticipantMap::new(1 << 12, 1 << 13, 1 << 14, 1 << 15);
+const TILE_TARGETING: u64 = 1 << 16;
+const CHAINED_PANELS: u64 = 1 << 17;
+/// Set when the code comes from a build with the `deterministic` feature, whose physics come out
+/// the same on every platform. Deterministic mode alone doesn't set it.
+const CROSS_PLATFORM: u64 = 1 << 18;
+
+// }}}
+
+/// The code for a match with these rules, started with this seed.
+pub fn encode(config: &GameConfig, seed: u64) -> String {
+    let config = GameConfig {
+        seed: Some(seed),
+        ..config.clone()
+    };
+    let mut bytes = seed.to_le_bytes().to_vec();
+    let flags = [
+        (config.shrinking_arena, SHRINKING_ARENA),
+        (config.earthquakes, EARTHQUAKES),
+        (config.mirror_spawns, MIRROR_SPAWNS),
+        (config.low_spec, LOW_SPEC),
+        (config.catch_up, CATCH_UP),
+        (config.vampiric_duels, VAMPIRIC_DUELS),
+        (
+            config.tie_break == TieBreak::MostTiles,
+            MOST_TILES_TIE_BREAK,
+        ),
+        (config.ball_sabotage, BALL_SABOTAGE),
+        (config.last_stand, LAST_STAND),
+        (config.shot_ai, SHOT_AI),
+        (config.deterministic, DETERMINISTIC),
+        (config.overheat, OVERHEAT),
+        (config.tile_targeting, TILE_TARGETING),
+        (config.chained_panels, CHAINED_PANELS),
+        (cfg!(feature = "deterministic"), CROSS_PLATFORM),
+    ]
+    .into_iter()
+    .chain(Participant::ALL.map(|p| (!config.participants[p], SITTING_OUT[p])))
+    .filter(|&(on, _)| on)
+    .fold(0, |flags, (_, flag)| flags | flag);
+    push_varint(&mut bytes, flags);
+    match config.burst_pattern {
+        BurstPattern::Sequential => bytes.push(0),
+        BurstPattern::Fan(count) => {
+            bytes.push(1);
+            push_varint(&mut bytes, count as u64);
+        }
+        BurstPattern::Salvo(value) => {
+            bytes.push(2);
+            push_varint(&mut bytes, value);
+        }
+    }
+    push_varint(&mut bytes, config.tile_count as u64);
+    match config.bullet_phase {
+        BulletPhasePolicy::Distance => bytes.push(0),
+        BulletPhasePolicy::Timed(millis) => {
+            bytes.push(1);
+            push_varint(&mut bytes, millis as u64);
+        }
+        BulletPhasePolicy::Contact => bytes.push(2),
+    }
+    push_varint(&mut bytes, config.victory_conditions.len() as u64);
+    for condition in &config.victory_conditions {
+        match *condition {
+            VictoryCondition::Territory(percent) => {
+                bytes.push(0);
+                push_varint(&mut bytes, percent as u64);
+            }
+            VictoryCondition::TimedScore(seconds) => {
+                bytes.push(1);
+                push_varint(&mut bytes, seconds as u64);
+            }
+        }
+    }
+    push_varint(&mut bytes, config.bullet_size_cap.map_or(0, |cap| cap + 1));
+    push_varint(&mut bytes, config.mutators.len() as u64);
+    for &mutator in &config.mutators {
+        bytes.push(Mutator::ALL.iter().position(|&m| m == mutator).unwrap() as u8);
+    }
+    bytes.extend_from_slice(&checksum(&config));
+    to_base32(&bytes)
+        .as_bytes()
+        .chunks(GROUP_LENGTH)
+        .map(String::from_utf8_lossy)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+/// The rules and seed of the match the code was made for.
+pub fn decode(code: &str) -> Result<GameConfig, String> {
+    let bytes = from_base32(code)?;
+    let mut reader = bytes.iter().copied();
+    let mut next = || reader.next().ok_or("the code is too short");
+    let mut seed = [0; 8];
+    for byte in &mut seed {
+        *byte = next()?;
+    }
+    let flags = read_varint(&mut next)?;
+    let burst_pattern = match next()? {
+        0 => BurstPattern::Sequential,
+        1 => BurstPattern::Fan(read_varint(&mut next)? as u8),
+        2 => BurstPattern::Salvo(read_varint(&mut next)?),
+        tag => return Err(format!("unknown burst pattern {}", tag)),
+    };
+    let tile_count = read_varint(&mut next)? as usize;
+    let bullet_phase = match next()? {
+        0 => BulletPhasePolicy::Distance,
+        1 => BulletPhasePolicy::Timed(read_varint(&mut next)? as u32),
+        2 => BulletPhasePolicy::Contact,
+        tag => return Err(format!("unknown bullet phase policy {}", tag)),
+    };
+    let victory_condition_count = read_varint(&mut next)?;
+    let mut victory_conditions = Vec::new();
+    for _ in 0..victory_condition_count {
+        victory_conditions.push(match next()? {
+            0 => VictoryCondition::Territory(read_varint(&mut next)? as u8),
+            1 => VictoryCondition::TimedScore(read_varint(&mut next)? as u32),
+            tag => return Err(format!("unknown victory condition {}", tag)),
+        });
+    }
+    let bullet_size_cap = read_varint(&mut next)?.checked_sub(1);
+    let mutator_count = read_varint(&mut next)?;
+    let mut mutators = Vec::new();
+    for _ in 0..mutator_count {
+        let tag = next()?;
+        match Mutator::ALL.get(tag as usize) {
+            Some(&mutator) => mutators.push(mutator),
+            None => return Err(format!("unknown mutator {}", tag)),
+        }
+    }
+    let config = GameConfig {
+        shrinking_arena: flags & SHRINKING_ARENA != 0,
+        earthquakes: flags & EARTHQUAKES != 0,
+        mirror_spawns: flags & MIRROR_SPAWNS != 0,
+        seed: Some(u64::from_le_bytes(seed)),
+        tile_count,
+        low_spec: flags & LOW_SPEC != 0,
+        catch_up: flags & CATCH_UP != 0,
+        burst_pattern,
+        tile_targeting: flags & TILE_TARGETING != 0,
+        vampiric_duels: flags & VAMPIRIC_DUELS != 0,
+        tie_break: if flags & MOST_TILES_TIE_BREAK != 0 {
+            TieBreak::MostTiles
+        } else {
+            TieBreak::Draw
+        },
+        ball_sabotage: flags & BALL_SABOTAGE != 0,
+        last_stand: flags & LAST_STAND != 0,
+        bullet_phase,
+        shot_ai: flags & SHOT_AI != 0,
+        overheat: flags & OVERHEAT != 0,
+        chained_panels: flags & CHAINED_PANELS != 0,
+        bullet_size_cap,
+        participants: SITTING_OUT.map(|flag| flags & flag == 0),
+        mutators,
+        victory_conditions,
+        deterministic: flags & DETERMINISTIC != 0,
+    };
+    let expected = [next()?, next()?];
+    if checksum(&config) != expected {
+        return Err("the code is mistyped, or from a different version of the game".to_string());
+    }
+    config.validate().map_err(|problems| problems.join(", "))?;
+    if flags & CROSS_PLATFORM != 0 && !cfg!(feature = "deterministic") {
+        return Err(
+            "the code is from a deterministic build, and only replays exactly in one built with \
+            the `deterministic` feature"
+                .to_string(),
+        );
+    }
+    Ok(config)
+}
+/// Catches typos, and codes made by versions of the game with rules this one doesn't have.
+fn checksum(config: &GameConfig) -> [u8; 2] {
+    let hash = config.rules_hash() ^ config.seed.unwrap_or_default();
+    [(hash >> 8) as u8, hash as u8]
+}
+fn push_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+fn read_varint(next: &mut impl FnMut() -> Result<u8, &'static str>) -> Result<u64, String> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = next()?;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err("a number in the code is too long".to_string())
+}
+fn to_base32(bytes: &[u8]) -> String {
+    let mut code = String::new();
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for &byte in bytes {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            code.push(ALPHABET[(buffer >> bits) as usize & 31] as char);
+        }
+    }
+    if bits > 0 {
+        code.push(ALPHABET[(buffer << (5 - bits)) as usize & 31] as char);
+    }
+    code
+}
+/// Ignores case and the dashes between groups, and reads the letters Crockford's base 32 leaves out
+/// as the digits they look like.
+fn from_base32(code: &str) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in code.chars().filter(|&c| c != '-') {
+        let c = match c.to_ascii_uppercase() {
+            'O' => '0',
+            'I' | 'L' => '1',
+            c => c,
+        };
+        let Some(value) = ALPHABET.iter().position(|&a| a as char == c) else {
+            return Err(format!("`{}` can't be in a match code", c));
+        };
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    Ok(bytes)
+}
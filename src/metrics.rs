@@ -0,0 +1,189 @@
+use std::{
+    fmt::Write as _,
+    io::{Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use bevy::{ecs::entity::Entities, prelude::*};
+
+use crate::{
+    battlefield::{Bullet, NewBullet, SurvivorCount},
+    panel_plugin::WorkerBall,
+    stats::{MatchFinishedEvent, MatchStats},
+    utils::Participant,
+};
+
+// Constants {{{
+
+/// The environment variable holding the address to serve the metrics on, e.g. `0.0.0.0:9100`.
+pub const METRICS_ADDRESS_VAR: &str = "METRICS_ADDRESS";
+/// How often the served metrics are refreshed, in seconds.
+const METRICS_UPDATE_INTERVAL: f32 = 1.0;
+/// How long a client gets to send its request and take the response, so one that stalls doesn't
+/// hold up the others, which are served one at a time.
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(2);
+
+// }}}
+
+/// Serves runtime metrics in the Prometheus text format over HTTP, for monitoring long-running
+/// instances.
+pub struct MetricsPlugin {
+    pub address: SocketAddr,
+}
+impl Plugin for MetricsPlugin {
+    fn build(&self, app: &mut App) {
+        let listener = match TcpListener::bind(self.address) {
+            Ok(listener) => listener,
+            Err(error) => {
+                error!("Failed to serve metrics on {}: {}", self.address, error);
+                return;
+            }
+        };
+        let page = MetricsPage::default();
+        let served_page = page.0.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let body = served_page.lock().unwrap().clone();
+                if let Err(error) = respond(stream, &body) {
+                    warn!("Failed to respond to a metrics request: {}", error);
+                }
+            }
+        });
+        app.insert_resource(page)
+            .insert_resource(MetricsTimer(Timer::from_seconds(
+                METRICS_UPDATE_INTERVAL,
+                TimerMode::Repeating,
+            )))
+            .init_resource::<MatchesCompleted>()
+            .add_systems(
+                Update,
+                (
+                    count_matches.run_if(on_event::<MatchFinishedEvent>()),
+                    update_metrics,
+                ),
+            );
+    }
+}
+
+/// The latest rendered metrics, shared with the thread serving them.
+#[derive(Resource, Clone, Default)]
+struct MetricsPage(Arc<Mutex<String>>);
+#[derive(Resource)]
+struct MetricsTimer(Timer);
+#[derive(Resource, Default)]
+struct MatchesCompleted(u64);
+
+fn respond(mut stream: TcpStream, body: &str) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(CONNECTION_TIMEOUT))?;
+    stream.set_write_timeout(Some(CONNECTION_TIMEOUT))?;
+    // Whatever was asked for, the answer is the same, so the request is read only to be polite.
+    let mut request = [0; 1024];
+    let _ = stream.read(&mut request)?;
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        body.len(),
+        body
+    )
+}
+fn count_matches(
+    mut events: EventReader<MatchFinishedEvent>,
+    mut completed: ResMut<MatchesCompleted>,
+) {
+    completed.0 += events.read().count() as u64;
+}
+#[allow(clippy::too_many_arguments)]
+fn update_metrics(
+    mut timer: ResMut<MetricsTimer>,
+    mut ticks: Local<u32>,
+    time: Res<Time>,
+    page: Res<MetricsPage>,
+    entities: &Entities,
+    bullets: Query<(), Or<(With<Bullet>, With<NewBullet>)>>,
+    worker_balls: Query<(), With<WorkerBall>>,
+    survivor_count: Res<SurvivorCount>,
+    completed: Res<MatchesCompleted>,
+    stats: Res<MatchStats>,
+) {
+    *ticks += 1;
+    if !timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+    let ticks_per_second = *ticks as f32 / timer.0.duration().as_secs_f32();
+    *ticks = 0;
+
+    let mut body = String::new();
+    let mut gauge = |name: &str, help: &str, value: f64| {
+        let _ = writeln!(
+            body,
+            "# HELP {name} {help}\n# TYPE {name} gauge\n{name} {value}"
+        );
+    };
+    gauge(
+        "multiply_or_release_ticks_per_second",
+        "Frames simulated per second.",
+        ticks_per_second as f64,
+    );
+    gauge(
+        "multiply_or_release_entities",
+        "Entities alive in the world.",
+        entities.len() as f64,
+    );
+    gauge(
+        "multiply_or_release_bullets",
+        "Bullets alive on the battlefield.",
+        bullets.iter().count() as f64,
+    );
+    gauge(
+        "multiply_or_release_worker_balls",
+        "Worker balls alive in the panels.",
+        worker_balls.iter().count() as f64,
+    );
+    gauge(
+        "multiply_or_release_survivors",
+        "Participants still in the current match.",
+        survivor_count.0 as f64,
+    );
+    gauge(
+        "multiply_or_release_match_elapsed_seconds",
+        "Length of the current match so far.",
+        stats.elapsed as f64,
+    );
+    let _ = writeln!(
+        body,
+        "# HELP multiply_or_release_matches_completed_total Matches finished since launch.\n\
+         # TYPE multiply_or_release_matches_completed_total counter\n\
+         multiply_or_release_matches_completed_total {}",
+        completed.0
+    );
+    let _ = writeln!(
+        body,
+        "# HELP multiply_or_release_trigger_hits Trigger zone hits in the current match.\n\
+         # TYPE multiply_or_release_trigger_hits gauge"
+    );
+    for participant in Participant::ALL {
+        let _ = writeln!(
+            body,
+            "multiply_or_release_trigger_hits{{participant=\"{}\"}} {}",
+            participant, stats.trigger_hits[participant]
+        );
+    }
+    let _ = writeln!(
+        body,
+        "# HELP multiply_or_release_eliminated_at_seconds When each participant was eliminated in \
+         the current match.\n# TYPE multiply_or_release_eliminated_at_seconds gauge"
+    );
+    for participant in Participant::ALL {
+        if let Some(time) = stats.eliminated_at[participant] {
+            let _ = writeln!(
+                body,
+                "multiply_or_release_eliminated_at_seconds{{participant=\"{}\"}} {}",
+                participant, time
+            );
+        }
+    }
+    *page.0.lock().unwrap() = body;
+}
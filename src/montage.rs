@@ -0,0 +1,175 @@
+use std::fs;
+
+use bevy::{
+    app::AppExit, prelude::*, render::view::screenshot::ScreenshotManager, window::PrimaryWindow,
+};
+
+use crate::{
+    battlefield::{MatchOutcome, RestartEvent},
+    config::{GameConfig, Tuning},
+    headless::{headless_app, HEADLESS_TIMESTEP},
+};
+
+// Constants {{{
+
+/// How much of the end of each match goes into the montage, in seconds.
+const CLIP_DURATION: f32 = 30.0;
+/// How long to keep recording once a match is over, so the finish isn't cut off abruptly.
+const CLIP_TAIL: f32 = 2.0;
+/// Matches that haven't ended after this many seconds of simulated time are left out.
+const MAX_MATCH_DURATION: f32 = 1200.0;
+const MONTAGE_DIR: &str = "montage";
+const USAGE: &str = "Usage: multiply_or_release montage <match count> [first seed]";
+
+// }}}
+
+/// Plays back the end of each planned match in the window, one after the other, and saves every
+/// frame of them to [`MONTAGE_DIR`]. Needs [`GameConfig::deterministic`], so the matches play out
+/// the same as they did headless.
+pub struct MontagePlugin {
+    pub clips: Vec<Clip>,
+}
+impl Plugin for MontagePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Montage {
+            clips: self.clips.clone(),
+            current: 0,
+            frame: 0,
+            tail: 0,
+            saved: 0,
+        })
+        .add_systems(Last, record_montage);
+    }
+}
+
+/// The part of a match that goes into the montage.
+#[derive(Debug, Clone, Copy)]
+pub struct Clip {
+    pub seed: u64,
+    /// The frame of the match to start saving at.
+    start_frame: u64,
+}
+#[derive(Resource)]
+struct Montage {
+    clips: Vec<Clip>,
+    /// The index of the clip being played back.
+    current: usize,
+    /// The number of frames since the current match started.
+    frame: u64,
+    /// The number of frames since the current match ended.
+    tail: u64,
+    /// The number of frames saved so far, which numbers the next one.
+    saved: u64,
+}
+
+/// Runs the `montage` subcommand with the arguments that follow it, and returns the clips to play
+/// back.
+pub fn run_cli(args: &[String]) -> Vec<Clip> {
+    let parse = |arg: Option<&String>, default: Option<u64>| match arg {
+        Some(arg) => arg.parse().ok(),
+        None => default,
+    };
+    let (Some(match_count), Some(first_seed)) =
+        (parse(args.first(), None), parse(args.get(1), Some(0)))
+    else {
+        eprintln!("{}", USAGE);
+        std::process::exit(2);
+    };
+    if let Err(error) = fs::create_dir_all(MONTAGE_DIR) {
+        eprintln!("Failed to create {}: {}", MONTAGE_DIR, error);
+        std::process::exit(1);
+    }
+    let clips = plan(match_count, first_seed);
+    if clips.is_empty() {
+        eprintln!("None of the matches finished, so there is nothing to put in the montage");
+        std::process::exit(1);
+    }
+    clips
+}
+/// Runs `match_count` headless matches with consecutive seeds, to find out where each of them
+/// ends.
+fn plan(match_count: u64, first_seed: u64) -> Vec<Clip> {
+    let max_frames = (MAX_MATCH_DURATION / HEADLESS_TIMESTEP) as u64;
+    let clip_frames = (CLIP_DURATION / HEADLESS_TIMESTEP) as u64;
+    let mut clips = Vec::new();
+    for seed in first_seed..first_seed + match_count {
+        let config = GameConfig {
+            seed: Some(seed),
+            ..default()
+        };
+        let mut app = headless_app(config, Tuning::default(), ());
+        let mut frames = 0;
+        while frames < max_frames
+            && *app.world().resource::<MatchOutcome>() == MatchOutcome::Undecided
+        {
+            app.update();
+            frames += 1;
+        }
+        if frames == max_frames {
+            eprintln!("Seed {}: unfinished, left out", seed);
+            continue;
+        }
+        eprintln!(
+            "Seed {}: finished after {:.1}s",
+            seed,
+            frames as f32 * HEADLESS_TIMESTEP
+        );
+        clips.push(Clip {
+            seed,
+            start_frame: frames.saturating_sub(clip_frames),
+        });
+    }
+    clips
+}
+/// Saves the frame if it's part of the current clip, and moves on to the next match once the
+/// current one has been over for [`CLIP_TAIL`].
+fn record_montage(
+    mut montage: ResMut<Montage>,
+    outcome: Res<MatchOutcome>,
+    mut config: ResMut<GameConfig>,
+    mut screenshots: ResMut<ScreenshotManager>,
+    window: Query<Entity, With<PrimaryWindow>>,
+    mut restart_events: EventWriter<RestartEvent>,
+    mut exit_events: EventWriter<AppExit>,
+) {
+    let montage = montage.as_mut();
+    // The app only exits at the end of the frame after the last clip.
+    let Some(&clip) = montage.clips.get(montage.current) else {
+        return;
+    };
+    montage.frame += 1;
+    if montage.frame >= clip.start_frame {
+        if let Ok(window) = window.get_single() {
+            let path = format!("{}/{:06}.png", MONTAGE_DIR, montage.saved);
+            if screenshots.save_screenshot_to_disk(window, path).is_ok() {
+                montage.saved += 1;
+            }
+        }
+    }
+    if *outcome == MatchOutcome::Undecided {
+        return;
+    }
+    montage.tail += 1;
+    if (montage.tail as f32) < CLIP_TAIL / HEADLESS_TIMESTEP {
+        return;
+    }
+    montage.current += 1;
+    montage.frame = 0;
+    montage.tail = 0;
+    match montage.clips.get(montage.current) {
+        Some(next) => {
+            config.seed = Some(next.seed);
+            restart_events.send(RestartEvent::default());
+        }
+        None => {
+            info!(
+                "Saved {} frames to {}. To turn them into a video, run: ffmpeg -framerate {} -i {}/%06d.png montage.mp4",
+                montage.saved,
+                MONTAGE_DIR,
+                (1.0 / HEADLESS_TIMESTEP).round(),
+                MONTAGE_DIR
+            );
+            exit_events.send(AppExit::Success);
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use std::time::Duration;
+
+use bevy::{audio::Volume, prelude::*};
+
+use crate::{
+    audio::SoundVolumes,
+    battlefield::{MatchOutcome, RestartEvent, SurvivorCount},
+    state::AppState,
+};
+
+// Constants {{{
+
+/// A bass line in A minor, played around and around.
+const BASS_LINE: [f32; 8] = [110.0, 110.0, 130.81, 110.0, 146.83, 130.81, 98.0, 123.47];
+const NOTE_DURATION: f32 = 0.22;
+const CALM_NOTE_INTERVAL: f32 = 0.5;
+const INTENSE_NOTE_INTERVAL: f32 = 0.28;
+const CALM_VOLUME: f32 = 0.08;
+const INTENSE_VOLUME: f32 = 0.14;
+/// How loud the bass line doubled an octave up is at full intensity.
+const LEAD_VOLUME: f32 = 0.06;
+/// The number of survivors at or below which the music is at full intensity.
+const INTENSE_SURVIVOR_COUNT: u8 = 2;
+/// How long the music takes to go from calm to intense.
+const TRANSITION_DURATION: f32 = 3.0;
+/// Played note by note once the match is over, after which the music stays quiet until the next
+/// match starts.
+const VICTORY_STING: [f32; 4] = [261.63, 329.63, 392.0, 523.25];
+const DRAW_STING: [f32; 4] = [220.0, 261.63, 329.63, 220.0];
+const STING_NOTE_DURATION: f32 = 0.3;
+const STING_NOTE_INTERVAL: f32 = 0.18;
+const STING_VOLUME: f32 = 0.12;
+
+// }}}
+
+/// Loops background music that picks up once only two participants survive, and plays a sting
+/// when the match ends.
+pub struct MusicPlugin;
+impl Plugin for MusicPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup)
+            .add_systems(OnEnter(AppState::GameOver), play_sting)
+            .add_systems(
+                Update,
+                (
+                    resume_loop.run_if(on_event::<RestartEvent>()),
+                    follow_survivors.run_if(resource_changed::<SurvivorCount>),
+                    play_music,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MusicPhase {
+    Loop,
+    /// Counting through the notes of the sting.
+    Sting {
+        draw: bool,
+        step: usize,
+    },
+}
+#[derive(Resource)]
+struct Music {
+    bass_line: Vec<Handle<Pitch>>,
+    victory_sting: Vec<Handle<Pitch>>,
+    draw_sting: Vec<Handle<Pitch>>,
+    phase: MusicPhase,
+    /// The next note of the bass line.
+    step: usize,
+    until_next_note: f32,
+    /// From 0 for calm to 1 for intense, moving towards the target a bit every frame.
+    intensity: f32,
+    target_intensity: f32,
+}
+
+fn lerp(from: f32, to: f32, t: f32) -> f32 {
+    from + (to - from) * t
+}
+fn setup(mut commands: Commands, mut pitches: ResMut<Assets<Pitch>>) {
+    let mut notes = |frequencies: &[f32], duration: f32| -> Vec<Handle<Pitch>> {
+        frequencies
+            .iter()
+            .map(|&frequency| pitches.add(Pitch::new(frequency, Duration::from_secs_f32(duration))))
+            .collect()
+    };
+    commands.insert_resource(Music {
+        bass_line: notes(&BASS_LINE, NOTE_DURATION),
+        victory_sting: notes(&VICTORY_STING, STING_NOTE_DURATION),
+        draw_sting: notes(&DRAW_STING, STING_NOTE_DURATION),
+        phase: MusicPhase::Loop,
+        step: 0,
+        until_next_note: 0.0,
+        intensity: 0.0,
+        target_intensity: 0.0,
+    });
+}
+fn follow_survivors(survivor_count: Res<SurvivorCount>, mut music: ResMut<Music>) {
+    music.target_intensity = if survivor_count.0 <= INTENSE_SURVIVOR_COUNT {
+        1.0
+    } else {
+        0.0
+    };
+}
+/// Coming back to the game over screen from the pause menu doesn't play it again.
+fn play_sting(outcome: Res<MatchOutcome>, mut music: ResMut<Music>) {
+    if music.phase != MusicPhase::Loop {
+        return;
+    }
+    music.phase = MusicPhase::Sting {
+        draw: *outcome == MatchOutcome::Draw,
+        step: 0,
+    };
+    music.until_next_note = 0.0;
+}
+/// The next match starts calm, from the top of the bass line.
+fn resume_loop(mut music: ResMut<Music>) {
+    music.phase = MusicPhase::Loop;
+    music.step = 0;
+    music.until_next_note = 0.0;
+    music.intensity = 0.0;
+    music.target_intensity = 0.0;
+}
+/// Keeps time by the real clock, so the music carries on through the menus and doesn't speed up
+/// with the simulation.
+fn play_music(
+    mut commands: Commands,
+    mut music: ResMut<Music>,
+    time: Res<Time<Real>>,
+    volumes: Res<SoundVolumes>,
+) {
+    let music = &mut *music;
+    let delta = time.delta_seconds();
+    let change = delta / TRANSITION_DURATION;
+    music.intensity += (music.target_intensity - music.intensity).clamp(-change, change);
+    music.until_next_note -= delta;
+    if music.until_next_note > 0.0 {
+        return;
+    }
+    let mut notes = Vec::new();
+    match &mut music.phase {
+        MusicPhase::Loop => {
+            music.until_next_note =
+                lerp(CALM_NOTE_INTERVAL, INTENSE_NOTE_INTERVAL, music.intensity);
+            let source = music.bass_line[music.step % music.bass_line.len()].clone();
+            music.step += 1;
+            let volume = lerp(CALM_VOLUME, INTENSE_VOLUME, music.intensity);
+            notes.push((source.clone(), volume, 1.0));
+            if music.intensity > 0.0 {
+                notes.push((source, LEAD_VOLUME * music.intensity, 2.0));
+            }
+        }
+        MusicPhase::Sting { draw, step } => {
+            let sting = if *draw {
+                &music.draw_sting
+            } else {
+                &music.victory_sting
+            };
+            let Some(source) = sting.get(*step) else {
+                return;
+            };
+            *step += 1;
+            music.until_next_note = STING_NOTE_INTERVAL;
+            notes.push((source.clone(), STING_VOLUME, 1.0));
+        }
+    }
+    if volumes.music <= 0.0 {
+        return;
+    }
+    for (source, volume, speed) in notes {
+        commands.spawn((
+            Name::new("Music Note"),
+            PitchBundle {
+                source,
+                settings: PlaybackSettings::DESPAWN
+                    .with_volume(Volume::new(volume * volumes.music))
+                    .with_speed(speed),
+            },
+        ));
+    }
+}
@@ -0,0 +1,122 @@
+use bevy::prelude::*;
+
+use crate::{
+    battlefield::RestartEvent,
+    config::{GameConfig, Mutator},
+    ui::PlayAreaNode,
+};
+
+// Constants {{{
+
+const LOW_GRAVITY_FACTOR: f32 = 0.5;
+const GIANT_BULLET_FACTOR: f32 = 2.0;
+const DOUBLE_MULTIPLY_FACTOR: u8 = 2;
+
+const HUD_FONT_SIZE: f32 = 20.0;
+/// Far enough down to clear the match clock.
+const HUD_TOP: f32 = 44.0;
+const HUD_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.8);
+
+// }}}
+
+/// Applies the mutators of the config at the start of every match, and lists the ones in play on
+/// the HUD, under the match clock.
+pub struct MutatorsPlugin;
+impl Plugin for MutatorsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MutatorEffects>()
+            .add_systems(PreStartup, apply_mutators)
+            .add_systems(Startup, setup_hud)
+            .add_systems(PreUpdate, apply_mutators.run_if(on_event::<RestartEvent>()))
+            .add_systems(
+                Update,
+                update_hud.run_if(resource_changed::<MutatorEffects>),
+            );
+    }
+}
+
+/// What the mutators of the current match change, as factors on the usual values. The config can
+/// change during a match, so this is what the rest of the game goes by.
+#[derive(Debug, Clone, Resource)]
+pub struct MutatorEffects {
+    pub active: Vec<Mutator>,
+    pub worker_gravity_factor: f32,
+    pub bullet_size_factor: f32,
+    pub multiply_factor: u8,
+    pub boost_cooldown: bool,
+}
+impl Default for MutatorEffects {
+    fn default() -> Self {
+        Self {
+            active: Vec::new(),
+            worker_gravity_factor: 1.0,
+            bullet_size_factor: 1.0,
+            multiply_factor: 1,
+            boost_cooldown: true,
+        }
+    }
+}
+impl MutatorEffects {
+    pub fn new(mutators: &[Mutator]) -> Self {
+        let mut effects = Self {
+            active: mutators.to_vec(),
+            ..default()
+        };
+        for &mutator in mutators {
+            match mutator {
+                Mutator::LowGravity => effects.worker_gravity_factor = LOW_GRAVITY_FACTOR,
+                Mutator::GiantBullets => effects.bullet_size_factor = GIANT_BULLET_FACTOR,
+                Mutator::DoubleMultiply => effects.multiply_factor = DOUBLE_MULTIPLY_FACTOR,
+                Mutator::NoBoostCooldown => effects.boost_cooldown = false,
+            }
+        }
+        effects
+    }
+}
+#[derive(Component, Clone, Copy)]
+struct MutatorsText;
+
+fn apply_mutators(config: Res<GameConfig>, mut effects: ResMut<MutatorEffects>) {
+    *effects = MutatorEffects::new(&config.mutators);
+}
+fn setup_hud(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Mutators HUD"),
+            PlayAreaNode,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    justify_content: JustifyContent::Center,
+                    padding: UiRect::top(Val::Px(HUD_TOP)),
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                MutatorsText,
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: default(),
+                        font_size: HUD_FONT_SIZE,
+                        color: HUD_COLOR,
+                    },
+                )
+                .with_text_justify(JustifyText::Center),
+            ));
+        });
+}
+fn update_hud(effects: Res<MutatorEffects>, mut texts: Query<&mut Text, With<MutatorsText>>) {
+    let value = effects
+        .active
+        .iter()
+        .map(|mutator| mutator.name())
+        .collect::<Vec<_>>()
+        .join("\n");
+    for mut text in &mut texts {
+        text.sections[0].value.clone_from(&value);
+    }
+}
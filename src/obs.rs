@@ -0,0 +1,266 @@
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::TcpStream,
+    sync::mpsc::{self, Receiver, Sender},
+};
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    battlefield::{EliminationEvent, RestartEvent, SurvivorCount},
+    state::AppState,
+    stats::MatchFinishedEvent,
+};
+
+// Constants {{{
+
+/// The environment variable holding the address of the OBS WebSocket server, e.g.
+/// `127.0.0.1:4455`.
+pub const OBS_ADDRESS_VAR: &str = "OBS_ADDRESS";
+/// The environment variable holding the path of the file mapping game events to OBS requests.
+const OBS_MAPPING_VAR: &str = "OBS_MAPPING";
+const DEFAULT_MAPPING_PATH: &str = "obs.ron";
+/// Any 16 bytes in base64 will do, the server only echoes back a hash of them.
+const WEBSOCKET_KEY: &str = "bXVsdGlwbHlvcnJlbGVhc2U=";
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_CLOSE: u8 = 0x8;
+
+// }}}
+
+/// Sends requests to OBS over its WebSocket API when key moments of the match happen, like
+/// switching to a close-up scene for the final duel, so a stream can run without anyone at the
+/// controls.
+///
+/// Which requests go out on which moments is read from a RON file, [`DEFAULT_MAPPING_PATH`] unless
+/// set otherwise with [`OBS_MAPPING_VAR`]. OBS must have authentication turned off.
+pub struct ObsPlugin {
+    pub address: String,
+}
+impl Plugin for ObsPlugin {
+    fn build(&self, app: &mut App) {
+        let path = std::env::var(OBS_MAPPING_VAR).unwrap_or_else(|_| DEFAULT_MAPPING_PATH.into());
+        let mapping = match fs::read_to_string(&path)
+            .map_err(|error| error.to_string())
+            .and_then(|text| ron::from_str::<Vec<Hook>>(&text).map_err(|error| error.to_string()))
+        {
+            Ok(mapping) => mapping,
+            Err(error) => {
+                error!("Failed to load the OBS mapping from {}: {}", path, error);
+                return;
+            }
+        };
+        let (sender, receiver) = mpsc::channel();
+        let address = self.address.clone();
+        std::thread::spawn(move || {
+            if let Err(error) = forward_requests(&address, receiver) {
+                error!("The connection to OBS at {} failed: {}", address, error);
+            }
+        });
+        app.insert_resource(ObsHooks { mapping, sender })
+            .add_systems(
+                Update,
+                (
+                    hook_match_start.run_if(on_event::<RestartEvent>()),
+                    hook_final_duel.run_if(
+                        in_state(AppState::Playing).and_then(resource_changed::<SurvivorCount>),
+                    ),
+                    hook_elimination.run_if(on_event::<EliminationEvent>()),
+                    hook_game_over.run_if(on_event::<MatchFinishedEvent>()),
+                ),
+            );
+    }
+}
+
+/// The moments of the match that requests can be sent on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+enum GameMoment {
+    MatchStart,
+    FinalDuelStart,
+    Elimination,
+    GameOver,
+}
+#[derive(Debug, Clone, Deserialize)]
+enum ObsAction {
+    SwitchScene(String),
+    /// Shows or hides a source in a scene, going by the ID OBS gives the source in that scene.
+    SetSourceEnabled {
+        scene: String,
+        item_id: u32,
+        enabled: bool,
+    },
+}
+impl ObsAction {
+    /// The request as an OBS WebSocket message.
+    fn to_message(&self, id: u64) -> String {
+        let (request_type, data) = match self {
+            Self::SwitchScene(scene) => (
+                "SetCurrentProgramScene",
+                format!("{{\"sceneName\":{}}}", json_string(scene)),
+            ),
+            Self::SetSourceEnabled {
+                scene,
+                item_id,
+                enabled,
+            } => (
+                "SetSceneItemEnabled",
+                format!(
+                    "{{\"sceneName\":{},\"sceneItemId\":{},\"sceneItemEnabled\":{}}}",
+                    json_string(scene),
+                    item_id,
+                    enabled
+                ),
+            ),
+        };
+        format!(
+            "{{\"op\":6,\"d\":{{\"requestType\":\"{}\",\"requestId\":\"{}\",\"requestData\":{}}}}}",
+            request_type, id, data
+        )
+    }
+}
+#[derive(Debug, Clone, Deserialize)]
+struct Hook {
+    on: GameMoment,
+    action: ObsAction,
+}
+#[derive(Resource)]
+struct ObsHooks {
+    mapping: Vec<Hook>,
+    sender: Sender<ObsAction>,
+}
+impl ObsHooks {
+    fn fire(&self, moment: GameMoment) {
+        for hook in self.mapping.iter().filter(|hook| hook.on == moment) {
+            // The connection thread only stops after logging why.
+            let _ = self.sender.send(hook.action.clone());
+        }
+    }
+}
+
+pub fn json_string(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() + 2);
+    escaped.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+/// Writes a single masked frame, as clients have to.
+fn write_frame(stream: &mut impl Write, opcode: u8, payload: &[u8]) -> io::Result<()> {
+    let mut frame = vec![0x80 | opcode];
+    match payload.len() {
+        length @ 0..=125 => frame.push(0x80 | length as u8),
+        length @ 126..=0xFFFF => {
+            frame.push(0x80 | 126);
+            frame.extend_from_slice(&(length as u16).to_be_bytes());
+        }
+        length => {
+            frame.push(0x80 | 127);
+            frame.extend_from_slice(&(length as u64).to_be_bytes());
+        }
+    }
+    let mask = rand::random::<[u8; 4]>();
+    frame.extend_from_slice(&mask);
+    frame.extend(payload.iter().zip(mask.iter().cycle()).map(|(b, m)| b ^ m));
+    stream.write_all(&frame)
+}
+/// Reads a single unmasked frame from the server, and returns its opcode and payload.
+fn read_frame(stream: &mut impl Read) -> io::Result<(u8, Vec<u8>)> {
+    let mut header = [0; 2];
+    stream.read_exact(&mut header)?;
+    let length = match header[1] & 0x7F {
+        126 => {
+            let mut length = [0; 2];
+            stream.read_exact(&mut length)?;
+            u16::from_be_bytes(length) as usize
+        }
+        127 => {
+            let mut length = [0; 8];
+            stream.read_exact(&mut length)?;
+            u64::from_be_bytes(length) as usize
+        }
+        length => length as usize,
+    };
+    let mut payload = vec![0; length];
+    stream.read_exact(&mut payload)?;
+    Ok((header[0] & 0x0F, payload))
+}
+/// Connects to OBS, then sends every action that comes through `receiver` for as long as the game
+/// runs.
+fn forward_requests(address: &str, receiver: Receiver<ObsAction>) -> io::Result<()> {
+    let stream = TcpStream::connect(address)?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+    write!(
+        writer,
+        "GET / HTTP/1.1\r\nHost: {}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+         Sec-WebSocket-Key: {}\r\nSec-WebSocket-Version: 13\r\n\
+         Sec-WebSocket-Protocol: obswebsocket.json\r\n\r\n",
+        address, WEBSOCKET_KEY
+    )?;
+    let mut status = String::new();
+    reader.read_line(&mut status)?;
+    if !status.contains(" 101 ") {
+        return Err(io::Error::other(format!(
+            "the WebSocket handshake was refused: {}",
+            status.trim_end()
+        )));
+    }
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 2 {
+        line.clear();
+    }
+    let (_, hello) = read_frame(&mut reader)?;
+    if String::from_utf8_lossy(&hello).contains("\"authentication\"") {
+        return Err(io::Error::other(
+            "OBS asks for a password, which isn't supported",
+        ));
+    }
+    write_frame(
+        &mut writer,
+        OPCODE_TEXT,
+        b"{\"op\":1,\"d\":{\"rpcVersion\":1}}",
+    )?;
+    info!("Connected to OBS at {}", address);
+    // Nothing the server says back is needed, but it has to be read so it doesn't back up.
+    std::thread::spawn(move || {
+        while let Ok((opcode, _)) = read_frame(&mut reader) {
+            if opcode == OPCODE_CLOSE {
+                return;
+            }
+        }
+    });
+    for (id, action) in receiver.iter().enumerate() {
+        write_frame(
+            &mut writer,
+            OPCODE_TEXT,
+            action.to_message(id as u64).as_bytes(),
+        )?;
+    }
+    Ok(())
+}
+fn hook_match_start(mut events: EventReader<RestartEvent>, hooks: Res<ObsHooks>) {
+    events.clear();
+    hooks.fire(GameMoment::MatchStart);
+}
+fn hook_final_duel(survivor_count: Res<SurvivorCount>, hooks: Res<ObsHooks>) {
+    if survivor_count.0 == 2 {
+        hooks.fire(GameMoment::FinalDuelStart);
+    }
+}
+fn hook_elimination(mut events: EventReader<EliminationEvent>, hooks: Res<ObsHooks>) {
+    for _ in events.read() {
+        hooks.fire(GameMoment::Elimination);
+    }
+}
+fn hook_game_over(mut events: EventReader<MatchFinishedEvent>, hooks: Res<ObsHooks>) {
+    events.clear();
+    hooks.fire(GameMoment::GameOver);
+}
@@ -1,22 +1,31 @@
 #![allow(clippy::type_complexity, clippy::too_many_arguments)]
 
 use crate::{
-    battlefield::{game_is_going, RestartEvent},
+    battlefield::{Charge, EliminationEvent, RestartEvent},
+    cameras::{panel_render_layers, PropagateRenderLayers},
     collision_groups::{self, PANEL_OBSTACLES, PANEL_TRIGGER_ZONES},
-    utils::{EffectPropertiesExt, ParticipantMap, TileColor, TrailEffect, TRAIL_LIFETIME},
+    config::{DisplayConfig, GameConfig, MatchSeed, ScreenLayout, Tuning, ZoneEffect},
+    mutators::MutatorEffects,
+    pressure_hud::short_number,
+    state::AppState,
+    utils::{
+        EffectPropertiesExt, EntityBudget, ParticipantMap, ScreenShake, TileColor, TrailEffect,
+        TRAIL_LIFETIME,
+    },
     Participant,
 };
 use bevy::{
     color::palettes::css,
     prelude::*,
+    render::view::RenderLayers,
     sprite::{MaterialMesh2dBundle, Mesh2dHandle},
 };
 use bevy_hanabi::prelude::*;
 use bevy_rapier2d::prelude::*;
 use rand::{
     distributions::{DistIter, Distribution, Uniform},
-    rngs::ThreadRng,
-    thread_rng, Rng,
+    rngs::StdRng,
+    thread_rng, Rng, SeedableRng,
 };
 use std::{borrow::Cow, time::Duration};
 
@@ -26,24 +35,32 @@ use std::{borrow::Cow, time::Duration};
 
 const LEFT_ROOT_X: f32 = -500.0;
 const RIGHT_ROOT_X: f32 = 500.0;
+/// In the portrait layout, the panels sit side by side below the battlefield.
+const PORTRAIT_ROOT_X: f32 = 150.0;
+const PORTRAIT_ROOT_Y: f32 = -740.0;
 
 const WALL_THICKNESS: f32 = 10.0;
 const WALL_COLOR: Color = Color::srgb(0.8, 0.8, 0.8);
 const ARENA_COLOR: Color = Color::Srgba(css::DARK_SLATE_GRAY);
 const ARENA_HEIGHT: f32 = 700.0;
-const ARENA_WIDTH: f32 = 260.0;
+pub const ARENA_WIDTH: f32 = 260.0;
 
 const TRIGGER_ZONE_Y: f32 = -250.0;
 const TRIGGER_ZONE_HEIGHT: f32 = 40.0;
 /// The color of the center trigger zone.
-const TRIGGER_ZONE_COLOR_0: Color = Color::Srgba(css::ALICE_BLUE);
+pub const TRIGGER_ZONE_COLOR_0: Color = Color::Srgba(css::ALICE_BLUE);
 /// The color of the trigger zones to the left and right of center.
-const TRIGGER_ZONE_COLOR_1: Color = Color::Srgba(css::LIGHT_PINK);
+pub const TRIGGER_ZONE_COLOR_1: Color = Color::Srgba(css::LIGHT_PINK);
 /// The color of the outer trigger zones.
-const TRIGGER_ZONE_COLOR_2: Color = Color::Srgba(css::LIGHT_SKY_BLUE);
+pub const TRIGGER_ZONE_COLOR_2: Color = Color::Srgba(css::LIGHT_SKY_BLUE);
+pub const PENALTY_ZONE_COLOR: Color = Color::Srgba(css::CRIMSON);
 const TRIGGER_ZONE_TEXT_COLOR: Color = Color::BLACK;
 const TRIGGER_ZONE_TEXT_SIZE: f32 = 12.0;
 
+/// How far below the top of the arena the turret charge of each participant is shown.
+const CHARGE_PREVIEW_OFFSET_Y: f32 = 18.0;
+const CHARGE_PREVIEW_FONT_SIZE: f32 = 16.0;
+
 const CIRCLE_RADIUS: f32 = 10.0;
 const CIRCLE_COLOR: Color = Color::srgb(0.8, 0.8, 0.8);
 const CIRCLE_PYRAMID_VERTICAL_OFFSET: f32 = 250.0;
@@ -51,6 +68,66 @@ const CIRCLE_PYRAMID_VERTICAL_COUNT: usize = 5;
 const CIRCLE_PYRAMID_VERTICAL_GAP: f32 = 8.0;
 const CIRCLE_PYRAMID_HORIZONTAL_GAP: f32 = 45.0;
 
+const BUMPER_RADIUS: f32 = 8.0;
+const BUMPER_COLOR: Color = Color::Srgba(css::ORANGE);
+const BUMPER_LIT_COLOR: Color = Color::Srgba(css::YELLOW);
+/// In the gap between the pyramid and the grid of circles.
+const BUMPER_Y: f32 = 104.0;
+const BUMPER_X: f32 = 72.0;
+/// The speed a bumper adds to a ball that hits it, pointing away from the bumper.
+const BUMPER_IMPULSE: f32 = 250.0;
+/// How long a bumper stays lit after it kicks a ball.
+const BUMPER_FLASH_DURATION: f32 = 0.15;
+
+/// The conveyor strips just above the trigger zones, and the sideways speed each one pushes balls
+/// toward. Positive speeds push right.
+const CONVEYORS: [ConveyorLayout; 2] = [
+    ConveyorLayout {
+        x: -2.0 * ARENA_WIDTH_FRAC_5,
+        width: ARENA_WIDTH_FRAC_5,
+        speed: 60.0,
+    },
+    ConveyorLayout {
+        x: 2.0 * ARENA_WIDTH_FRAC_5,
+        width: ARENA_WIDTH_FRAC_5,
+        speed: -60.0,
+    },
+];
+const CONVEYOR_Y: f32 = TRIGGER_ZONE_Y + 35.0;
+const CONVEYOR_HEIGHT: f32 = 12.0;
+/// How quickly a ball on a conveyor picks up its speed, per second. Fast balls go through before
+/// it makes much of a difference, slow ones get carried along.
+const CONVEYOR_GRIP: f32 = 8.0;
+const CONVEYOR_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.25);
+const CONVEYOR_TEXT_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.6);
+
+/// The transfer chutes sit against the walls in both bottom corners, just above the conveyors.
+const CHUTE_SIZE: f32 = 16.0;
+const CHUTE_X: f32 = ARENA_WIDTH_FRAC_2 - CHUTE_SIZE / 2.0;
+const CHUTE_Y: f32 = CONVEYOR_Y + (CONVEYOR_HEIGHT + CHUTE_SIZE) / 2.0;
+/// The chance that a worker ball dropping into a chute goes through to the other panel.
+const CHUTE_TRANSFER_CHANCE: f64 = 0.25;
+const CHUTE_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.6);
+
+/// The ball savers sit against the walls in both bottom corners, right above the chutes.
+const BALL_SAVER_SIZE: f32 = 16.0;
+const BALL_SAVER_X: f32 = ARENA_WIDTH_FRAC_2 - BALL_SAVER_SIZE / 2.0;
+const BALL_SAVER_Y: f32 = CHUTE_Y + (CHUTE_SIZE + BALL_SAVER_SIZE) / 2.0;
+/// How long a ball saver takes to recharge after handing out a bonus ball.
+const BALL_SAVER_COOLDOWN: f32 = 15.0;
+const BALL_SAVER_COLOR: Color = Color::Srgba(css::LIME);
+const BALL_SAVER_SPENT_COLOR: Color = Color::srgba(0.2, 0.8, 0.2, 0.25);
+
+/// How long the gate stays over one of the release zones before sliding over to the other.
+const GATE_INTERVAL: f32 = 12.0;
+const GATE_SLIDE_DURATION: f32 = 0.5;
+const GATE_HEIGHT: f32 = 6.0;
+/// Just over the trigger zones, so it catches balls before they get in.
+const GATE_Y: f32 = TRIGGER_LINE_Y + 4.0;
+const GATE_COLOR: Color = Color::Srgba(css::LIGHT_SLATE_GRAY);
+const GATE_TEXT_COLOR: Color = Color::WHITE;
+const GATE_TEXT_OFFSET_Y: f32 = 10.0;
+
 const TRIGGER_ZONE_DIVIDER_COLOR: Color = Color::srgb(0.8, 0.8, 0.8);
 const TRIGGER_ZONE_DIVIDER_HEIGHT_OFFSET: f32 = 2.5;
 const TRIGGER_ZONE_DIVIDER_RADIUS: f32 = 2.5;
@@ -64,10 +141,31 @@ const CIRCLE_GRID_HORIZONTAL_HALF_COUNT_ODD_ROW: usize = 3;
 
 pub const WORKER_BALL_RADIUS: f32 = 5.0;
 const WORKER_BALL_SPAWN_Y: f32 = 320.0;
-const WORKER_BALL_RESTITUTION_COEFFICIENT: f32 = 0.5;
-const WORKER_BALL_SPAWN_TIMER_SECS: f32 = 10.0;
+pub const WORKER_BALL_RESTITUTION_COEFFICIENT: f32 = 0.5;
+pub const DEFAULT_WORKER_BALL_SPAWN_INTERVAL: f32 = 10.0;
 pub const WORKER_BALL_COUNT_MAX: usize = 6;
+/// The most worker balls a participant can have at once, bonus balls included.
+const WORKER_BALL_HARD_CAP: usize = 10;
 const WORKER_BALL_GRAVITY_SCALE: f32 = 15.0;
+/// The least time between two triggers by the same worker ball. It also has to have left the zone
+/// completely, above the trigger line, before it can trigger again.
+const TRIGGER_COOLDOWN: f32 = 0.5;
+/// Mixed into the match seed to seed the [`MatchRng`].
+const MATCH_RNG_SEED_MASK: u64 = 0x9e37_79b9_7f4a_7c15;
+
+const EARTHQUAKE_MIN_INTERVAL: f32 = 45.0;
+const EARTHQUAKE_MAX_INTERVAL: f32 = 90.0;
+/// The change in velocity applied to each worker ball. The vertical component is always upward to
+/// knock settled balls loose.
+const EARTHQUAKE_IMPULSE: f32 = 150.0;
+const EARTHQUAKE_SHAKE_DURATION: f32 = 0.6;
+const EARTHQUAKE_SHAKE_INTENSITY: f32 = 6.0;
+
+const PENALTY_SHAKE_DURATION: f32 = 0.25;
+const PENALTY_SHAKE_INTENSITY: f32 = 4.0;
+/// The red washed over the panel a penalty zone was hit in, fading out over the duration.
+const PENALTY_FLASH_COLOR: Color = Color::srgba(1.0, 0.0, 0.0, 0.3);
+const PENALTY_FLASH_DURATION: f32 = 0.4;
 
 // Z-index
 const WALL_Z: f32 = -4.0;
@@ -75,8 +173,15 @@ const ARENA_Z: f32 = -3.0;
 const CIRCLE_Z: f32 = -1.0;
 const TRIGGER_ZONE_Z: f32 = -2.0;
 const TRIGGER_ZONE_DIVIDER_Z: f32 = -1.0;
+const BUMPER_Z: f32 = -1.0;
+const CONVEYOR_Z: f32 = -1.5;
+const CHUTE_Z: f32 = -1.5;
+const BALL_SAVER_Z: f32 = -1.5;
+const GATE_Z: f32 = -1.0;
 const TRIGGER_ZONE_TEXT_OFFSET_Z: f32 = -1.0;
 const WORKER_BALL_Z: f32 = 1.0;
+const CHARGE_PREVIEW_Z: f32 = 2.0;
+const PENALTY_FLASH_Z: f32 = 3.0;
 
 // Calculated
 const WALL_HEIGHT: f32 = ARENA_HEIGHT + 2.0 * WALL_THICKNESS;
@@ -84,12 +189,17 @@ const WALL_WIDTH: f32 = ARENA_WIDTH + 2.0 * WALL_THICKNESS;
 const ARENA_HEIGHT_FRAC_2: f32 = ARENA_HEIGHT / 2.0;
 const ARENA_WIDTH_FRAC_2: f32 = ARENA_WIDTH / 2.0;
 const ARENA_WIDTH_FRAC_5: f32 = ARENA_WIDTH / 5.0;
-const ARENA_WIDTH_FRAC_10: f32 = ARENA_WIDTH / 10.0;
+/// The top edge of the trigger zones, relative to the panel root.
+pub const TRIGGER_LINE_Y: f32 = TRIGGER_ZONE_Y + TRIGGER_ZONE_HEIGHT / 2.0;
 
 const CIRCLE_HALF_GAP: f32 = CIRCLE_PYRAMID_HORIZONTAL_GAP / 2.0;
 const CIRCLE_DIAMETER: f32 = CIRCLE_RADIUS * 2.0;
 
 const WORKER_BALL_DIAMETER: f32 = WORKER_BALL_RADIUS * 2.0;
+/// The letter on each worker ball is laid out at this size and scaled down to fit the ball, to
+/// keep it sharp.
+const WORKER_BALL_LABEL_FONT_SIZE: f32 = 32.0;
+const WORKER_BALL_LABEL_SCALE: f32 = WORKER_BALL_DIAMETER * 0.8 / WORKER_BALL_LABEL_FONT_SIZE;
 
 // Messages
 
@@ -102,22 +212,92 @@ const EXPECT_TWO_PANELS_MSG: &str = "There should be exactly two entities with `
 pub struct PanelPlugin;
 impl Plugin for PanelPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<TriggerEvent>()
+        app.init_resource::<Tuning>()
+            .add_event::<TriggerEvent>()
+            .add_event::<SabotageEvent>()
+            .register_type::<TriggerType>()
+            .register_type::<WorkerBall>()
+            .register_type::<TriggerCooldown>()
             .add_systems(Startup, setup)
             .add_systems(
                 Update,
-                spawn_workers.run_if(game_is_going.and_then(spawn_workers_condition)),
+                spawn_workers.run_if(in_state(AppState::Playing).and_then(spawn_workers_condition)),
             )
-            .add_systems(Update, ball_reset.run_if(game_is_going))
             .add_systems(
                 Update,
-                trigger_event
-                    .run_if(on_event::<CollisionEvent>().or_else(on_event::<RestartEvent>())),
+                (
+                    save_balls.run_if(on_event::<CollisionEvent>()),
+                    spawn_bonus_workers.run_if(bonus_workers_pending),
+                )
+                    .chain()
+                    .after(spawn_workers)
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(Update, recharge_ball_savers)
+            .add_systems(Update, ball_reset.run_if(in_state(AppState::Playing)))
+            .add_systems(
+                Update,
+                (
+                    rearm_trigger_cooldowns,
+                    trigger_event
+                        .run_if(on_event::<CollisionEvent>().or_else(on_event::<RestartEvent>())),
+                )
+                    .chain(),
             )
             .add_systems(
                 Update,
                 update_workers_particle_position.before(spawn_workers),
             )
+            .add_systems(
+                Update,
+                earthquake.run_if(in_state(AppState::Playing).and_then(earthquakes_enabled)),
+            )
+            .add_systems(
+                Update,
+                despawn_eliminated_workers.run_if(on_event::<EliminationEvent>()),
+            )
+            .add_systems(Update, update_charge_previews)
+            .add_systems(
+                Update,
+                (
+                    flash_penalties.run_if(on_event::<TriggerEvent>()),
+                    fade_penalty_flashes,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    show_chutes.run_if(resource_changed::<GameConfig>),
+                    transfer_through_chutes
+                        .run_if(
+                            in_state(AppState::Playing)
+                                .and_then(chained_panels_enabled)
+                                .and_then(on_event::<CollisionEvent>()),
+                        )
+                        .after(earthquake),
+                ),
+            )
+            .add_systems(
+                Update,
+                (run_conveyors, move_gates).run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(
+                Update,
+                sabotage_event.run_if(
+                    in_state(AppState::Playing)
+                        .and_then(ball_sabotage_enabled)
+                        .and_then(on_event::<CollisionEvent>()),
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    bump_worker_balls.run_if(on_event::<CollisionEvent>()),
+                    flash_bumpers,
+                )
+                    .chain(),
+            )
             .add_systems(Update, restart.run_if(on_event::<RestartEvent>()));
     }
 }
@@ -126,12 +306,24 @@ impl Plugin for PanelPlugin {
 pub struct TriggerEvent {
     pub participant: Participant,
     pub trigger_type: TriggerType,
+    /// The trigger zone that was hit, unless the event was made up rather than coming from the
+    /// panels.
+    pub zone: Option<Entity>,
+}
+/// Sent when a worker ball bumps into one of an opponent's, with the ball sabotage rule on.
+#[derive(Debug, Event, Clone, Copy)]
+pub struct SabotageEvent {
+    pub attacker: Participant,
+    pub victim: Participant,
 }
-#[derive(Debug, Component, Clone, Copy)]
+#[derive(Debug, Component, Clone, Copy, Reflect)]
+#[reflect(Component)]
 pub enum TriggerType {
     Multiply(u8),
     BurstShot,
     ChargedShot,
+    /// Takes this percentage off the turret's charge, leaving at least 1.
+    Penalty(u8),
 }
 impl std::fmt::Display for TriggerType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -139,6 +331,7 @@ impl std::fmt::Display for TriggerType {
             Self::Multiply(factor) => write!(f, "x{}", factor),
             Self::BurstShot => write!(f, "Release\nBurst\nShots"),
             Self::ChargedShot => write!(f, "Release\nChanged\nShots"),
+            Self::Penalty(percent) => write!(f, "-{}%", percent),
         }
     }
 }
@@ -195,6 +388,9 @@ struct WorkerBallTrailBundle {
     // {{{
     link: WorkerBallTrail,
     peb: ParticleEffectBundle,
+    /// Trails aren't children of the panel roots, so they have to be put on the panel's render
+    /// layer themselves.
+    render_layers: RenderLayers,
     name: Name,
 }
 impl WorkerBallTrailBundle {
@@ -203,9 +399,11 @@ impl WorkerBallTrailBundle {
         target_x: f32,
         color: impl Into<LinearRgba>,
         effect: Handle<EffectAsset>,
+        side: PanelRootSide,
     ) -> Self {
         Self {
             link: WorkerBallTrail(target),
+            render_layers: panel_render_layers(side),
             peb: ParticleEffectBundle {
                 effect: ParticleEffect::new(effect),
                 effect_properties: EffectProperties::from_spawn_color(color)
@@ -216,33 +414,56 @@ impl WorkerBallTrailBundle {
         }
     }
 }
-#[derive(Component, Clone, Copy, Default)]
+#[derive(Component, Clone, Copy, Default, Reflect)]
+#[reflect(Component)]
 /// Marker to mark this entity as a worker ball.
-struct WorkerBall;
+pub struct WorkerBall;
 #[derive(Resource, Clone, Default)]
 struct WorkerBallSpawner {
     mesh: Mesh2dHandle,
     timer: Timer,
     counter: usize,
+    /// The bonus balls handed out by the ball savers that have yet to be spawned.
+    bonus: ParticipantMap<usize>,
 }
 impl WorkerBallSpawner {
-    fn new(mesh: Mesh2dHandle) -> Self {
-        let mut timer = Timer::from_seconds(WORKER_BALL_SPAWN_TIMER_SECS, TimerMode::Repeating);
-        timer.tick(Duration::from_secs_f32(
-            WORKER_BALL_SPAWN_TIMER_SECS - TRAIL_LIFETIME,
-        ));
-        Self {
+    /// The first worker ball spawns just long enough after the start for the trails to clear.
+    fn new(mesh: Mesh2dHandle, interval: f32) -> Self {
+        let mut spawner = Self {
             mesh,
-            timer,
+            timer: Timer::from_seconds(interval, TimerMode::Repeating),
             counter: 0,
-        }
+            bonus: default(),
+        };
+        spawner.reset(interval);
+        spawner
     }
-    fn reset(&mut self) {
-        self.timer.reset();
+    fn reset(&mut self, interval: f32) {
+        self.timer = Timer::from_seconds(interval, TimerMode::Repeating);
         self.timer.tick(Duration::from_secs_f32(
-            WORKER_BALL_SPAWN_TIMER_SECS - TRAIL_LIFETIME,
+            (interval - TRAIL_LIFETIME).max(0.0),
         ));
         self.counter = 0;
+        self.bonus = default();
+    }
+}
+#[derive(Resource, Clone)]
+struct EarthquakeTimer(Timer);
+impl EarthquakeTimer {
+    fn random(rng: &mut impl Rng) -> Self {
+        let secs = rng.gen_range(EARTHQUAKE_MIN_INTERVAL..=EARTHQUAKE_MAX_INTERVAL);
+        Self(Timer::from_seconds(secs, TimerMode::Once))
+    }
+}
+/// The random numbers for everything in a match besides the worker ball spawns, seeded from the
+/// [`MatchSeed`] so the match plays out the same way every time it's started with that seed.
+#[derive(Resource, Deref, DerefMut)]
+pub struct MatchRng(StdRng);
+impl MatchRng {
+    fn new(seed: u64) -> Self {
+        // Kept apart from the spawn sequences, which are seeded with the match seed itself when
+        // they're mirrored.
+        Self(StdRng::seed_from_u64(seed ^ MATCH_RNG_SEED_MASK))
     }
 }
 #[derive(Bundle, Clone, Default)]
@@ -257,6 +478,9 @@ struct WorkerBallBundle {
     rigidbody: RigidBody,
     velocity: Velocity,
     gravity: GravityScale,
+    /// Needed for bumping into bumpers and other participants' balls to be noticed.
+    active_events: ActiveEvents,
+    trigger_cooldown: TriggerCooldown,
     name: Name,
 }
 impl WorkerBallBundle {
@@ -265,6 +489,7 @@ impl WorkerBallBundle {
         x: f32,
         mesh: Mesh2dHandle,
         material: Handle<ColorMaterial>,
+        gravity_scale: f32,
     ) -> Self {
         Self {
             name: Name::new("Worker Ball"),
@@ -287,24 +512,216 @@ impl WorkerBallBundle {
             },
             rigidbody: RigidBody::Dynamic,
             velocity: Velocity::zero(),
-            gravity: GravityScale(WORKER_BALL_GRAVITY_SCALE),
+            gravity: GravityScale(gravity_scale),
+            active_events: ActiveEvents::COLLISION_EVENTS,
+            trigger_cooldown: TriggerCooldown::default(),
         }
     }
     // }}}
 }
-#[derive(Clone, Copy, Component, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy)]
+struct ConveyorLayout {
+    x: f32,
+    width: f32,
+    speed: f32,
+}
+/// A strip that drags the worker balls going through it sideways.
+#[derive(Component, Clone, Copy)]
+struct Conveyor {
+    half_size: Vec2,
+    speed: f32,
+}
+/// A lid that takes turns covering the burst and charged shot zones, so which kind of release a
+/// ball gets depends on when it lands as well as where.
+#[derive(Component)]
+struct Gate {
+    timer: Timer,
+    covers_burst_shot: bool,
+}
+impl Default for Gate {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(GATE_INTERVAL, TimerMode::Repeating),
+            covers_burst_shot: true,
+        }
+    }
+}
+impl Gate {
+    fn x(&self) -> f32 {
+        let target = if self.covers_burst_shot {
+            -2.0 * ARENA_WIDTH_FRAC_5
+        } else {
+            2.0 * ARENA_WIDTH_FRAC_5
+        };
+        let t = (self.timer.elapsed_secs() / GATE_SLIDE_DURATION).min(1.0);
+        (-target).lerp(target, t * t * (3.0 - 2.0 * t))
+    }
+}
+/// The red wash over a panel after one of its penalty zones was hit.
+#[derive(Component)]
+struct PenaltyFlash(Timer);
+/// A hole in a bottom corner of the panel that worker balls now and then drop through to the other
+/// panel, with [`GameConfig::chained_panels`] on.
+#[derive(Component, Clone, Copy)]
+struct Chute;
+/// A spot in a bottom corner of the panel that gives the owner of a worker ball rolling into it an
+/// extra ball, then takes a while to recharge.
+#[derive(Component)]
+struct BallSaver {
+    cooldown: Timer,
+}
+impl Default for BallSaver {
+    fn default() -> Self {
+        let mut cooldown = Timer::from_seconds(BALL_SAVER_COOLDOWN, TimerMode::Once);
+        cooldown.tick(cooldown.duration());
+        Self { cooldown }
+    }
+}
+/// Marker for worker balls that went through a chute and are away from their own panel.
+#[derive(Component, Clone, Copy)]
+struct Transferred;
+/// The countdown to the gate's next move, shown on top of it.
+#[derive(Component, Clone, Copy)]
+struct GateText;
+/// A pinball-style bumper that kicks worker balls away on contact, rather than just letting them
+/// bounce off.
+#[derive(Component)]
+pub struct Bumper {
+    flash: Timer,
+}
+impl Default for Bumper {
+    fn default() -> Self {
+        let mut flash = Timer::from_seconds(BUMPER_FLASH_DURATION, TimerMode::Once);
+        flash.tick(flash.duration());
+        Self { flash }
+    }
+}
+/// Bumpers switch to the lit material for a moment when they kick a ball.
+#[derive(Resource)]
+struct BumperMaterials {
+    idle: Handle<ColorMaterial>,
+    lit: Handle<ColorMaterial>,
+}
+/// Keeps a worker ball sitting on the edge of a trigger zone from setting it off over and over as
+/// it touches the zone and lets go of it.
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component)]
+struct TriggerCooldown {
+    timer: Timer,
+    /// Whether the ball has been all the way above the trigger zones since it last triggered.
+    armed: bool,
+}
+impl Default for TriggerCooldown {
+    fn default() -> Self {
+        let mut timer = Timer::from_seconds(TRIGGER_COOLDOWN, TimerMode::Once);
+        timer.tick(timer.duration());
+        Self { timer, armed: true }
+    }
+}
+impl TriggerCooldown {
+    fn ready(&self) -> bool {
+        self.armed && self.timer.finished()
+    }
+    fn start(&mut self) {
+        self.armed = false;
+        self.timer.reset();
+    }
+}
+/// The current charge of a participant's turret, shown at the top of their panel so the stakes of
+/// the next trigger are clear without looking at the battlefield.
+#[derive(Component, Clone, Copy)]
+struct ChargePreview(Participant);
+/// The participant's initial on top of their worker balls, so they can be told apart without
+/// relying on color.
+#[derive(Bundle, Clone)]
+struct WorkerBallLabelBundle {
+    text_bundle: Text2dBundle,
+    name: Name,
+}
+impl WorkerBallLabelBundle {
+    fn new(participant: Participant) -> Self {
+        Self {
+            text_bundle: Text2dBundle {
+                text: Text::from_section(
+                    participant.initial(),
+                    TextStyle {
+                        font: default(),
+                        font_size: WORKER_BALL_LABEL_FONT_SIZE,
+                        color: Color::BLACK,
+                    },
+                ),
+                transform: Transform::from_xyz(0.0, 0.0, 0.1)
+                    .with_scale(Vec3::splat(WORKER_BALL_LABEL_SCALE)),
+                ..default()
+            },
+            name: Name::new("Worker Ball Label"),
+        }
+    }
+}
+#[derive(Debug, Clone, Copy, Component, PartialEq, Eq)]
 pub enum PanelRootSide {
     Left,
     Right,
 }
 impl PanelRootSide {
-    fn for_participant(p: Participant) -> Self {
+    fn root_position(self, layout: ScreenLayout) -> Vec2 {
+        match (layout, self) {
+            (ScreenLayout::Landscape, Self::Left) => Vec2::new(LEFT_ROOT_X, 0.0),
+            (ScreenLayout::Landscape, Self::Right) => Vec2::new(RIGHT_ROOT_X, 0.0),
+            (ScreenLayout::Portrait, Self::Left) => Vec2::new(-PORTRAIT_ROOT_X, PORTRAIT_ROOT_Y),
+            (ScreenLayout::Portrait, Self::Right) => Vec2::new(PORTRAIT_ROOT_X, PORTRAIT_ROOT_Y),
+        }
+    }
+    /// Where unused worker ball trails are parked, out of sight above the panel.
+    fn trail_parking_position(self, layout: ScreenLayout) -> Vec3 {
+        (self.root_position(layout) + Vec2::new(0.0, WORKER_BALL_SPAWN_Y)).extend(0.0)
+    }
+    pub fn for_participant(p: Participant) -> Self {
         match p {
             Participant::A | Participant::B => Self::Left,
             Participant::C | Participant::D => Self::Right,
         }
     }
 }
+/// The random number generators used to pick where each participant's worker balls spawn.
+#[derive(Resource)]
+struct SpawnRng(ParticipantMap<StdRng>);
+impl SpawnRng {
+    /// When `mirrored`, every participant gets the same sequence of spawn positions (flipped
+    /// horizontally for the second participant of each panel so the two don't overlap).
+    fn new(mirrored: bool, seed: u64) -> Self {
+        let mut seeds = StdRng::seed_from_u64(seed);
+        let mut rng = || {
+            if mirrored {
+                StdRng::seed_from_u64(seed)
+            } else {
+                StdRng::seed_from_u64(seeds.gen())
+            }
+        };
+        Self(ParticipantMap::new(rng(), rng(), rng(), rng()))
+    }
+    fn sample_x(
+        &mut self,
+        participant: Participant,
+        root_position: Vec2,
+        rapier: &RapierContext,
+        collider: &Collider,
+    ) -> f32 {
+        let sign = match participant {
+            Participant::A | Participant::C => 1.0,
+            Participant::B | Participant::D => -1.0,
+        };
+        WorkerBallShapeCaster::new(
+            root_position,
+            Uniform::new(-ARENA_WIDTH_FRAC_2, ARENA_WIDTH_FRAC_2),
+            rapier,
+            collider,
+            &mut self.0[participant],
+            sign,
+        )
+        .get()
+    }
+}
 #[derive(Component, Clone, Copy)]
 pub struct PanelRoot(PanelRootSide);
 #[derive(Bundle)]
@@ -396,19 +813,39 @@ impl ObstacleBundleBuilder {
     // }}}
 }
 
+/// Gives the match the random number generators a new match with the seed starts out with.
+pub fn seed_match_rngs(commands: &mut Commands, mirror_spawns: bool, seed: u64) {
+    commands.insert_resource(SpawnRng::new(mirror_spawns, seed));
+    let mut rng = MatchRng::new(seed);
+    commands.insert_resource(EarthquakeTimer::random(&mut *rng));
+    commands.insert_resource(rng);
+}
 fn setup(
     mut commands: Commands,
     mut materials: ResMut<Assets<ColorMaterial>>,
     mut meshes: ResMut<Assets<Mesh>>,
+    config: Res<GameConfig>,
+    display_config: Res<DisplayConfig>,
+    tuning: Res<Tuning>,
+    colors: Res<ParticipantMap<TileColor>>,
 ) {
-    commands.insert_resource(WorkerBallSpawner::new(Mesh2dHandle(
-        meshes.add(Circle::new(WORKER_BALL_RADIUS)),
-    )));
+    let layout = display_config.layout;
+    let seed = config.seed.unwrap_or_else(|| thread_rng().gen());
+    commands.insert_resource(MatchSeed(seed));
+    seed_match_rngs(&mut commands, config.mirror_spawns, seed);
+    commands.insert_resource(WorkerBallSpawner::new(
+        Mesh2dHandle(meshes.add(Circle::new(WORKER_BALL_RADIUS))),
+        tuning.worker_ball_spawn_interval,
+    ));
     let left_root = commands
         .spawn((
             Name::new("Left Panel Root"),
             PanelRoot(PanelRootSide::Left),
-            SpatialBundle::from_transform(Transform::from_xyz(LEFT_ROOT_X, 0.0, 0.0)),
+            panel_render_layers(PanelRootSide::Left),
+            PropagateRenderLayers,
+            SpatialBundle::from_transform(Transform::from_translation(
+                PanelRootSide::Left.root_position(layout).extend(0.0),
+            )),
             RigidBody::Fixed,
             CollisionGroups::new(
                 collision_groups::PANEL_OBSTACLES,
@@ -430,7 +867,11 @@ fn setup(
         .spawn((
             Name::new("Right Panel Root"),
             PanelRoot(PanelRootSide::Right),
-            SpatialBundle::from_transform(Transform::from_xyz(RIGHT_ROOT_X, 0.0, 0.0)),
+            panel_render_layers(PanelRootSide::Right),
+            PropagateRenderLayers,
+            SpatialBundle::from_transform(Transform::from_translation(
+                PanelRootSide::Right.root_position(layout).extend(0.0),
+            )),
             RigidBody::Fixed,
             CollisionGroups::new(
                 collision_groups::PANEL_OBSTACLES,
@@ -466,7 +907,75 @@ fn setup(
             TRIGGER_ZONE_DIVIDER_RADIUS,
         ));
 
+    let bumper_materials = BumperMaterials {
+        idle: materials.add(BUMPER_COLOR),
+        lit: materials.add(BUMPER_LIT_COLOR),
+    };
+    let bumper_builder = ObstacleBundleBuilder::new()
+        .name("Bumper")
+        .z(BUMPER_Z)
+        .material(bumper_materials.idle.clone())
+        .mesh(meshes.add(Circle::new(BUMPER_RADIUS)))
+        .collider(Collider::ball(BUMPER_RADIUS));
+    commands.insert_resource(bumper_materials);
+
+    let gate_builder = ObstacleBundleBuilder::new()
+        .name("Gate")
+        .z(GATE_Z)
+        .material(materials.add(GATE_COLOR))
+        .mesh(meshes.add(Rectangle::new(ARENA_WIDTH_FRAC_5, GATE_HEIGHT)))
+        .collider(Collider::cuboid(
+            ARENA_WIDTH_FRAC_5 / 2.0,
+            GATE_HEIGHT / 2.0,
+        ));
+
+    let zone_width_scale = ARENA_WIDTH
+        / tuning
+            .trigger_zones
+            .iter()
+            .map(|zone| zone.width)
+            .sum::<f32>();
+    // The zone that multiplies the most stands out from the other Multiply zones.
+    let strongest_multiply = tuning
+        .trigger_zones
+        .iter()
+        .filter_map(|zone| match zone.effect {
+            ZoneEffect::Multiply(factor) => Some(factor),
+            _ => None,
+        })
+        .max();
     let mut f = |root: Entity| {
+        let gate = Gate::default();
+        commands
+            .spawn((gate_builder.clone().xy(gate.x(), GATE_Y).buildtmb(), gate))
+            .insert(RigidBody::KinematicPositionBased)
+            .with_children(|parent| {
+                parent.spawn((
+                    GateText,
+                    Text2dBundle {
+                        text: Text::from_section(
+                            "",
+                            TextStyle {
+                                color: GATE_TEXT_COLOR,
+                                font_size: TRIGGER_ZONE_TEXT_SIZE,
+                                ..default()
+                            },
+                        ),
+                        transform: Transform::from_xyz(0.0, GATE_TEXT_OFFSET_Y, 0.1),
+                        ..default()
+                    },
+                ));
+            })
+            .set_parent(root);
+        for x in [-BUMPER_X, BUMPER_X] {
+            commands
+                .spawn((
+                    bumper_builder.clone().xy(x, BUMPER_Y).buildtmb(),
+                    Bumper::default(),
+                    ActiveEvents::COLLISION_EVENTS,
+                ))
+                .set_parent(root);
+        }
         for i in 0..CIRCLE_PYRAMID_VERTICAL_COUNT {
             let y = -(i as f32) * (CIRCLE_DIAMETER + CIRCLE_PYRAMID_VERTICAL_GAP)
                 + CIRCLE_PYRAMID_VERTICAL_OFFSET;
@@ -541,47 +1050,30 @@ fn setup(
             }
         }
 
-        commands
-            .spawn(
-                divider_builder
-                    .clone()
-                    .xy(-ARENA_WIDTH_FRAC_10, TRIGGER_ZONE_Y)
-                    .buildtmb(),
-            )
-            .set_parent(root);
-        commands
-            .spawn(
-                divider_builder
-                    .clone()
-                    .xy(-ARENA_WIDTH_FRAC_5 - ARENA_WIDTH_FRAC_10, TRIGGER_ZONE_Y)
-                    .buildtmb(),
-            )
-            .set_parent(root);
-        commands
-            .spawn(
-                divider_builder
-                    .clone()
-                    .xy(ARENA_WIDTH_FRAC_10, TRIGGER_ZONE_Y)
-                    .buildtmb(),
-            )
-            .set_parent(root);
-        commands
-            .spawn(
-                divider_builder
-                    .clone()
-                    .xy(ARENA_WIDTH_FRAC_5 + ARENA_WIDTH_FRAC_10, TRIGGER_ZONE_Y)
-                    .buildtmb(),
-            )
-            .set_parent(root);
-        let mut f = |trigger_type, x, color| {
-            commands
-                .spawn(TriggerZoneBundle::new(
-                    trigger_type,
-                    Vec2::new(ARENA_WIDTH_FRAC_5, TRIGGER_ZONE_HEIGHT),
-                    Vec3::new(x, TRIGGER_ZONE_Y, TRIGGER_ZONE_Z),
-                    color,
-                ))
-                .set_parent(root);
+        let mut left = -ARENA_WIDTH_FRAC_2;
+        for (i, zone) in tuning.trigger_zones.iter().enumerate() {
+            let width = zone.width * zone_width_scale;
+            let x = left + width / 2.0;
+            left += width;
+            let trigger_type = zone.effect.trigger_type();
+            let color = match zone.effect {
+                ZoneEffect::Multiply(factor) if Some(factor) == strongest_multiply => {
+                    TRIGGER_ZONE_COLOR_0
+                }
+                ZoneEffect::Multiply(_) => TRIGGER_ZONE_COLOR_1,
+                ZoneEffect::BurstShot(_) | ZoneEffect::ChargedShot(_) => TRIGGER_ZONE_COLOR_2,
+                ZoneEffect::Penalty(_) => PENALTY_ZONE_COLOR,
+            };
+            let mut zone_entity = commands.spawn(TriggerZoneBundle::new(
+                trigger_type,
+                Vec2::new(width, TRIGGER_ZONE_HEIGHT),
+                Vec3::new(x, TRIGGER_ZONE_Y, TRIGGER_ZONE_Z),
+                color,
+            ));
+            if let ZoneEffect::BurstShot(release) | ZoneEffect::ChargedShot(release) = zone.effect {
+                zone_entity.insert(release);
+            }
+            zone_entity.set_parent(root);
             commands
                 .spawn(Text2dBundle {
                     text: Text::from_section(
@@ -605,28 +1097,88 @@ fn setup(
                 })
                 .insert(Name::new(format!("Trigger Zone Text: {}", trigger_type)))
                 .set_parent(root);
-        };
-        f(TriggerType::Multiply(4), 0.0, TRIGGER_ZONE_COLOR_0);
-        f(
-            TriggerType::Multiply(2),
-            -ARENA_WIDTH_FRAC_5,
-            TRIGGER_ZONE_COLOR_1,
-        );
-        f(
-            TriggerType::Multiply(2),
-            ARENA_WIDTH_FRAC_5,
-            TRIGGER_ZONE_COLOR_1,
-        );
-        f(
-            TriggerType::BurstShot,
-            -2.0 * ARENA_WIDTH_FRAC_5,
-            TRIGGER_ZONE_COLOR_2,
-        );
-        f(
-            TriggerType::ChargedShot,
-            2.0 * ARENA_WIDTH_FRAC_5,
-            TRIGGER_ZONE_COLOR_2,
-        );
+            if i + 1 < tuning.trigger_zones.len() {
+                commands
+                    .spawn(divider_builder.clone().xy(left, TRIGGER_ZONE_Y).buildtmb())
+                    .set_parent(root);
+            }
+        }
+        for x in [-CHUTE_X, CHUTE_X] {
+            commands
+                .spawn((
+                    Name::new("Transfer Chute"),
+                    Chute,
+                    SpriteBundle {
+                        transform: Transform::from_xyz(x, CHUTE_Y, CHUTE_Z),
+                        sprite: Sprite {
+                            color: CHUTE_COLOR,
+                            custom_size: Some(Vec2::splat(CHUTE_SIZE)),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    Collider::cuboid(CHUTE_SIZE / 2.0, CHUTE_SIZE / 2.0),
+                    CollisionGroups::new(PANEL_TRIGGER_ZONES, collision_groups::PANEL_BALLS),
+                    ActiveEvents::COLLISION_EVENTS,
+                    Sensor,
+                ))
+                .set_parent(root);
+        }
+        for x in [-BALL_SAVER_X, BALL_SAVER_X] {
+            commands
+                .spawn((
+                    Name::new("Ball Saver"),
+                    BallSaver::default(),
+                    SpriteBundle {
+                        transform: Transform::from_xyz(x, BALL_SAVER_Y, BALL_SAVER_Z),
+                        sprite: Sprite {
+                            color: BALL_SAVER_COLOR,
+                            custom_size: Some(Vec2::splat(BALL_SAVER_SIZE)),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    Collider::cuboid(BALL_SAVER_SIZE / 2.0, BALL_SAVER_SIZE / 2.0),
+                    CollisionGroups::new(PANEL_TRIGGER_ZONES, collision_groups::PANEL_BALLS),
+                    ActiveEvents::COLLISION_EVENTS,
+                    Sensor,
+                ))
+                .set_parent(root);
+        }
+        for conveyor in CONVEYORS {
+            commands
+                .spawn((
+                    Name::new("Conveyor"),
+                    Conveyor {
+                        half_size: Vec2::new(conveyor.width, CONVEYOR_HEIGHT) / 2.0,
+                        speed: conveyor.speed,
+                    },
+                    SpriteBundle {
+                        transform: Transform::from_xyz(conveyor.x, CONVEYOR_Y, CONVEYOR_Z),
+                        sprite: Sprite {
+                            color: CONVEYOR_COLOR,
+                            custom_size: Some(Vec2::new(conveyor.width, CONVEYOR_HEIGHT)),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                ))
+                .with_children(|parent| {
+                    parent.spawn(Text2dBundle {
+                        text: Text::from_section(
+                            if conveyor.speed > 0.0 { ">>>" } else { "<<<" },
+                            TextStyle {
+                                color: CONVEYOR_TEXT_COLOR,
+                                font_size: TRIGGER_ZONE_TEXT_SIZE,
+                                ..default()
+                            },
+                        ),
+                        transform: Transform::from_xyz(0.0, 0.0, 0.1),
+                        ..default()
+                    });
+                })
+                .set_parent(root);
+        }
 
         commands
             .spawn(SpriteBundle {
@@ -661,6 +1213,37 @@ fn setup(
     };
     f(left_root);
     f(right_root);
+    for participant in Participant::ALL {
+        let (root, x) = match participant {
+            Participant::A => (left_root, -ARENA_WIDTH_FRAC_2 / 2.0),
+            Participant::B => (left_root, ARENA_WIDTH_FRAC_2 / 2.0),
+            Participant::C => (right_root, -ARENA_WIDTH_FRAC_2 / 2.0),
+            Participant::D => (right_root, ARENA_WIDTH_FRAC_2 / 2.0),
+        };
+        commands
+            .spawn((
+                Name::new(format!("Charge Preview: {}", participant)),
+                ChargePreview(participant),
+                Text2dBundle {
+                    text: Text::from_section(
+                        "",
+                        TextStyle {
+                            font: default(),
+                            font_size: CHARGE_PREVIEW_FONT_SIZE,
+                            color: colors[participant].0,
+                        },
+                    )
+                    .with_justify(JustifyText::Center),
+                    transform: Transform::from_xyz(
+                        x,
+                        ARENA_HEIGHT_FRAC_2 - CHARGE_PREVIEW_OFFSET_Y,
+                        CHARGE_PREVIEW_Z,
+                    ),
+                    ..default()
+                },
+            ))
+            .set_parent(root);
+    }
 }
 fn spawn_workers_condition(spawner: Res<WorkerBallSpawner>) -> bool {
     spawner.counter < WORKER_BALL_COUNT_MAX
@@ -670,60 +1253,73 @@ fn spawn_workers(
     mut spawner: ResMut<WorkerBallSpawner>,
     time: Res<Time>,
     rapier: Res<RapierContext>,
+    mut spawn_rng: ResMut<SpawnRng>,
     materials: Res<ParticipantMap<Handle<ColorMaterial>>>,
     colors: Res<ParticipantMap<TileColor>>,
     survivors: Res<ParticipantMap<bool>>,
+    mutators: Res<MutatorEffects>,
     root: Query<(Entity, &GlobalTransform, &PanelRoot)>,
-    effect: Res<TrailEffect>,
+    effect: Option<Res<TrailEffect>>,
+    budget: Res<EntityBudget>,
     mut trail_query: Query<(Entity, &mut EffectProperties, &InactiveWorkerBallTrail)>,
 ) {
     spawner.timer.tick(time.delta());
     if !spawner.timer.just_finished() {
         return;
     }
+    let effect = effect.filter(|_| budget.trails);
     // TODO: handle trail effect
     let mut f = |a, b, root_entity, root_transform: &GlobalTransform, want_left| {
+        let side = if want_left {
+            PanelRootSide::Left
+        } else {
+            PanelRootSide::Right
+        };
         let root_translation = root_transform.translation();
         let collider = Collider::ball(WORKER_BALL_RADIUS);
-        let mut caster = WorkerBallShapeCaster::new(
-            root_translation.xy(),
-            Uniform::new(-ARENA_WIDTH_FRAC_2, ARENA_WIDTH_FRAC_2),
-            &rapier,
-            &collider,
-        );
+        let mut sample_x = |participant: Participant| {
+            spawn_rng.sample_x(participant, root_translation.xy(), &rapier, &collider)
+        };
         match (survivors[a].then_some(a), survivors[b].then_some(b)) {
             (None, None) => (),
             (Some(survivor), None) | (None, Some(survivor)) => {
-                let x = caster.get();
+                let x = sample_x(survivor);
                 let ball = commands
                     .spawn(WorkerBallBundle::new(
                         survivor,
                         x,
                         spawner.mesh.clone(),
                         materials.get(survivor).clone(),
+                        WORKER_BALL_GRAVITY_SCALE * mutators.worker_gravity_factor,
                     ))
+                    .with_children(|ball| {
+                        ball.spawn(WorkerBallLabelBundle::new(survivor));
+                    })
                     .set_parent(root_entity)
                     .id();
-                commands.spawn(WorkerBallTrailBundle::new(
-                    ball,
-                    x + root_translation.x,
-                    colors.get(survivor).0,
-                    effect.0.clone(),
-                ));
+                if let Some(effect) = &effect {
+                    commands.spawn(WorkerBallTrailBundle::new(
+                        ball,
+                        x + root_translation.x,
+                        colors.get(survivor).0,
+                        effect.0.clone(),
+                        side,
+                    ));
+                }
             }
             (Some(a), Some(b)) => {
                 let mut xa;
                 let mut xb;
                 loop {
-                    xa = caster.get();
-                    xb = caster.get();
+                    xa = sample_x(a);
+                    xb = sample_x(b);
                     if (xa - xb).abs() > WORKER_BALL_DIAMETER {
                         break;
                     }
                 }
                 let mut trail_query_iter = trail_query.iter_mut().filter_map(
                     |(e, p, &InactiveWorkerBallTrail(is_left))| {
-                        (is_left == want_left).then_some((e, p))
+                        (budget.trails && is_left == want_left).then_some((e, p))
                     },
                 );
                 let mut setup_trail = |participant, x| {
@@ -733,7 +1329,11 @@ fn spawn_workers(
                             x,
                             spawner.mesh.clone(),
                             materials.get(participant).clone(),
+                            WORKER_BALL_GRAVITY_SCALE * mutators.worker_gravity_factor,
                         ))
+                        .with_children(|ball| {
+                            ball.spawn(WorkerBallLabelBundle::new(participant));
+                        })
                         .set_parent(root_entity)
                         .id();
                     if let Some((trail_entity, mut trail_properties)) = trail_query_iter.next() {
@@ -747,12 +1347,13 @@ fn spawn_workers(
                             WORKER_BALL_SPAWN_Y,
                             0.0,
                         ));
-                    } else {
+                    } else if let Some(effect) = &effect {
                         commands.spawn(WorkerBallTrailBundle::new(
                             ball,
                             x + root_translation.x,
                             colors.get(participant).0,
                             effect.0.clone(),
+                            side,
                         ));
                     }
                 };
@@ -785,10 +1386,68 @@ fn spawn_workers(
     );
     spawner.counter += 1;
 }
+fn bonus_workers_pending(spawner: Res<WorkerBallSpawner>) -> bool {
+    Participant::ALL
+        .into_iter()
+        .any(|participant| spawner.bonus[participant] > 0)
+}
+/// Bonus balls spawn in the participant's own panel, whichever panel the ball that earned them was
+/// in.
+fn spawn_bonus_workers(
+    mut commands: Commands,
+    mut spawner: ResMut<WorkerBallSpawner>,
+    rapier: Res<RapierContext>,
+    mut spawn_rng: ResMut<SpawnRng>,
+    materials: Res<ParticipantMap<Handle<ColorMaterial>>>,
+    colors: Res<ParticipantMap<TileColor>>,
+    mutators: Res<MutatorEffects>,
+    root_query: Query<(Entity, &GlobalTransform, &PanelRoot)>,
+    effect: Option<Res<TrailEffect>>,
+    budget: Res<EntityBudget>,
+) {
+    let effect = effect.filter(|_| budget.trails);
+    let collider = Collider::ball(WORKER_BALL_RADIUS);
+    for participant in Participant::ALL {
+        let side = PanelRootSide::for_participant(participant);
+        let (root_entity, root_transform) = root_query
+            .iter()
+            .find_map(|(entity, transform, &PanelRoot(root_side))| {
+                (root_side == side).then_some((entity, transform))
+            })
+            .expect(EXPECT_EACH_PANEL_SIDE_EXIST_MSG);
+        let root_translation = root_transform.translation();
+        for _ in 0..std::mem::take(&mut spawner.bonus[participant]) {
+            let x = spawn_rng.sample_x(participant, root_translation.xy(), &rapier, &collider);
+            let ball = commands
+                .spawn(WorkerBallBundle::new(
+                    participant,
+                    x,
+                    spawner.mesh.clone(),
+                    materials.get(participant).clone(),
+                    WORKER_BALL_GRAVITY_SCALE * mutators.worker_gravity_factor,
+                ))
+                .with_children(|ball| {
+                    ball.spawn(WorkerBallLabelBundle::new(participant));
+                })
+                .set_parent(root_entity)
+                .id();
+            if let Some(effect) = &effect {
+                commands.spawn(WorkerBallTrailBundle::new(
+                    ball,
+                    x + root_translation.x,
+                    colors.get(participant).0,
+                    effect.0.clone(),
+                    side,
+                ));
+            }
+        }
+    }
+}
 fn update_workers_particle_position(
     mut commands: Commands,
     mut query: Query<((Entity, &WorkerBallTrail), &mut EffectProperties)>,
     transform_query: Query<&GlobalTransform>,
+    config: Res<DisplayConfig>,
     mut go_left: Local<bool>,
 ) {
     for ((trail_entity, &WorkerBallTrail(ball_entity)), mut properties) in &mut query {
@@ -801,9 +1460,13 @@ fn update_workers_particle_position(
                 .entity(trail_entity)
                 .insert(InactiveWorkerBallTrail(*go_left))
                 .remove::<WorkerBallTrail>();
-            let x = if *go_left { LEFT_ROOT_X } else { RIGHT_ROOT_X };
+            let side = if *go_left {
+                PanelRootSide::Left
+            } else {
+                PanelRootSide::Right
+            };
             properties.set_spawn_color(LinearRgba::NONE);
-            properties.set_position(Vec3::new(x, WORKER_BALL_SPAWN_Y, 0.0));
+            properties.set_position(side.trail_parking_position(config.layout));
             *go_left = !*go_left;
         }
     }
@@ -813,7 +1476,8 @@ fn trigger_event(
     mut restart_event: EventReader<RestartEvent>,
     mut trigger_event: EventWriter<TriggerEvent>,
     trigger_zone_query: Query<&TriggerType>,
-    worker_ball_query: Query<&Participant, With<WorkerBall>>,
+    mut worker_ball_query: Query<(&Participant, &mut TriggerCooldown), With<WorkerBall>>,
+    survivors: Res<ParticipantMap<bool>>,
 ) {
     if !restart_event.is_empty() {
         collision_events.clear();
@@ -822,38 +1486,170 @@ fn trigger_event(
     for collision_event in collision_events.read() {
         match collision_event {
             &CollisionEvent::Started(a, b, _) => {
-                let &trigger_type = if let Ok(x) = trigger_zone_query.get(a) {
-                    x
+                let (zone, &trigger_type) = if let Ok(x) = trigger_zone_query.get(a) {
+                    (a, x)
                 } else if let Ok(x) = trigger_zone_query.get(b) {
-                    x
+                    (b, x)
                 } else {
                     continue;
                 };
-                let &participant = if let Ok(x) = worker_ball_query.get(a) {
-                    x
-                } else if let Ok(x) = worker_ball_query.get(b) {
-                    x
-                } else {
+                let ball = if zone == a { b } else { a };
+                // Goes by the ball rather than the panel, since balls that went through a chute
+                // keep triggering for their owner.
+                let Ok((&participant, mut cooldown)) = worker_ball_query.get_mut(ball) else {
                     continue;
                 };
+                // The ball may still be around for a frame after its turret was eliminated.
+                if !survivors[participant] || !cooldown.ready() {
+                    continue;
+                }
+                cooldown.start();
                 trigger_event.send(TriggerEvent {
                     participant,
                     trigger_type,
+                    zone: Some(zone),
                 });
             }
             CollisionEvent::Stopped(_, _, _) => (),
         }
     }
 }
+fn run_conveyors(
+    time: Res<Time>,
+    conveyor_query: Query<(&Conveyor, &Transform, &Parent)>,
+    mut worker_ball_query: Query<(&mut Velocity, &Transform, &Parent), With<WorkerBall>>,
+) {
+    let grip = (CONVEYOR_GRIP * time.delta_seconds()).min(1.0);
+    for (mut velocity, ball_transform, ball_parent) in &mut worker_ball_query {
+        for (conveyor, conveyor_transform, conveyor_parent) in &conveyor_query {
+            let offset = ball_transform.translation.xy() - conveyor_transform.translation.xy();
+            if ball_parent == conveyor_parent && offset.abs().cmple(conveyor.half_size).all() {
+                velocity.linvel.x += (conveyor.speed - velocity.linvel.x) * grip;
+            }
+        }
+    }
+}
+fn move_gates(
+    time: Res<Time>,
+    mut gate_query: Query<(&mut Gate, &mut Transform, &Children)>,
+    mut text_query: Query<&mut Text, With<GateText>>,
+) {
+    for (mut gate, mut transform, children) in &mut gate_query {
+        if gate.timer.tick(time.delta()).just_finished() {
+            gate.covers_burst_shot = !gate.covers_burst_shot;
+        }
+        transform.translation.x = gate.x();
+        let countdown = gate.timer.remaining_secs().ceil().to_string();
+        let mut texts = text_query.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            if text.sections[0].value != countdown {
+                text.sections[0].value.clone_from(&countdown);
+            }
+        }
+    }
+}
+fn bump_worker_balls(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut bumper_query: Query<(&mut Bumper, &GlobalTransform)>,
+    mut worker_ball_query: Query<(&mut Velocity, &GlobalTransform), With<WorkerBall>>,
+) {
+    for collision_event in collision_events.read() {
+        let &CollisionEvent::Started(a, b, _) = collision_event else {
+            continue;
+        };
+        let (bumper_entity, ball_entity) = if bumper_query.contains(a) {
+            (a, b)
+        } else if bumper_query.contains(b) {
+            (b, a)
+        } else {
+            continue;
+        };
+        let (Ok((mut bumper, bumper_transform)), Ok((mut velocity, ball_transform))) = (
+            bumper_query.get_mut(bumper_entity),
+            worker_ball_query.get_mut(ball_entity),
+        ) else {
+            continue;
+        };
+        let direction = (ball_transform.translation() - bumper_transform.translation())
+            .xy()
+            .normalize_or_zero();
+        velocity.linvel += direction * BUMPER_IMPULSE;
+        bumper.flash.reset();
+    }
+}
+fn flash_bumpers(
+    time: Res<Time>,
+    materials: Res<BumperMaterials>,
+    mut bumper_query: Query<(&mut Bumper, &mut Handle<ColorMaterial>)>,
+) {
+    for (mut bumper, mut material) in &mut bumper_query {
+        bumper.flash.tick(time.delta());
+        let target = if bumper.flash.finished() {
+            &materials.idle
+        } else {
+            &materials.lit
+        };
+        if *material != *target {
+            *material = target.clone();
+        }
+    }
+}
+fn rearm_trigger_cooldowns(
+    time: Res<Time>,
+    mut worker_ball_query: Query<(&mut TriggerCooldown, &Transform), With<WorkerBall>>,
+) {
+    for (mut cooldown, transform) in &mut worker_ball_query {
+        cooldown.timer.tick(time.delta());
+        if transform.translation.y - WORKER_BALL_RADIUS > TRIGGER_LINE_Y {
+            cooldown.armed = true;
+        }
+    }
+}
+fn ball_sabotage_enabled(config: Res<GameConfig>) -> bool {
+    config.ball_sabotage
+}
+fn sabotage_event(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut sabotage_events: EventWriter<SabotageEvent>,
+    worker_ball_query: Query<&Participant, With<WorkerBall>>,
+) {
+    for collision_event in collision_events.read() {
+        let &CollisionEvent::Started(a, b, _) = collision_event else {
+            continue;
+        };
+        let (Ok(&a), Ok(&b)) = (worker_ball_query.get(a), worker_ball_query.get(b)) else {
+            continue;
+        };
+        if a != b {
+            sabotage_events.send(SabotageEvent {
+                attacker: a,
+                victim: b,
+            });
+            sabotage_events.send(SabotageEvent {
+                attacker: b,
+                victim: a,
+            });
+        }
+    }
+}
 fn ball_reset(
+    mut commands: Commands,
     mut collision_events: EventReader<CollisionEvent>,
     rapier: Res<RapierContext>,
-    root_query: Query<(&GlobalTransform, &PanelRoot)>,
+    mut spawn_rng: ResMut<SpawnRng>,
+    root_query: Query<(Entity, &GlobalTransform, &PanelRoot)>,
     trigger_zone_query: Query<(), With<TriggerType>>,
     mut worker_ball_query: Query<
-        (&mut Transform, &mut Velocity, &Collider, &Participant),
+        (
+            &mut Transform,
+            &mut Velocity,
+            &Collider,
+            &Participant,
+            Has<Transferred>,
+        ),
         With<WorkerBall>,
     >,
+    trail_query: Query<(Entity, &WorkerBallTrail)>,
 ) {
     for collision_event in collision_events.read() {
         match collision_event {
@@ -866,26 +1662,27 @@ fn ball_reset(
                 } else {
                     continue;
                 };
-                let Ok((mut ball_transform, mut velocity, collider, &participant)) =
+                let Ok((mut ball_transform, mut velocity, collider, &participant, transferred)) =
                     worker_ball_query.get_mut(ball_entity)
                 else {
                     continue;
                 };
 
                 let target_side = PanelRootSide::for_participant(participant);
-                let root = root_query
+                let (root_entity, root) = root_query
                     .into_iter()
-                    .find_map(|(transform, &PanelRoot(side))| {
-                        (side == target_side).then_some(transform)
+                    .find_map(|(entity, transform, &PanelRoot(side))| {
+                        (side == target_side).then_some((entity, transform))
                     })
                     .expect(EXPECT_EACH_PANEL_SIDE_EXIST_MSG);
-                let x = WorkerBallShapeCaster::new(
-                    root.translation().xy(),
-                    Uniform::new(-ARENA_WIDTH_FRAC_2, ARENA_WIDTH_FRAC_2),
-                    &rapier,
-                    collider,
-                )
-                .get();
+                if transferred {
+                    commands
+                        .entity(ball_entity)
+                        .set_parent(root_entity)
+                        .remove::<Transferred>();
+                    move_trail(&mut commands, &trail_query, ball_entity, target_side);
+                }
+                let x = spawn_rng.sample_x(participant, root.translation().xy(), &rapier, collider);
                 ball_transform.translation.x = x;
                 ball_transform.translation.y = WORKER_BALL_SPAWN_Y;
                 *velocity = Velocity::zero();
@@ -893,28 +1690,35 @@ fn ball_reset(
         }
     }
 }
-struct WorkerBallShapeCaster<'a, 'b, D> {
+struct WorkerBallShapeCaster<'a, 'b, 'c, D> {
     root_position: Vec2,
-    rng_iter: DistIter<D, ThreadRng, f32>,
+    rng_iter: DistIter<D, &'c mut StdRng, f32>,
+    /// Multiplied with every sampled position, to mirror the positions of one of the participants
+    /// sharing a panel.
+    sign: f32,
     rapier: &'a RapierContext,
     collider: &'b Collider,
 }
-impl<'a, 'b, D: Distribution<f32>> WorkerBallShapeCaster<'a, 'b, D> {
+impl<'a, 'b, 'c, D: Distribution<f32>> WorkerBallShapeCaster<'a, 'b, 'c, D> {
     fn new(
         root_position: Vec2,
         dist: D,
         rapier: &'a RapierContext,
         collider: &'b Collider,
+        rng: &'c mut StdRng,
+        sign: f32,
     ) -> Self {
         Self {
             root_position,
-            rng_iter: thread_rng().sample_iter(dist),
+            rng_iter: rng.sample_iter(dist),
+            sign,
             rapier,
             collider,
         }
     }
     fn get(&mut self) -> f32 {
         for x in &mut self.rng_iter {
+            let x = x * self.sign;
             if self
                 .rapier
                 .intersection_with_shape(
@@ -937,21 +1741,301 @@ impl<'a, 'b, D: Distribution<f32>> WorkerBallShapeCaster<'a, 'b, D> {
         unreachable!("`self.rng_iter: DistIter` is an infinite iterator.");
     }
 }
+fn earthquakes_enabled(config: Res<GameConfig>) -> bool {
+    config.earthquakes
+}
+fn earthquake(
+    mut timer: ResMut<EarthquakeTimer>,
+    mut shake: ResMut<ScreenShake>,
+    mut worker_ball_query: Query<&mut Velocity, With<WorkerBall>>,
+    mut rng: ResMut<MatchRng>,
+    time: Res<Time>,
+) {
+    timer.0.tick(time.delta());
+    if !timer.0.just_finished() {
+        return;
+    }
+    *timer = EarthquakeTimer::random(&mut **rng);
+    for mut velocity in &mut worker_ball_query {
+        velocity.linvel +=
+            Vec2::new(rng.gen_range(-1.0..=1.0), rng.gen_range(0.5..=1.0)) * EARTHQUAKE_IMPULSE;
+    }
+    shake.start(EARTHQUAKE_SHAKE_DURATION, EARTHQUAKE_SHAKE_INTENSITY);
+}
+/// Washes the panel the penalty zone was in with red and shakes the screen. Made up penalties go
+/// by the participant's own panel.
+fn flash_penalties(
+    mut commands: Commands,
+    mut events: EventReader<TriggerEvent>,
+    mut shake: ResMut<ScreenShake>,
+    zone_query: Query<&Parent, With<TriggerType>>,
+    root_query: Query<(Entity, &PanelRoot)>,
+) {
+    for event in events.read() {
+        if !matches!(event.trigger_type, TriggerType::Penalty(_)) {
+            continue;
+        }
+        let side = PanelRootSide::for_participant(event.participant);
+        let root = event
+            .zone
+            .and_then(|zone| zone_query.get(zone).ok())
+            .map(Parent::get)
+            .or_else(|| {
+                root_query
+                    .iter()
+                    .find_map(|(entity, &PanelRoot(root_side))| {
+                        (root_side == side).then_some(entity)
+                    })
+            });
+        let Some(root) = root else {
+            continue;
+        };
+        commands
+            .spawn((
+                Name::new("Penalty Flash"),
+                PenaltyFlash(Timer::from_seconds(PENALTY_FLASH_DURATION, TimerMode::Once)),
+                SpriteBundle {
+                    transform: Transform::from_xyz(0.0, 0.0, PENALTY_FLASH_Z),
+                    sprite: Sprite {
+                        color: PENALTY_FLASH_COLOR,
+                        custom_size: Some(Vec2::new(ARENA_WIDTH, ARENA_HEIGHT)),
+                        ..default()
+                    },
+                    ..default()
+                },
+            ))
+            .set_parent(root);
+        shake.start(PENALTY_SHAKE_DURATION, PENALTY_SHAKE_INTENSITY);
+    }
+}
+fn fade_penalty_flashes(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut flashes: Query<(Entity, &mut Sprite, &mut PenaltyFlash)>,
+) {
+    for (entity, mut sprite, mut flash) in &mut flashes {
+        flash.0.tick(time.delta());
+        if flash.0.finished() {
+            commands.entity(entity).despawn_recursive();
+        } else {
+            sprite
+                .color
+                .set_alpha(PENALTY_FLASH_COLOR.alpha() * flash.0.fraction_remaining());
+        }
+    }
+}
+/// A ball saver hit by a worker ball queues up a bonus ball for its owner, unless they're at the
+/// hard cap already.
+fn save_balls(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut spawner: ResMut<WorkerBallSpawner>,
+    survivors: Res<ParticipantMap<bool>>,
+    mut saver_query: Query<&mut BallSaver>,
+    worker_ball_query: Query<&Participant, With<WorkerBall>>,
+) {
+    for collision_event in collision_events.read() {
+        let &CollisionEvent::Started(a, b, _) = collision_event else {
+            continue;
+        };
+        let (saver_entity, ball_entity) = if saver_query.contains(a) {
+            (a, b)
+        } else if saver_query.contains(b) {
+            (b, a)
+        } else {
+            continue;
+        };
+        let (Ok(mut saver), Ok(&participant)) = (
+            saver_query.get_mut(saver_entity),
+            worker_ball_query.get(ball_entity),
+        ) else {
+            continue;
+        };
+        if !saver.cooldown.finished() || !survivors[participant] {
+            continue;
+        }
+        let ball_count = worker_ball_query
+            .iter()
+            .filter(|&&owner| owner == participant)
+            .count();
+        if ball_count + spawner.bonus[participant] >= WORKER_BALL_HARD_CAP {
+            continue;
+        }
+        spawner.bonus[participant] += 1;
+        saver.cooldown.reset();
+    }
+}
+fn recharge_ball_savers(time: Res<Time>, mut saver_query: Query<(&mut BallSaver, &mut Sprite)>) {
+    for (mut saver, mut sprite) in &mut saver_query {
+        saver.cooldown.tick(time.delta());
+        let color = if saver.cooldown.finished() {
+            BALL_SAVER_COLOR
+        } else {
+            BALL_SAVER_SPENT_COLOR
+        };
+        if sprite.color != color {
+            sprite.color = color;
+        }
+    }
+}
+fn chained_panels_enabled(config: Res<GameConfig>) -> bool {
+    config.chained_panels
+}
+fn show_chutes(config: Res<GameConfig>, mut chute_query: Query<&mut Visibility, With<Chute>>) {
+    let visibility = if config.chained_panels {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    for mut chute_visibility in &mut chute_query {
+        chute_visibility.set_if_neq(visibility);
+    }
+}
+/// Drops the ball in at the top of the other panel. It can't go through another chute until it's
+/// been sent home by `ball_reset`, so it doesn't bounce back and forth.
+fn transfer_through_chutes(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut rng: ResMut<MatchRng>,
+    chute_query: Query<(), With<Chute>>,
+    root_query: Query<(Entity, &PanelRoot)>,
+    mut worker_ball_query: Query<
+        (&mut Transform, &mut Velocity, &Parent),
+        (With<WorkerBall>, Without<Transferred>),
+    >,
+    trail_query: Query<(Entity, &WorkerBallTrail)>,
+) {
+    for collision_event in collision_events.read() {
+        let &CollisionEvent::Started(a, b, _) = collision_event else {
+            continue;
+        };
+        let ball_entity = if chute_query.contains(a) {
+            b
+        } else if chute_query.contains(b) {
+            a
+        } else {
+            continue;
+        };
+        let Ok((mut transform, mut velocity, parent)) = worker_ball_query.get_mut(ball_entity)
+        else {
+            continue;
+        };
+        if !rng.gen_bool(CHUTE_TRANSFER_CHANCE) {
+            continue;
+        }
+        let Some((root_entity, &PanelRoot(side))) = root_query
+            .iter()
+            .find(|&(entity, _)| entity != parent.get())
+        else {
+            continue;
+        };
+        let max_x = ARENA_WIDTH_FRAC_2 - WORKER_BALL_RADIUS;
+        transform.translation.x = rng.gen_range(-max_x..=max_x);
+        transform.translation.y = WORKER_BALL_SPAWN_Y;
+        *velocity = Velocity::zero();
+        commands
+            .entity(ball_entity)
+            .set_parent(root_entity)
+            .insert(Transferred);
+        move_trail(&mut commands, &trail_query, ball_entity, side);
+    }
+}
+/// Trails aren't children of the balls, so they have to be put on the render layer of the panel
+/// a ball moves to themselves.
+fn move_trail(
+    commands: &mut Commands,
+    trail_query: &Query<(Entity, &WorkerBallTrail)>,
+    ball: Entity,
+    side: PanelRootSide,
+) {
+    for (trail, &WorkerBallTrail(target)) in trail_query {
+        if target == ball {
+            commands.entity(trail).insert(panel_render_layers(side));
+        }
+    }
+}
+fn update_charge_previews(
+    turrets: Res<ParticipantMap<Entity>>,
+    charges: Query<&Charge>,
+    mut previews: Query<(&mut Text, &ChargePreview)>,
+    config: Res<GameConfig>,
+) {
+    for (mut text, &ChargePreview(participant)) in &mut previews {
+        let value = match charges.get(turrets[participant]) {
+            Ok(charge) => short_number(charge.value() as u128),
+            Err(_) if !config.participants[participant] => String::new(),
+            Err(_) => "OUT".to_string(),
+        };
+        // Only touch the text when it changes, so it isn't laid out again every frame.
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
+/// Eliminated participants stop getting new worker balls in `spawn_workers`, and the ones they
+/// have left are cleared off the panel here. Their trails get parked by
+/// `update_workers_particle_position`.
+fn despawn_eliminated_workers(
+    mut commands: Commands,
+    mut events: EventReader<EliminationEvent>,
+    worker_ball_query: Query<(Entity, &Participant), With<WorkerBall>>,
+) {
+    for event in events.read() {
+        for (entity, &participant) in &worker_ball_query {
+            if participant == event.participant {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+    }
+}
 fn restart(
     mut commands: Commands,
     mut spawner: ResMut<WorkerBallSpawner>,
+    mut earthquake: ResMut<EarthquakeTimer>,
+    mut spawn_rng: ResMut<SpawnRng>,
+    mut rng: ResMut<MatchRng>,
+    mut match_seed: ResMut<MatchSeed>,
+    mut events: EventReader<RestartEvent>,
+    config: Res<GameConfig>,
+    display_config: Res<DisplayConfig>,
+    tuning: Res<Tuning>,
     mut trails: Query<(&mut EffectProperties, &mut InactiveWorkerBallTrail)>,
+    mut gates: Query<&mut Gate>,
+    mut savers: Query<&mut BallSaver>,
     garbage: Query<Entity, With<WorkerBall>>,
 ) {
-    spawner.reset();
+    spawner.reset(tuning.worker_ball_spawn_interval);
+    for mut gate in &mut gates {
+        *gate = Gate::default();
+    }
+    for mut saver in &mut savers {
+        *saver = BallSaver::default();
+    }
+    let mut rematch = false;
+    let mut seed = None;
+    for event in events.read() {
+        rematch |= event.rematch;
+        seed = event.seed.or(seed);
+    }
+    if let Some(seed) = seed {
+        match_seed.0 = seed;
+    } else if !rematch {
+        match_seed.0 = config.seed.unwrap_or_else(|| thread_rng().gen());
+    }
+    *spawn_rng = SpawnRng::new(config.mirror_spawns, match_seed.0);
+    *rng = MatchRng::new(match_seed.0);
+    *earthquake = EarthquakeTimer::random(&mut **rng);
     for entity in garbage.iter() {
         commands.entity(entity).despawn_recursive();
     }
     let mut go_left = false;
     for (mut properties, mut trail) in trails.iter_mut() {
-        let x = if go_left { LEFT_ROOT_X } else { RIGHT_ROOT_X };
+        let side = if go_left {
+            PanelRootSide::Left
+        } else {
+            PanelRootSide::Right
+        };
         properties.set_spawn_color(LinearRgba::NONE);
-        properties.set_position(Vec3::new(x, WORKER_BALL_SPAWN_Y, 0.0));
+        properties.set_position(side.trail_parking_position(display_config.layout));
         trail.0 = go_left;
         go_left = !go_left;
     }
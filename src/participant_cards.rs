@@ -0,0 +1,206 @@
+#![allow(clippy::too_many_arguments)]
+
+use bevy::prelude::*;
+
+use crate::{
+    battlefield::{quadrant_center, Charge, TileCounts, TileGrid, Turret},
+    config::Tuning,
+    mutators::MutatorEffects,
+    pressure_hud::{short_number, BulletTally},
+    ui::PlayAreaNode,
+    utils::{BallColor, Participant, ParticipantMap},
+};
+
+// Constants {{{
+
+const CARD_MARGIN: f32 = 8.0;
+const CARD_WIDTH: f32 = 190.0;
+const CARD_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.6);
+const NAME_FONT_SIZE: f32 = 22.0;
+const DETAILS_FONT_SIZE: f32 = 16.0;
+const DETAILS_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
+const SWATCH_SIZE: f32 = 14.0;
+/// What the whole card turns once its participant is eliminated.
+const ELIMINATED_COLOR: Color = Color::srgb(0.45, 0.45, 0.45);
+
+// }}}
+
+/// A card in each participant's corner of the play area with how they're doing: their turret
+/// charge, how much of the territory they own, how many bullets they have in flight, and whether
+/// their next release gets a boosted charge. It greys out once they're eliminated.
+pub struct ParticipantCardsPlugin;
+impl Plugin for ParticipantCardsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_cards)
+            .add_systems(Update, update_cards);
+    }
+}
+
+#[derive(Component, Clone, Copy)]
+struct ParticipantCard(Participant);
+#[derive(Component, Clone, Copy)]
+struct CardSwatch(Participant);
+/// The name of the participant in the first section, and the rest in the second.
+#[derive(Component, Clone, Copy)]
+struct CardText(Participant);
+
+fn setup_cards(mut commands: Commands, colors: Res<ParticipantMap<BallColor>>) {
+    commands
+        .spawn((
+            Name::new("Participant Cards"),
+            PlayAreaNode,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            for participant in Participant::ALL {
+                let corner = quadrant_center(participant);
+                let margin = Val::Px(CARD_MARGIN);
+                let (mut top, mut bottom, mut left, mut right) =
+                    (Val::Auto, Val::Auto, Val::Auto, Val::Auto);
+                if corner.y > 0.0 {
+                    top = margin;
+                } else {
+                    bottom = margin;
+                }
+                if corner.x > 0.0 {
+                    right = margin;
+                } else {
+                    left = margin;
+                }
+                parent
+                    .spawn((
+                        ParticipantCard(participant),
+                        NodeBundle {
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                top,
+                                bottom,
+                                left,
+                                right,
+                                width: Val::Px(CARD_WIDTH),
+                                column_gap: Val::Px(8.0),
+                                align_items: AlignItems::Start,
+                                padding: UiRect::all(Val::Px(8.0)),
+                                ..default()
+                            },
+                            background_color: CARD_BACKGROUND.into(),
+                            border_radius: BorderRadius::all(Val::Px(8.0)),
+                            ..default()
+                        },
+                    ))
+                    .with_children(|card| {
+                        card.spawn((
+                            CardSwatch(participant),
+                            NodeBundle {
+                                style: Style {
+                                    width: Val::Px(SWATCH_SIZE),
+                                    height: Val::Px(SWATCH_SIZE),
+                                    margin: UiRect::top(Val::Px(4.0)),
+                                    ..default()
+                                },
+                                background_color: colors[participant].0.into(),
+                                border_radius: BorderRadius::MAX,
+                                ..default()
+                            },
+                        ));
+                        card.spawn((
+                            CardText(participant),
+                            TextBundle::from_sections([
+                                TextSection::new(
+                                    participant.to_string(),
+                                    TextStyle {
+                                        font: default(),
+                                        font_size: NAME_FONT_SIZE,
+                                        color: colors[participant].0,
+                                    },
+                                ),
+                                TextSection::new(
+                                    "",
+                                    TextStyle {
+                                        font: default(),
+                                        font_size: DETAILS_FONT_SIZE,
+                                        color: DETAILS_COLOR,
+                                    },
+                                ),
+                            ]),
+                        ));
+                    });
+            }
+        });
+}
+fn update_cards(
+    turret_entities: Res<ParticipantMap<Entity>>,
+    turrets: Query<(&Charge, &Turret)>,
+    survivors: Res<ParticipantMap<bool>>,
+    tile_counts: Res<TileCounts>,
+    grid: Res<TileGrid>,
+    tally: Res<BulletTally>,
+    mutators: Res<MutatorEffects>,
+    tuning: Res<Tuning>,
+    colors: Res<ParticipantMap<BallColor>>,
+    time: Res<Time>,
+    mut cards: Query<(&ParticipantCard, &mut Style)>,
+    mut swatches: Query<(&CardSwatch, &mut BackgroundColor)>,
+    mut texts: Query<(&CardText, &mut Text)>,
+) {
+    for (&ParticipantCard(participant), mut style) in &mut cards {
+        // Participants sitting out the match don't get a turret.
+        let display = if turret_entities[participant] == Entity::PLACEHOLDER {
+            Display::None
+        } else {
+            Display::Flex
+        };
+        if style.display != display {
+            style.display = display;
+        }
+    }
+    let color = |participant: Participant| {
+        if survivors[participant] {
+            colors[participant].0
+        } else {
+            ELIMINATED_COLOR
+        }
+    };
+    for (&CardSwatch(participant), mut background) in &mut swatches {
+        background.set_if_neq(color(participant).into());
+    }
+    let tile_total = (grid.side_count() * grid.side_count()) as f32;
+    for (&CardText(participant), mut text) in &mut texts {
+        let details = match turrets.get(turret_entities[participant]) {
+            Ok((charge, turret)) if survivors[participant] => {
+                let cooldown =
+                    turret.boost_cooldown_left(time.elapsed_seconds(), tuning.boost_cooldown);
+                format!(
+                    "\nCharge {}\nTiles {:.0}%\nBullets {}\n{}",
+                    short_number(charge.value() as u128),
+                    tile_counts.0[participant] as f32 / tile_total * 100.0,
+                    tally.count(participant),
+                    if !mutators.boost_cooldown || cooldown == 0.0 {
+                        "Boost ready".to_string()
+                    } else {
+                        format!("Boost in {:.1}s", cooldown)
+                    }
+                )
+            }
+            _ => "\nEliminated".to_string(),
+        };
+        let name_color = color(participant);
+        if text.sections[0].style.color != name_color {
+            text.sections[0].style.color = name_color;
+            text.sections[1].style.color = if survivors[participant] {
+                DETAILS_COLOR
+            } else {
+                ELIMINATED_COLOR
+            };
+        }
+        if text.sections[1].value != details {
+            text.sections[1].value = details;
+        }
+    }
+}
@@ -0,0 +1,521 @@
+use bevy::{app::AppExit, prelude::*};
+
+use crate::{
+    battlefield::MatchOutcome,
+    config::{GameConfig, Mutator, TieBreak},
+    focus::Focusable,
+    presets::Presets,
+    restart::{restart_pending, RestartRequestEvent},
+    state::AppState,
+    tutorial::Tutorial,
+    utils::Participant,
+};
+
+// Constants {{{
+
+const MENU_KEY: KeyCode = KeyCode::Escape;
+/// Pauses without the menu. The tutorial takes it over while it's running.
+const QUICK_PAUSE_KEY: KeyCode = KeyCode::Space;
+
+const MENU_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.7);
+const MENU_TITLE_FONT_SIZE: f32 = 64.0;
+const MENU_BUTTON_FONT_SIZE: f32 = 32.0;
+const MENU_BUTTON_WIDTH: f32 = 320.0;
+const MENU_BUTTON_HEIGHT: f32 = 60.0;
+const NORMAL_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
+const HOVERED_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
+const OVERLAY_FONT_SIZE: f32 = 28.0;
+const OVERLAY_HINT_FONT_SIZE: f32 = 18.0;
+
+// }}}
+
+/// The menu that comes up when the game is paused with Escape. Pausing with Space instead only
+/// shows a small overlay, to keep the frozen battlefield in view.
+pub struct PauseMenuPlugin;
+impl Plugin for PauseMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(AppState::Paused),
+            (
+                spawn_pause_menu.run_if(not(resource_exists::<QuickPause>)),
+                spawn_pause_overlay.run_if(resource_exists::<QuickPause>),
+            ),
+        )
+        .add_systems(OnExit(AppState::Paused), despawn_pause_menu)
+        .add_systems(
+            Update,
+            (
+                toggle_pause.run_if(not(restart_pending)),
+                (
+                    handle_menu_buttons,
+                    handle_setting_toggles,
+                    handle_preset_button,
+                    update_setting_labels,
+                    update_preset_label,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Paused)),
+            ),
+        );
+    }
+}
+
+/// Present while the game is paused with [`QUICK_PAUSE_KEY`].
+#[derive(Resource)]
+struct QuickPause;
+/// The root of the menu or of the overlay, whichever is up.
+#[derive(Component, Clone, Copy)]
+struct PauseMenu;
+#[derive(Component, Clone, Copy)]
+struct SettingsList;
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum MenuButton {
+    Resume,
+    Restart,
+    Settings,
+    Quit,
+}
+/// The match options that can be changed from the menu. They take effect when the next match
+/// starts.
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum Setting {
+    ShrinkingArena,
+    Earthquakes,
+    MirrorSpawns,
+    LowSpec,
+    CatchUp,
+    VampiricDuels,
+    /// Whether nobody being left standing is settled by tiles, rather than called a draw.
+    MostTilesTieBreak,
+    BallSabotage,
+    LastStand,
+    ShotAi,
+    Overheat,
+    TileTargeting,
+    ChainedPanels,
+    /// Whether the participant takes part in the match, rather than sitting it out.
+    Seated(Participant),
+    Mutator(Mutator),
+}
+impl Setting {
+    const ALL: [Self; 21] = [
+        Self::ShrinkingArena,
+        Self::Earthquakes,
+        Self::MirrorSpawns,
+        Self::LowSpec,
+        Self::CatchUp,
+        Self::VampiricDuels,
+        Self::MostTilesTieBreak,
+        Self::BallSabotage,
+        Self::LastStand,
+        Self::ShotAi,
+        Self::Overheat,
+        Self::TileTargeting,
+        Self::ChainedPanels,
+        Self::Seated(Participant::A),
+        Self::Seated(Participant::B),
+        Self::Seated(Participant::C),
+        Self::Seated(Participant::D),
+        Self::Mutator(Mutator::LowGravity),
+        Self::Mutator(Mutator::GiantBullets),
+        Self::Mutator(Mutator::DoubleMultiply),
+        Self::Mutator(Mutator::NoBoostCooldown),
+    ];
+    fn name(self) -> &'static str {
+        match self {
+            Self::ShrinkingArena => "Shrinking Arena",
+            Self::Earthquakes => "Earthquakes",
+            Self::MirrorSpawns => "Mirror Spawns",
+            Self::LowSpec => "Low Spec",
+            Self::CatchUp => "Catch-up Bonus",
+            Self::VampiricDuels => "Vampiric Duels",
+            Self::MostTilesTieBreak => "Most Tiles Breaks Ties",
+            Self::BallSabotage => "Ball Sabotage",
+            Self::LastStand => "Last Stand",
+            Self::ShotAi => "Turrets Pick Shots",
+            Self::Overheat => "Overheating",
+            Self::TileTargeting => "Bursts Aim at Tiles",
+            Self::ChainedPanels => "Transfer Chutes",
+            Self::Seated(Participant::A) => "Red Plays",
+            Self::Seated(Participant::B) => "Green Plays",
+            Self::Seated(Participant::C) => "Violet Plays",
+            Self::Seated(Participant::D) => "Yellow Plays",
+            Self::Mutator(mutator) => mutator.name(),
+        }
+    }
+    fn get(self, config: &GameConfig) -> bool {
+        match self {
+            Self::ShrinkingArena => config.shrinking_arena,
+            Self::Earthquakes => config.earthquakes,
+            Self::MirrorSpawns => config.mirror_spawns,
+            Self::LowSpec => config.low_spec,
+            Self::CatchUp => config.catch_up,
+            Self::VampiricDuels => config.vampiric_duels,
+            Self::MostTilesTieBreak => config.tie_break == TieBreak::MostTiles,
+            Self::BallSabotage => config.ball_sabotage,
+            Self::LastStand => config.last_stand,
+            Self::ShotAi => config.shot_ai,
+            Self::Overheat => config.overheat,
+            Self::TileTargeting => config.tile_targeting,
+            Self::ChainedPanels => config.chained_panels,
+            Self::Seated(participant) => config.participants[participant],
+            Self::Mutator(mutator) => config.mutators.contains(&mutator),
+        }
+    }
+    fn toggle(self, config: &mut GameConfig) {
+        let value = match self {
+            Self::MostTilesTieBreak => {
+                config.tie_break = match config.tie_break {
+                    TieBreak::Draw => TieBreak::MostTiles,
+                    TieBreak::MostTiles => TieBreak::Draw,
+                };
+                return;
+            }
+            Self::Seated(participant) => {
+                let seated_count = Participant::ALL
+                    .into_iter()
+                    .filter(|&p| config.participants[p])
+                    .count();
+                // It takes at least two to have a match.
+                if config.participants[participant] && seated_count <= 2 {
+                    return;
+                }
+                &mut config.participants[participant]
+            }
+            Self::Mutator(mutator) => {
+                if let Some(i) = config.mutators.iter().position(|&m| m == mutator) {
+                    config.mutators.remove(i);
+                } else {
+                    config.mutators.push(mutator);
+                    config.mutators.sort();
+                }
+                return;
+            }
+            Self::ShrinkingArena => &mut config.shrinking_arena,
+            Self::Earthquakes => &mut config.earthquakes,
+            Self::MirrorSpawns => &mut config.mirror_spawns,
+            Self::LowSpec => &mut config.low_spec,
+            Self::CatchUp => &mut config.catch_up,
+            Self::VampiricDuels => &mut config.vampiric_duels,
+            Self::BallSabotage => &mut config.ball_sabotage,
+            Self::LastStand => &mut config.last_stand,
+            Self::ShotAi => &mut config.shot_ai,
+            Self::Overheat => &mut config.overheat,
+            Self::TileTargeting => &mut config.tile_targeting,
+            Self::ChainedPanels => &mut config.chained_panels,
+        };
+        *value = !*value;
+    }
+}
+/// Switches to the next preset, which changes all of the settings at once.
+#[derive(Component, Clone, Copy)]
+struct PresetButton;
+#[derive(Component, Clone, Copy)]
+struct PresetLabel;
+/// Marker for the text of a setting's button.
+#[derive(Component, Clone, Copy)]
+struct SettingLabel(Setting);
+
+/// The game can be paused after a match too, to change the settings for the next one. Either key
+/// unpauses it.
+fn toggle_pause(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    tutorial: Option<Res<Tutorial>>,
+    state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    outcome: Res<MatchOutcome>,
+) {
+    let quick = keys.just_pressed(QUICK_PAUSE_KEY) && tutorial.is_none();
+    if !keys.just_pressed(MENU_KEY) && !quick {
+        return;
+    }
+    next_state.set(match state.get() {
+        AppState::Playing | AppState::GameOver => {
+            if quick {
+                commands.insert_resource(QuickPause);
+            }
+            AppState::Paused
+        }
+        AppState::Paused => AppState::unpaused(*outcome),
+        AppState::MainMenu | AppState::HallOfFame => return,
+    });
+}
+fn menu_button(parent: &mut ChildBuilder, bundle: impl Bundle, label: impl Bundle) {
+    parent
+        .spawn((
+            bundle,
+            Focusable,
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(MENU_BUTTON_WIDTH),
+                    height: Val::Px(MENU_BUTTON_HEIGHT),
+                    border: UiRect::all(Val::Px(5.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                border_color: BorderColor(Color::BLACK),
+                border_radius: BorderRadius::MAX,
+                background_color: NORMAL_BUTTON.into(),
+                ..default()
+            },
+        ))
+        .with_children(|button| {
+            button.spawn(label);
+        });
+}
+fn button_text(text: impl Into<String>) -> TextBundle {
+    TextBundle::from_section(
+        text,
+        TextStyle {
+            font: default(),
+            font_size: MENU_BUTTON_FONT_SIZE,
+            color: Color::srgb(0.9, 0.9, 0.9),
+        },
+    )
+}
+fn spawn_pause_menu(mut commands: Commands, presets: Res<Presets>) {
+    commands
+        .spawn((
+            Name::new("Pause Menu"),
+            PauseMenu,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(16.0),
+                    ..default()
+                },
+                background_color: MENU_BACKGROUND.into(),
+                z_index: ZIndex::Global(2),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Paused",
+                TextStyle {
+                    font: default(),
+                    font_size: MENU_TITLE_FONT_SIZE,
+                    color: Color::WHITE,
+                },
+            ));
+            for (button, label) in [
+                (MenuButton::Resume, "Resume"),
+                (MenuButton::Restart, "Restart"),
+                (MenuButton::Settings, "Settings"),
+                (MenuButton::Quit, "Quit"),
+            ] {
+                menu_button(parent, button, button_text(label));
+            }
+            parent
+                .spawn((
+                    SettingsList,
+                    NodeBundle {
+                        style: Style {
+                            display: Display::None,
+                            flex_direction: FlexDirection::Column,
+                            align_items: AlignItems::Center,
+                            row_gap: Val::Px(8.0),
+                            margin: UiRect::top(Val::Px(16.0)),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                ))
+                .with_children(|list| {
+                    if !presets.0.is_empty() {
+                        menu_button(list, PresetButton, (PresetLabel, button_text("")));
+                    }
+                    for setting in Setting::ALL {
+                        menu_button(
+                            list,
+                            setting,
+                            (SettingLabel(setting), button_text(setting.name())),
+                        );
+                    }
+                });
+        });
+}
+fn spawn_pause_overlay(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Pause Overlay"),
+            PauseMenu,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                z_index: ZIndex::Global(2),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        padding: UiRect::axes(Val::Px(24.0), Val::Px(12.0)),
+                        ..default()
+                    },
+                    background_color: MENU_BACKGROUND.into(),
+                    border_radius: BorderRadius::all(Val::Px(8.0)),
+                    ..default()
+                })
+                .with_children(|overlay| {
+                    overlay.spawn(TextBundle::from_section(
+                        "Paused",
+                        TextStyle {
+                            font: default(),
+                            font_size: OVERLAY_FONT_SIZE,
+                            color: Color::WHITE,
+                        },
+                    ));
+                    overlay.spawn(TextBundle::from_section(
+                        "Space or Esc to resume",
+                        TextStyle {
+                            font: default(),
+                            font_size: OVERLAY_HINT_FONT_SIZE,
+                            color: Color::srgb(0.9, 0.9, 0.9),
+                        },
+                    ));
+                });
+        });
+}
+fn despawn_pause_menu(mut commands: Commands, menu: Query<Entity, With<PauseMenu>>) {
+    commands.remove_resource::<QuickPause>();
+    for entity in &menu {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+fn highlight_button(
+    interaction: Interaction,
+    color: &mut BackgroundColor,
+    border: &mut BorderColor,
+) {
+    match interaction {
+        Interaction::Pressed | Interaction::Hovered => {
+            *color = HOVERED_BUTTON.into();
+            border.0 = Color::WHITE;
+        }
+        Interaction::None => {
+            *color = NORMAL_BUTTON.into();
+            border.0 = Color::BLACK;
+        }
+    }
+}
+fn handle_menu_buttons(
+    mut interaction_query: Query<
+        (
+            &Interaction,
+            &MenuButton,
+            &mut BackgroundColor,
+            &mut BorderColor,
+        ),
+        Changed<Interaction>,
+    >,
+    mut settings_list: Query<&mut Style, With<SettingsList>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    outcome: Res<MatchOutcome>,
+    mut restart_events: EventWriter<RestartRequestEvent>,
+    mut exit_events: EventWriter<AppExit>,
+) {
+    for (&interaction, &button, mut color, mut border_color) in &mut interaction_query {
+        highlight_button(interaction, &mut color, &mut border_color);
+        if interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            MenuButton::Resume => next_state.set(AppState::unpaused(*outcome)),
+            MenuButton::Restart => {
+                restart_events.send_default();
+                next_state.set(AppState::unpaused(*outcome));
+            }
+            MenuButton::Settings => {
+                for mut style in &mut settings_list {
+                    style.display = match style.display {
+                        Display::None => Display::Flex,
+                        _ => Display::None,
+                    };
+                }
+            }
+            MenuButton::Quit => {
+                exit_events.send(AppExit::Success);
+            }
+        }
+    }
+}
+fn handle_setting_toggles(
+    mut interaction_query: Query<
+        (
+            &Interaction,
+            &Setting,
+            &mut BackgroundColor,
+            &mut BorderColor,
+        ),
+        Changed<Interaction>,
+    >,
+    mut config: ResMut<GameConfig>,
+) {
+    for (&interaction, &setting, mut color, mut border_color) in &mut interaction_query {
+        highlight_button(interaction, &mut color, &mut border_color);
+        if interaction == Interaction::Pressed {
+            setting.toggle(&mut config);
+        }
+    }
+}
+fn handle_preset_button(
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor, &mut BorderColor),
+        (Changed<Interaction>, With<PresetButton>),
+    >,
+    mut config: ResMut<GameConfig>,
+    presets: Res<Presets>,
+) {
+    for (&interaction, mut color, mut border_color) in &mut interaction_query {
+        highlight_button(interaction, &mut color, &mut border_color);
+        if interaction != Interaction::Pressed {
+            continue;
+        }
+        let next = presets
+            .current(&config)
+            .map_or(0, |i| (i + 1) % presets.0.len());
+        *config = presets.0[next].apply(&config);
+    }
+}
+fn update_setting_labels(config: Res<GameConfig>, mut labels: Query<(&mut Text, &SettingLabel)>) {
+    for (mut text, &SettingLabel(setting)) in &mut labels {
+        let value = if setting.get(&config) { "On" } else { "Off" };
+        let label = format!("{}: {}", setting.name(), value);
+        if text.sections[0].value != label {
+            text.sections[0].value = label;
+        }
+    }
+}
+/// Settings changed by hand after picking a preset make it a custom one.
+fn update_preset_label(
+    config: Res<GameConfig>,
+    presets: Res<Presets>,
+    mut labels: Query<&mut Text, With<PresetLabel>>,
+) {
+    let name = presets
+        .current(&config)
+        .map_or("Custom", |i| presets.0[i].name.as_str());
+    let label = format!("Preset: {}", name);
+    for mut text in &mut labels {
+        if text.sections[0].value != label {
+            text.sections[0].value.clone_from(&label);
+        }
+    }
+}
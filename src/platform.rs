@@ -0,0 +1,16 @@
+//! Differences between running natively and running in a browser.
+
+/// Whether the particle effects can run. They need compute shaders, which browsers only have
+/// through WebGPU, so browser builds have to be made with the `webgpu` feature and run in a
+/// browser that supports it.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn particles_supported() -> bool {
+    true
+}
+#[cfg(target_arch = "wasm32")]
+pub fn particles_supported() -> bool {
+    cfg!(feature = "webgpu")
+        && web_sys::window().is_some_and(|window| {
+            js_sys::Reflect::has(&window.navigator(), &"gpu".into()).unwrap_or(false)
+        })
+}
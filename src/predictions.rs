@@ -0,0 +1,265 @@
+use std::{
+    fmt::Write as _,
+    io::{BufRead, BufReader},
+    net::{SocketAddr, TcpListener},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Mutex,
+    },
+};
+
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    battlefield::{RestartEvent, SurvivorCount},
+    config::GameConfig,
+    state::AppState,
+    stats::MatchFinishedEvent,
+    ui::PlayAreaNode,
+    utils::{Participant, ParticipantMap},
+};
+
+// Constants {{{
+
+/// The environment variable holding the address to take predictions on, e.g. `127.0.0.1:9200`.
+pub const PREDICTIONS_ADDRESS_VAR: &str = "PREDICTIONS_ADDRESS";
+/// How long viewers have to lock in a pick once predictions open, in seconds.
+const PREDICTION_WINDOW: f32 = 20.0;
+/// The points for picking the winner at the start of the match.
+const MATCH_STAKE: u32 = 10;
+/// The points for picking the winner at the start of the final duel, which is easier to call.
+const DUEL_STAKE: u32 = 4;
+const LEADERBOARD_SIZE: usize = 5;
+
+const OVERLAY_FONT_SIZE: f32 = 18.0;
+const OVERLAY_COLOR: Color = Color::WHITE;
+const OVERLAY_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.6);
+
+// }}}
+
+/// Lets viewers predict the winner of the match at its start, and again at the start of the final
+/// duel, and keeps a leaderboard of their points across matches.
+///
+/// Picks come in over TCP as lines of `<viewer> <participant>`, like `alice red` or `bob g`, so
+/// any chat bot can forward them.
+pub struct PredictionsPlugin {
+    pub address: SocketAddr,
+}
+impl Plugin for PredictionsPlugin {
+    fn build(&self, app: &mut App) {
+        let listener = match TcpListener::bind(self.address) {
+            Ok(listener) => listener,
+            Err(error) => {
+                error!("Failed to take predictions on {}: {}", self.address, error);
+                return;
+            }
+        };
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let sender = sender.clone();
+                std::thread::spawn(move || read_picks(BufReader::new(stream), sender));
+            }
+        });
+        app.insert_resource(PickInbox(Mutex::new(receiver)))
+            .init_resource::<Predictions>()
+            .init_resource::<Leaderboard>()
+            .add_systems(Startup, (setup_overlay, open_match_round))
+            .add_systems(
+                Update,
+                (
+                    open_match_round.run_if(on_event::<RestartEvent>()),
+                    open_duel_round.run_if(
+                        in_state(AppState::Playing).and_then(resource_changed::<SurvivorCount>),
+                    ),
+                    take_picks,
+                    score_predictions.run_if(on_event::<MatchFinishedEvent>()),
+                    update_overlay,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// The picks sent in by viewers, as they come off the connections.
+#[derive(Resource)]
+struct PickInbox(Mutex<Receiver<(String, String)>>);
+/// A chance for viewers to call the winner, worth `stake` points if they get it right.
+struct Round {
+    stake: u32,
+    candidates: Vec<Participant>,
+    picks: HashMap<String, Participant>,
+}
+/// The rounds of the current match. Only the latest one takes picks, and only until the timer
+/// runs out.
+#[derive(Resource, Default)]
+struct Predictions {
+    rounds: Vec<Round>,
+    timer: Timer,
+}
+impl Predictions {
+    fn open(&mut self, stake: u32, candidates: Vec<Participant>) {
+        self.rounds.push(Round {
+            stake,
+            candidates,
+            picks: HashMap::new(),
+        });
+        self.timer = Timer::from_seconds(PREDICTION_WINDOW, TimerMode::Once);
+    }
+    fn is_open(&self) -> bool {
+        !self.rounds.is_empty() && !self.timer.finished()
+    }
+}
+/// Every viewer's points, kept for as long as the game runs.
+#[derive(Resource, Default)]
+struct Leaderboard(HashMap<String, u32>);
+#[derive(Component, Clone, Copy)]
+struct PredictionsText;
+
+fn read_picks(reader: impl BufRead, sender: Sender<(String, String)>) {
+    for line in reader.lines() {
+        let Ok(line) = line else {
+            return;
+        };
+        let mut words = line.split_whitespace();
+        if let (Some(viewer), Some(pick)) = (words.next(), words.next()) {
+            if sender.send((viewer.to_owned(), pick.to_owned())).is_err() {
+                return;
+            }
+        }
+    }
+}
+/// Takes either the name of the participant or its initial, ignoring case.
+fn parse_pick(pick: &str) -> Option<Participant> {
+    Participant::ALL.into_iter().find(|participant| {
+        participant.to_string().eq_ignore_ascii_case(pick)
+            || pick.eq_ignore_ascii_case(participant.initial().encode_utf8(&mut [0; 4]))
+    })
+}
+fn open_match_round(mut predictions: ResMut<Predictions>, config: Res<GameConfig>) {
+    let candidates = Participant::ALL
+        .into_iter()
+        .filter(|&participant| config.participants[participant])
+        .collect();
+    predictions.rounds.clear();
+    predictions.open(MATCH_STAKE, candidates);
+}
+fn open_duel_round(
+    mut predictions: ResMut<Predictions>,
+    survivor_count: Res<SurvivorCount>,
+    survivors: Res<ParticipantMap<bool>>,
+) {
+    // The survivor count only comes down to 2 once a match, but a restored autosave might start
+    // there.
+    if survivor_count.0 != 2 || predictions.rounds.len() > 1 {
+        return;
+    }
+    let candidates = Participant::ALL
+        .into_iter()
+        .filter(|&participant| survivors[participant])
+        .collect();
+    predictions.open(DUEL_STAKE, candidates);
+}
+/// Picks that come in while predictions are closed are thrown away. A viewer who picks again
+/// within the same round changes their pick.
+fn take_picks(time: Res<Time>, inbox: Res<PickInbox>, mut predictions: ResMut<Predictions>) {
+    predictions.timer.tick(time.delta());
+    let is_open = predictions.is_open();
+    let inbox = inbox.0.lock().unwrap();
+    let Some(round) = predictions.rounds.last_mut() else {
+        return;
+    };
+    for (viewer, pick) in inbox.try_iter() {
+        if !is_open {
+            continue;
+        }
+        match parse_pick(&pick) {
+            Some(participant) if round.candidates.contains(&participant) => {
+                round.picks.insert(viewer, participant);
+            }
+            _ => debug!("Ignored prediction {:?} from {}", pick, viewer),
+        }
+    }
+}
+fn score_predictions(
+    mut events: EventReader<MatchFinishedEvent>,
+    mut predictions: ResMut<Predictions>,
+    mut leaderboard: ResMut<Leaderboard>,
+) {
+    for event in events.read() {
+        for round in predictions.rounds.drain(..) {
+            for (viewer, pick) in round.picks {
+                let points = leaderboard.0.entry(viewer).or_default();
+                if Some(pick) == event.winner {
+                    *points += round.stake;
+                }
+            }
+        }
+    }
+}
+fn setup_overlay(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Predictions Overlay"),
+            PlayAreaNode,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    justify_content: JustifyContent::End,
+                    align_items: AlignItems::Start,
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                PredictionsText,
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: default(),
+                        font_size: OVERLAY_FONT_SIZE,
+                        color: OVERLAY_COLOR,
+                    },
+                )
+                .with_style(Style {
+                    padding: UiRect::axes(Val::Px(12.0), Val::Px(4.0)),
+                    margin: UiRect::all(Val::Px(8.0)),
+                    ..default()
+                })
+                .with_background_color(OVERLAY_BACKGROUND),
+            ));
+        });
+}
+fn update_overlay(
+    predictions: Res<Predictions>,
+    leaderboard: Res<Leaderboard>,
+    mut texts: Query<(&mut Text, &mut Visibility), With<PredictionsText>>,
+) {
+    if !predictions.is_changed() && !leaderboard.is_changed() {
+        return;
+    }
+    let mut body = String::new();
+    if let (true, Some(round)) = (predictions.is_open(), predictions.rounds.last()) {
+        let _ = writeln!(
+            body,
+            "Predictions close in {:.0}s ({} in)",
+            predictions.timer.remaining_secs().ceil(),
+            round.picks.len()
+        );
+    }
+    let mut standings = leaderboard.0.iter().collect::<Vec<_>>();
+    standings.sort_by(|(a_name, a), (b_name, b)| b.cmp(a).then(a_name.cmp(b_name)));
+    for (rank, (viewer, points)) in standings.into_iter().take(LEADERBOARD_SIZE).enumerate() {
+        let _ = writeln!(body, "{}. {} - {}", rank + 1, viewer, points);
+    }
+    for (mut text, mut visibility) in &mut texts {
+        text.sections[0].value = body.trim_end().to_owned();
+        *visibility = if body.is_empty() {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+    }
+}
@@ -0,0 +1,85 @@
+use std::{fs, io::ErrorKind, path::Path};
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::config::GameConfig;
+
+// Constants {{{
+
+const PRESETS_DIR: &str = "assets/presets";
+
+// }}}
+
+/// A named set of rules, read from a RON file in [`PRESETS_DIR`]. Options the file leaves out get
+/// their default values.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    #[serde(default)]
+    config: GameConfig,
+}
+impl Preset {
+    /// `config` with the rules of this preset, keeping the options that are about this machine or
+    /// match rather than about the rules.
+    pub fn apply(&self, config: &GameConfig) -> GameConfig {
+        GameConfig {
+            seed: config.seed,
+            low_spec: config.low_spec,
+            deterministic: config.deterministic,
+            participants: config.participants,
+            ..self.config.clone()
+        }
+    }
+}
+/// All the presets, sorted by file name.
+#[derive(Debug, Resource, Default)]
+pub struct Presets(pub Vec<Preset>);
+impl Presets {
+    /// Files that can't be read or parsed are skipped with a warning.
+    pub fn load() -> Self {
+        let entries = match fs::read_dir(PRESETS_DIR) {
+            Ok(entries) => entries,
+            Err(error) => {
+                if error.kind() != ErrorKind::NotFound {
+                    warn!("Failed to read {}: {}", PRESETS_DIR, error);
+                }
+                return Self::default();
+            }
+        };
+        let mut paths = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|extension| extension == "ron"))
+            .collect::<Vec<_>>();
+        paths.sort();
+        Self(paths.iter().filter_map(|path| load_preset(path)).collect())
+    }
+    /// Ignores case, so `chaos` finds the preset named Chaos.
+    pub fn find(&self, name: &str) -> Option<&Preset> {
+        self.0
+            .iter()
+            .find(|preset| preset.name.eq_ignore_ascii_case(name))
+    }
+    /// The preset `config` was set up with, if it hasn't been changed since.
+    pub fn current(&self, config: &GameConfig) -> Option<usize> {
+        self.0
+            .iter()
+            .position(|preset| preset.apply(config) == *config)
+    }
+}
+
+fn load_preset(path: &Path) -> Option<Preset> {
+    let text = fs::read_to_string(path)
+        .map_err(|error| warn!("Failed to read {}: {}", path.display(), error))
+        .ok()?;
+    let preset: Preset = ron::from_str(&text)
+        .map_err(|error| warn!("Failed to parse {}: {}", path.display(), error))
+        .ok()?;
+    if let Err(problems) = preset.config.validate() {
+        for problem in problems {
+            warn!("Skipping {}: {}", path.display(), problem);
+        }
+        return None;
+    }
+    Some(preset)
+}
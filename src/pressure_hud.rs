@@ -0,0 +1,144 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    battlefield::{Bullet, Charge},
+    ui::PlayAreaNode,
+    utils::{BallColor, Participant, ParticipantMap},
+};
+
+// Constants {{{
+
+const HUD_FONT_SIZE: f32 = 20.0;
+const HUD_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.6);
+const NUMBER_SUFFIXES: [&str; 7] = ["", "K", "M", "B", "T", "Qa", "Qi"];
+
+// }}}
+
+/// A row at the bottom of the screen with how many bullets each participant has in flight, and how
+/// much charge they carry between them.
+pub struct BulletPressurePlugin;
+impl Plugin for BulletPressurePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BulletTally>()
+            .add_systems(Startup, setup_hud)
+            .add_systems(
+                Update,
+                (
+                    tally_bullets,
+                    update_hud.run_if(resource_changed::<BulletTally>),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Kept up to date bullet by bullet, rather than adding up every bullet each frame.
+#[derive(Resource, Default)]
+pub struct BulletTally {
+    /// The owner and last seen charge of every bullet in flight.
+    bullets: HashMap<Entity, (Participant, u64)>,
+    counts: ParticipantMap<u32>,
+    /// Wide enough that a handful of maxed out bullets can't overflow it.
+    charges: ParticipantMap<u128>,
+}
+impl BulletTally {
+    /// The number of bullets the participant has in flight.
+    pub fn count(&self, participant: Participant) -> u32 {
+        self.counts[participant]
+    }
+}
+#[derive(Component, Clone, Copy)]
+struct PressureText(Participant);
+
+fn setup_hud(mut commands: Commands, colors: Res<ParticipantMap<BallColor>>) {
+    commands
+        .spawn((
+            Name::new("Bullet Pressure HUD"),
+            PlayAreaNode,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::End,
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Val::Px(24.0),
+                        padding: UiRect::axes(Val::Px(12.0), Val::Px(4.0)),
+                        margin: UiRect::bottom(Val::Px(8.0)),
+                        ..default()
+                    },
+                    background_color: HUD_BACKGROUND.into(),
+                    border_radius: BorderRadius::all(Val::Px(8.0)),
+                    ..default()
+                })
+                .with_children(|row| {
+                    for participant in Participant::ALL {
+                        row.spawn((
+                            PressureText(participant),
+                            TextBundle::from_section(
+                                "",
+                                TextStyle {
+                                    font: default(),
+                                    font_size: HUD_FONT_SIZE,
+                                    color: colors[participant].0,
+                                },
+                            ),
+                        ));
+                    }
+                });
+        });
+}
+fn tally_bullets(
+    mut tally: ResMut<BulletTally>,
+    changed: Query<(Entity, &Participant, &Charge), (With<Bullet>, Changed<Charge>)>,
+    mut removed: RemovedComponents<Bullet>,
+) {
+    if changed.is_empty() && removed.is_empty() {
+        return;
+    }
+    let tally = tally.as_mut();
+    for (entity, &participant, charge) in &changed {
+        match tally.bullets.insert(entity, (participant, charge.value())) {
+            Some((_, old)) => tally.charges[participant] -= old as u128,
+            None => tally.counts[participant] += 1,
+        }
+        tally.charges[participant] += charge.value() as u128;
+    }
+    for entity in removed.read() {
+        if let Some((participant, value)) = tally.bullets.remove(&entity) {
+            tally.counts[participant] -= 1;
+            tally.charges[participant] -= value as u128;
+        }
+    }
+}
+/// Like 12.3K or 4.5B, to keep the row short.
+pub fn short_number(value: u128) -> String {
+    let mut scaled = value as f64;
+    for suffix in NUMBER_SUFFIXES {
+        if scaled < 1000.0 {
+            return if suffix.is_empty() {
+                value.to_string()
+            } else {
+                format!("{:.1}{}", scaled, suffix)
+            };
+        }
+        scaled /= 1000.0;
+    }
+    format!("{:.1e}", value as f64)
+}
+fn update_hud(tally: Res<BulletTally>, mut texts: Query<(&mut Text, &PressureText)>) {
+    for (mut text, &PressureText(participant)) in &mut texts {
+        text.sections[0].value = format!(
+            "{} bullets, {} charge",
+            tally.counts[participant],
+            short_number(tally.charges[participant])
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+
+use crate::{
+    battlefield::{quadrant_center, QuadrantTileCounts, BATTLEFIELD_HALF_WIDTH},
+    utils::{Participant, ParticipantMap, TileColor},
+};
+
+// Constants {{{
+
+/// How strongly each quadrant is tinted toward the color of whoever owns most of it.
+const TINT_ALPHA: f32 = 0.15;
+/// How quickly the tint follows a change of hands, per second.
+const TINT_FADE_SPEED: f32 = 2.0;
+/// Over the tiles, under the tile flashes and bullets.
+const TINT_Z: f32 = -0.75;
+
+// }}}
+
+/// Washes each quadrant of the battlefield in the color of whoever holds most of it, so shifts in
+/// territory show up even zoomed out or at high speed.
+pub struct QuadrantTintPlugin;
+impl Plugin for QuadrantTintPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<QuadrantOwners>()
+            .add_systems(Startup, setup_tints)
+            .add_systems(
+                Update,
+                (
+                    find_quadrant_owners.run_if(resource_changed::<QuadrantTileCounts>),
+                    fade_tints,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Which participant owns most of each quadrant, with the quadrants named after whoever starts
+/// out in them.
+#[derive(Resource, Default)]
+struct QuadrantOwners(ParticipantMap<Option<Participant>>);
+#[derive(Component, Clone, Copy)]
+struct QuadrantTint(Participant);
+
+fn setup_tints(mut commands: Commands) {
+    for quadrant in Participant::ALL {
+        commands.spawn((
+            Name::new(format!("Quadrant Tint: {}", quadrant)),
+            QuadrantTint(quadrant),
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::NONE,
+                    custom_size: Some(Vec2::splat(BATTLEFIELD_HALF_WIDTH)),
+                    ..default()
+                },
+                transform: Transform::from_translation(quadrant_center(quadrant).extend(TINT_Z)),
+                ..default()
+            },
+        ));
+    }
+}
+fn find_quadrant_owners(counts: Res<QuadrantTileCounts>, mut owners: ResMut<QuadrantOwners>) {
+    let counts = &counts.0;
+    for quadrant in Participant::ALL {
+        owners.0[quadrant] = Participant::ALL
+            .into_iter()
+            .filter(|&p| counts[quadrant][p] > 0)
+            .max_by_key(|&p| counts[quadrant][p]);
+    }
+}
+fn fade_tints(
+    time: Res<Time>,
+    owners: Res<QuadrantOwners>,
+    colors: Res<ParticipantMap<TileColor>>,
+    mut tints: Query<(&mut Sprite, &QuadrantTint)>,
+) {
+    let t = (TINT_FADE_SPEED * time.delta_seconds()).min(1.0);
+    for (mut sprite, &QuadrantTint(quadrant)) in &mut tints {
+        let target = match owners.0[quadrant] {
+            Some(owner) => colors[owner].0.with_alpha(TINT_ALPHA),
+            None => Color::NONE,
+        };
+        let color = sprite.color.mix(&target, t);
+        if sprite.color != color {
+            sprite.color = color;
+        }
+    }
+}
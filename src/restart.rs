@@ -0,0 +1,293 @@
+use bevy::prelude::*;
+
+use crate::{
+    battlefield::{MatchOutcome, RestartEvent},
+    state::AppState,
+};
+
+// Constants {{{
+
+/// Seconds between asking for a restart and the match actually restarting.
+const RESTART_COUNTDOWN: f32 = 3.0;
+const RESTART_KEY: KeyCode = KeyCode::KeyR;
+
+const OVERLAY_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.5);
+const OVERLAY_FONT_SIZE: f32 = 56.0;
+const OVERLAY_BUTTON_FONT_SIZE: f32 = 32.0;
+const NORMAL_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
+const HOVERED_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
+
+// }}}
+
+/// Restarts the match after a countdown that can be cancelled, so a long match can't be thrown
+/// away by accident.
+pub struct RestartPlugin;
+impl Plugin for RestartPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<RestartRequestEvent>().add_systems(
+            Update,
+            (
+                restart_hotkey.run_if(
+                    not(in_state(AppState::MainMenu)).and_then(not(in_state(AppState::HallOfFame))),
+                ),
+                start_countdown.run_if(on_event::<RestartRequestEvent>()),
+                (handle_overlay_input, tick_countdown)
+                    .chain()
+                    .run_if(resource_exists::<PendingRestart>),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Asks for the match to be restarted once the countdown runs out.
+#[derive(Event, Default)]
+pub struct RestartRequestEvent {
+    /// Replay the seed of the match that just ended instead of picking a new one.
+    pub rematch: bool,
+    /// The seed to play the next match with instead, like one typed in on the game over screen.
+    pub seed: Option<u64>,
+}
+#[derive(Resource)]
+pub enum PendingRestart {
+    /// Waiting for the player to confirm a restart in the middle of a match.
+    Confirming,
+    Countdown {
+        timer: Timer,
+        rematch: bool,
+        seed: Option<u64>,
+    },
+}
+#[derive(Component, Clone, Copy)]
+struct RestartOverlay;
+#[derive(Component, Clone, Copy)]
+struct RestartOverlayText;
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum OverlayButton {
+    Confirm,
+    Cancel,
+}
+
+/// Whether a restart is being confirmed or counted down, during which the overlay takes the
+/// keyboard.
+pub fn restart_pending(pending: Option<Res<PendingRestart>>) -> bool {
+    pending.is_some()
+}
+fn spawn_overlay(commands: &mut Commands, text: String, buttons: &[(OverlayButton, &str)]) {
+    commands
+        .spawn((
+            Name::new("Restart Overlay"),
+            RestartOverlay,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(20.0),
+                    ..default()
+                },
+                background_color: OVERLAY_BACKGROUND.into(),
+                z_index: ZIndex::Global(3),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                RestartOverlayText,
+                TextBundle::from_section(
+                    text,
+                    TextStyle {
+                        font: default(),
+                        font_size: OVERLAY_FONT_SIZE,
+                        color: Color::WHITE,
+                    },
+                ),
+            ));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        column_gap: Val::Px(20.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|row| {
+                    for &(button, label) in buttons {
+                        row.spawn((
+                            button,
+                            ButtonBundle {
+                                style: Style {
+                                    width: Val::Px(200.0),
+                                    height: Val::Px(65.0),
+                                    border: UiRect::all(Val::Px(5.0)),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    ..default()
+                                },
+                                border_color: BorderColor(Color::BLACK),
+                                border_radius: BorderRadius::MAX,
+                                background_color: NORMAL_BUTTON.into(),
+                                ..default()
+                            },
+                        ))
+                        .with_children(|button| {
+                            button.spawn(TextBundle::from_section(
+                                label,
+                                TextStyle {
+                                    font: default(),
+                                    font_size: OVERLAY_BUTTON_FONT_SIZE,
+                                    color: Color::srgb(0.9, 0.9, 0.9),
+                                },
+                            ));
+                        });
+                    }
+                });
+        });
+}
+fn countdown_text(timer: &Timer) -> String {
+    format!("Restarting in {}", timer.remaining_secs().ceil())
+}
+fn begin_countdown(commands: &mut Commands, rematch: bool, seed: Option<u64>) {
+    let timer = Timer::from_seconds(RESTART_COUNTDOWN, TimerMode::Once);
+    spawn_overlay(
+        commands,
+        countdown_text(&timer),
+        &[(OverlayButton::Cancel, "Cancel")],
+    );
+    commands.insert_resource(PendingRestart::Countdown {
+        timer,
+        rematch,
+        seed,
+    });
+}
+fn despawn_overlay(commands: &mut Commands, overlay: &Query<Entity, With<RestartOverlay>>) {
+    for entity in overlay {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+/// Restarting with the hotkey while the match is still going has to be confirmed first.
+fn restart_hotkey(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    pending: Option<Res<PendingRestart>>,
+    outcome: Res<MatchOutcome>,
+) {
+    if !keys.just_pressed(RESTART_KEY) || pending.is_some() {
+        return;
+    }
+    if *outcome == MatchOutcome::Undecided {
+        spawn_overlay(
+            &mut commands,
+            "Restart the match in progress?".to_string(),
+            &[
+                (OverlayButton::Confirm, "Restart"),
+                (OverlayButton::Cancel, "Cancel"),
+            ],
+        );
+        commands.insert_resource(PendingRestart::Confirming);
+    } else {
+        begin_countdown(&mut commands, false, None);
+    }
+}
+fn start_countdown(
+    mut commands: Commands,
+    mut events: EventReader<RestartRequestEvent>,
+    pending: Option<Res<PendingRestart>>,
+    overlay: Query<Entity, With<RestartOverlay>>,
+) {
+    let mut rematch = false;
+    let mut seed = None;
+    for event in events.read() {
+        rematch |= event.rematch;
+        seed = event.seed.or(seed);
+    }
+    if matches!(pending.as_deref(), Some(PendingRestart::Countdown { .. })) {
+        return;
+    }
+    despawn_overlay(&mut commands, &overlay);
+    begin_countdown(&mut commands, rematch, seed);
+}
+fn handle_overlay_input(
+    mut commands: Commands,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut interaction_query: Query<
+        (
+            &Interaction,
+            &OverlayButton,
+            &mut BackgroundColor,
+            &mut BorderColor,
+        ),
+        Changed<Interaction>,
+    >,
+    pending: Res<PendingRestart>,
+    overlay: Query<Entity, With<RestartOverlay>>,
+) {
+    let mut pressed = None;
+    for (interaction, &button, mut color, mut border_color) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => pressed = Some(button),
+            Interaction::Hovered => {
+                *color = HOVERED_BUTTON.into();
+                border_color.0 = Color::WHITE;
+            }
+            Interaction::None => {
+                *color = NORMAL_BUTTON.into();
+                border_color.0 = Color::BLACK;
+            }
+        }
+    }
+    // Escape is taken here so that it doesn't also open the pause menu.
+    if keys.clear_just_pressed(KeyCode::Escape) {
+        pressed = Some(OverlayButton::Cancel);
+    } else if keys.just_pressed(KeyCode::Enter) && matches!(*pending, PendingRestart::Confirming) {
+        pressed = Some(OverlayButton::Confirm);
+    }
+    match pressed {
+        Some(OverlayButton::Confirm) => {
+            despawn_overlay(&mut commands, &overlay);
+            begin_countdown(&mut commands, false, None);
+        }
+        Some(OverlayButton::Cancel) => {
+            despawn_overlay(&mut commands, &overlay);
+            commands.remove_resource::<PendingRestart>();
+        }
+        None => (),
+    }
+}
+/// Counts in real time, so the countdown isn't held up by pausing or slowing down the game.
+fn tick_countdown(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut pending: ResMut<PendingRestart>,
+    mut text: Query<&mut Text, With<RestartOverlayText>>,
+    overlay: Query<Entity, With<RestartOverlay>>,
+    mut restart_events: EventWriter<RestartEvent>,
+) {
+    let PendingRestart::Countdown {
+        timer,
+        rematch,
+        seed,
+    } = pending.as_mut()
+    else {
+        return;
+    };
+    timer.tick(time.delta());
+    if timer.finished() {
+        despawn_overlay(&mut commands, &overlay);
+        commands.remove_resource::<PendingRestart>();
+        restart_events.send(RestartEvent {
+            rematch: *rematch,
+            seed: *seed,
+        });
+        return;
+    }
+    for mut text in &mut text {
+        let countdown = countdown_text(timer);
+        if text.sections[0].value != countdown {
+            text.sections[0].value = countdown;
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use rusqlite::{params, Connection};
+
+use crate::{
+    battlefield::{Placements, TileCounts},
+    config::{GameConfig, MatchSeed},
+    stats::{MatchFinishedEvent, MatchStats},
+    utils::{Participant, ParticipantMap},
+};
+
+// Constants {{{
+
+const DATABASE_PATH: &str = "results.sqlite3";
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS matches (
+    id INTEGER PRIMARY KEY,
+    finished_at INTEGER NOT NULL,
+    seed INTEGER NOT NULL,
+    config_hash INTEGER NOT NULL,
+    winner TEXT,
+    duration REAL NOT NULL
+);
+CREATE TABLE IF NOT EXISTS participant_stats (
+    match_id INTEGER NOT NULL REFERENCES matches(id),
+    participant TEXT NOT NULL,
+    tiles INTEGER NOT NULL,
+    trigger_hits INTEGER NOT NULL,
+    eliminated_at REAL,
+    PRIMARY KEY (match_id, participant)
+);
+";
+
+/// Changes to the schema since it was first written, applied in order to older databases. How
+/// many have been applied is kept in `user_version`.
+const MIGRATIONS: &[&str] = &[
+    "ALTER TABLE participant_stats ADD COLUMN placement INTEGER;",
+    "ALTER TABLE participant_stats ADD COLUMN biggest_shot INTEGER;",
+];
+
+// }}}
+
+/// Records every finished match into a local SQLite database.
+pub struct ResultsDbPlugin;
+impl Plugin for ResultsDbPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            record_match.run_if(on_event::<MatchFinishedEvent>()),
+        );
+    }
+}
+
+/// The winner of a recorded match.
+#[derive(Debug, Clone, Copy)]
+pub struct Champion {
+    pub participant: Participant,
+    /// When the match ended, in seconds since the Unix epoch.
+    pub finished_at: u64,
+    /// How long the match lasted, in seconds.
+    pub duration: f32,
+    /// `None` for matches recorded before the biggest shots were.
+    pub biggest_shot: Option<u64>,
+}
+
+fn open() -> rusqlite::Result<Connection> {
+    let connection = Connection::open(DATABASE_PATH)?;
+    connection.execute_batch(SCHEMA)?;
+    let version: usize = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    for migration in MIGRATIONS.iter().skip(version) {
+        connection.execute_batch(migration)?;
+    }
+    connection.pragma_update(None, "user_version", MIGRATIONS.len())?;
+    Ok(connection)
+}
+fn record_match(
+    mut events: EventReader<MatchFinishedEvent>,
+    stats: Res<MatchStats>,
+    config: Res<GameConfig>,
+    seed: Res<MatchSeed>,
+    placements: Res<Placements>,
+    tile_counts: Res<TileCounts>,
+) {
+    let tile_counts = tile_counts.0.map(u64::from);
+    for event in events.read() {
+        let result = insert_match(event, &stats, &config, seed.0, &tile_counts, &placements);
+        if let Err(error) = result {
+            error!("Failed to record the match in {}: {}", DATABASE_PATH, error);
+        }
+    }
+}
+fn insert_match(
+    event: &MatchFinishedEvent,
+    stats: &MatchStats,
+    config: &GameConfig,
+    seed: u64,
+    tile_counts: &ParticipantMap<u64>,
+    placements: &Placements,
+) -> rusqlite::Result<()> {
+    let mut connection = open()?;
+    let transaction = connection.transaction()?;
+    let finished_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    // SQLite integers are signed, so the 64 bit seed and hash are stored by their bit patterns.
+    transaction.execute(
+        "INSERT INTO matches (finished_at, seed, config_hash, winner, duration)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            finished_at as i64,
+            seed as i64,
+            config.rules_hash() as i64,
+            event.winner.map(|winner| winner.to_string()),
+            stats.elapsed,
+        ],
+    )?;
+    let match_id = transaction.last_insert_rowid();
+    for participant in Participant::ALL {
+        transaction.execute(
+            "INSERT INTO participant_stats
+             (match_id, participant, tiles, trigger_hits, eliminated_at, placement,
+              biggest_shot)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                match_id,
+                participant.to_string(),
+                tile_counts[participant] as i64,
+                stats.trigger_hits[participant] as i64,
+                stats.eliminated_at[participant],
+                placements.0[participant].map(|placement| placement.place),
+                stats.biggest_shot[participant] as i64,
+            ],
+        )?;
+    }
+    transaction.commit()
+}
+
+/// Every match that had a winner, the latest first.
+pub fn champions() -> rusqlite::Result<Vec<Champion>> {
+    let connection = open()?;
+    let mut statement = connection.prepare(
+        "SELECT matches.winner, matches.finished_at, matches.duration,
+                participant_stats.biggest_shot
+         FROM matches JOIN participant_stats
+         ON participant_stats.match_id = matches.id
+            AND participant_stats.participant = matches.winner
+         ORDER BY matches.finished_at DESC, matches.id DESC",
+    )?;
+    let rows = statement.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, i64>(1)?,
+            row.get::<_, f64>(2)?,
+            row.get::<_, Option<i64>>(3)?,
+        ))
+    })?;
+    let mut champions = Vec::new();
+    for row in rows {
+        let (winner, finished_at, duration, biggest_shot) = row?;
+        // Rows written by a build with other participant names are left out.
+        let Some(participant) = Participant::ALL
+            .into_iter()
+            .find(|participant| participant.to_string() == winner)
+        else {
+            continue;
+        };
+        champions.push(Champion {
+            participant,
+            finished_at: finished_at as u64,
+            duration: duration as f32,
+            biggest_shot: biggest_shot.map(|shot| shot as u64),
+        });
+    }
+    Ok(champions)
+}
+
+/// Runs the `results` subcommand, printing a summary of every match recorded so far.
+pub fn run_cli() {
+    if let Err(error) = print_summary() {
+        eprintln!("Failed to read {}: {}", DATABASE_PATH, error);
+        std::process::exit(1);
+    }
+}
+fn print_summary() -> rusqlite::Result<()> {
+    let connection = open()?;
+    let (match_count, average_duration): (u64, Option<f64>) =
+        connection.query_row("SELECT COUNT(*), AVG(duration) FROM matches", [], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?;
+    println!("Matches: {}", match_count);
+    let Some(average_duration) = average_duration else {
+        return Ok(());
+    };
+    println!("Average match length: {:.1}s", average_duration);
+
+    println!("Wins:");
+    let mut statement = connection.prepare(
+        "SELECT COALESCE(winner, 'Draw'), COUNT(*), AVG(duration) FROM matches
+         GROUP BY winner ORDER BY COUNT(*) DESC",
+    )?;
+    let rows = statement.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, u64>(1)?,
+            row.get::<_, f64>(2)?,
+        ))
+    })?;
+    for row in rows {
+        let (winner, wins, duration) = row?;
+        println!(
+            "  {:<7} {:>5} ({:.1}%), {:.1}s on average",
+            winner,
+            wins,
+            wins as f64 / match_count as f64 * 100.0,
+            duration
+        );
+    }
+
+    println!("Per participant averages:");
+    let mut statement = connection.prepare(
+        "SELECT participant, AVG(tiles), AVG(trigger_hits), AVG(eliminated_at)
+         FROM participant_stats GROUP BY participant ORDER BY participant",
+    )?;
+    let rows = statement.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, f64>(1)?,
+            row.get::<_, f64>(2)?,
+            row.get::<_, Option<f64>>(3)?,
+        ))
+    })?;
+    for row in rows {
+        let (participant, tiles, trigger_hits, eliminated_at) = row?;
+        let eliminated_at =
+            eliminated_at.map_or_else(|| "never".to_string(), |time| format!("{:.1}s", time));
+        println!(
+            "  {:<7} {:>7.1} tiles, {:>7.1} trigger hits, eliminated at {}",
+            participant, tiles, trigger_hits, eliminated_at
+        );
+    }
+    Ok(())
+}
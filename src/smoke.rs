@@ -0,0 +1,183 @@
+#![allow(clippy::too_many_arguments)]
+
+use bevy::{app::AppExit, input::InputSystem, prelude::*};
+
+use crate::{
+    battlefield::{Charge, EliminationEvent, MatchOutcome},
+    utils::{Participant, ParticipantMap},
+};
+
+// Constants {{{
+
+/// How long a step can wait for its check to pass, in seconds of real time.
+const STEP_TIMEOUT: f32 = 10.0;
+/// How much faster than usual the match runs while fast-forwarding.
+const FAST_FORWARD_SPEED: f32 = 4.0;
+
+/// Starts a match from the main menu and goes through it and a restart the way a player would,
+/// checking along the way that what should be on screen is.
+const SCRIPT: &[Step] = &[
+    Step::ClickButton("Play"),
+    Step::Expect(Check::TurretCount(4)),
+    Step::Speed(FAST_FORWARD_SPEED),
+    Step::Wait(5.0),
+    Step::Expect(Check::MatchGoing),
+    Step::Eliminate(Participant::B),
+    Step::Expect(Check::TextShown("GREEN Eliminated")),
+    Step::Expect(Check::TurretCount(3)),
+    // Restarting in the middle of a match has to be confirmed, and can be backed out of.
+    Step::PressKey(KeyCode::KeyR),
+    Step::Expect(Check::TextShown("Restart the match in progress?")),
+    Step::PressKey(KeyCode::Escape),
+    Step::Wait(0.5),
+    Step::Expect(Check::MatchGoing),
+    Step::Eliminate(Participant::C),
+    Step::Eliminate(Participant::D),
+    Step::Expect(Check::Winner(Participant::A)),
+    Step::Expect(Check::TextShown("Game Over")),
+    Step::ClickButton("Restart"),
+    Step::Expect(Check::TextShown("Restarting in")),
+    Step::Expect(Check::MatchGoing),
+    Step::Expect(Check::TurretCount(4)),
+    Step::Speed(1.0),
+    Step::Wait(2.0),
+];
+
+// }}}
+
+/// Plays through [`SCRIPT`] in the full game, feeding it keyboard and mouse input like a player
+/// would, and exits with an error if any of its checks fail, to catch the plugins breaking each
+/// other. A panic anywhere fails it too.
+pub struct SmokeTestPlugin;
+impl Plugin for SmokeTestPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SmokeTest>()
+            .add_systems(PreUpdate, run_script.after(InputSystem));
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Step {
+    /// Lets the game run for this many seconds of real time.
+    Wait(f32),
+    /// Runs the simulation this many times as fast as usual from now on.
+    Speed(f32),
+    Eliminate(Participant),
+    PressKey(KeyCode),
+    /// Clicks the button with this label.
+    ClickButton(&'static str),
+    /// Waits for the check to pass, for up to [`STEP_TIMEOUT`].
+    Expect(Check),
+}
+#[derive(Debug, Clone, Copy)]
+enum Check {
+    MatchGoing,
+    Winner(Participant),
+    /// Some text on screen contains this.
+    TextShown(&'static str),
+    /// The number of turrets on the battlefield.
+    TurretCount(usize),
+}
+#[derive(Resource, Default)]
+struct SmokeTest {
+    /// The index of the step being run.
+    current: usize,
+    /// How long the current step has been running, in seconds of real time.
+    elapsed: f32,
+    /// The button clicked by the last step, to let go of.
+    clicked: Option<Entity>,
+}
+
+fn run_script(
+    mut test: ResMut<SmokeTest>,
+    real_time: Res<Time<Real>>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut buttons: Query<(Entity, &mut Interaction, &Children, &InheritedVisibility), With<Button>>,
+    texts: Query<&Text>,
+    outcome: Res<MatchOutcome>,
+    turrets: Res<ParticipantMap<Entity>>,
+    charges: Query<&Charge>,
+    mut elimination_events: EventWriter<EliminationEvent>,
+    mut exit_events: EventWriter<AppExit>,
+) {
+    if let Some(clicked) = test.clicked.take() {
+        if let Ok((_, mut interaction, ..)) = buttons.get_mut(clicked) {
+            *interaction = Interaction::None;
+        }
+    }
+    let Some(&step) = SCRIPT.get(test.current) else {
+        return;
+    };
+    test.elapsed += real_time.delta_seconds();
+    let text_shown = |needle: &str| {
+        texts.iter().any(|text| {
+            text.sections
+                .iter()
+                .any(|section| section.value.contains(needle))
+        })
+    };
+    let done = match step {
+        Step::Wait(seconds) => test.elapsed >= seconds,
+        Step::Speed(speed) => {
+            virtual_time.set_relative_speed(speed);
+            true
+        }
+        Step::Eliminate(participant) => {
+            elimination_events.send(EliminationEvent { participant });
+            true
+        }
+        Step::PressKey(key) => {
+            // Let go right away, which still leaves it just pressed for this frame.
+            keys.press(key);
+            keys.release(key);
+            true
+        }
+        Step::ClickButton(label) => {
+            let button = buttons.iter_mut().find(|(_, _, children, visibility)| {
+                visibility.get()
+                    && texts
+                        .iter_many(*children)
+                        .any(|text| text.sections.iter().any(|section| section.value == label))
+            });
+            match button {
+                Some((entity, mut interaction, ..)) => {
+                    *interaction = Interaction::Pressed;
+                    test.clicked = Some(entity);
+                    true
+                }
+                None => false,
+            }
+        }
+        Step::Expect(check) => match check {
+            Check::MatchGoing => *outcome == MatchOutcome::Undecided,
+            Check::Winner(winner) => *outcome == MatchOutcome::Winner(winner),
+            Check::TextShown(needle) => text_shown(needle),
+            Check::TurretCount(count) => {
+                Participant::ALL
+                    .into_iter()
+                    .filter(|&p| charges.get(turrets[p]).is_ok())
+                    .count()
+                    == count
+            }
+        },
+    };
+    if done {
+        info!("Smoke test step {} passed: {:?}", test.current + 1, step);
+        test.current += 1;
+        test.elapsed = 0.0;
+        if test.current == SCRIPT.len() {
+            info!("Smoke test passed");
+            exit_events.send(AppExit::Success);
+        }
+    } else if test.elapsed > STEP_TIMEOUT && !matches!(step, Step::Wait(_)) {
+        error!(
+            "Smoke test step {} failed after {}s: {:?}",
+            test.current + 1,
+            STEP_TIMEOUT,
+            step
+        );
+        test.current = SCRIPT.len();
+        exit_events.send(AppExit::error());
+    }
+}
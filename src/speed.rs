@@ -0,0 +1,355 @@
+use bevy::{prelude::*, ui::RelativeCursorPosition};
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    battlefield::Bullet,
+    config::GameConfig,
+    panel_plugin::{TriggerEvent, TriggerType},
+    state::AppState,
+};
+
+// Constants {{{
+
+/// The simulation speeds bound to the number keys 1 to 5.
+const SPEED_PRESETS: [(KeyCode, f32); 5] = [
+    (KeyCode::Digit1, 0.25),
+    (KeyCode::Digit2, 1.0),
+    (KeyCode::Digit3, 2.0),
+    (KeyCode::Digit4, 4.0),
+    (KeyCode::Digit5, 8.0),
+];
+const AUTO_THROTTLE_KEY: KeyCode = KeyCode::Digit0;
+/// The speed the auto-throttle goes up to while nothing is happening.
+const AUTO_THROTTLE_SPEED: f32 = 4.0;
+/// How long, in real time, things have to be quiet before the auto-throttle speeds up.
+const AUTO_THROTTLE_DELAY: f32 = 2.0;
+/// The longest physics step at normal speed, as in Rapier's default configuration.
+const BASE_MAX_DT: f32 = 1.0 / 60.0;
+/// How long the new speed stays on screen after changing it.
+const SPEED_TEXT_DURATION: f32 = 1.5;
+const SPEED_TEXT_FONT_SIZE: f32 = 48.0;
+
+/// The range of the speed slider, the same as the number keys'.
+const SLIDER_MIN_SPEED: f32 = 0.25;
+const SLIDER_MAX_SPEED: f32 = 8.0;
+/// The slider snaps to this many steps, evenly spaced on a log scale so 1x is one of them.
+const SLIDER_STEPS: f32 = 10.0;
+/// Far enough down to clear the speed text.
+const SLIDER_TOP: f32 = 70.0;
+const SLIDER_WIDTH: f32 = 200.0;
+/// The height that can be clicked, which is more than the track's.
+const SLIDER_HEIGHT: f32 = 24.0;
+const SLIDER_TRACK_HEIGHT: f32 = 6.0;
+const SLIDER_HANDLE_SIZE: f32 = 16.0;
+const SLIDER_TRACK_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.3);
+const SLIDER_HANDLE_COLOR: Color = Color::WHITE;
+
+// }}}
+
+/// Number key hotkeys and a slider to speed up or slow down the whole simulation, and an
+/// auto-throttle, toggled with 0, that speeds through the quiet stretches of a match. None of it
+/// works in deterministic mode, where the simulation has to go one fixed step per frame.
+pub struct SimulationSpeedPlugin {
+    /// How many times as fast as usual the simulation starts out running.
+    pub initial_speed: f32,
+}
+impl Plugin for SimulationSpeedPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SetSpeedEvent>()
+            .init_resource::<AutoThrottle>()
+            .insert_resource(InitialSpeed(self.initial_speed))
+            .add_systems(
+                Startup,
+                (
+                    setup_speed_text,
+                    (setup_speed_slider, apply_initial_speed).run_if(not(deterministic)),
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    drag_speed_slider,
+                    change_speed,
+                    toggle_auto_throttle,
+                    auto_throttle
+                        .run_if(auto_throttle_enabled.and_then(in_state(AppState::Playing))),
+                    hide_speed_text.run_if(resource_exists::<SpeedTextTimer>),
+                    update_speed_slider,
+                )
+                    .chain()
+                    .run_if(not(deterministic)),
+            );
+    }
+}
+
+/// Asks for the simulation to run this many times as fast as usual, like the number keys do.
+#[derive(Event, Clone, Copy)]
+pub struct SetSpeedEvent {
+    pub speed: f32,
+}
+#[derive(Resource, Clone, Copy)]
+struct InitialSpeed(f32);
+#[derive(Component, Clone, Copy)]
+struct SpeedText;
+#[derive(Resource)]
+struct SpeedTextTimer(Timer);
+/// The part of the slider that can be clicked and dragged along.
+#[derive(Component, Clone, Copy)]
+struct SpeedSlider;
+#[derive(Component, Clone, Copy)]
+struct SpeedSliderHandle;
+#[derive(Resource)]
+struct AutoThrottle {
+    enabled: bool,
+    /// How long it's been quiet for.
+    quiet: Timer,
+    /// Whether the auto-throttle is what has the simulation sped up right now.
+    boosted: bool,
+}
+impl Default for AutoThrottle {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            quiet: Timer::from_seconds(AUTO_THROTTLE_DELAY, TimerMode::Once),
+            boosted: false,
+        }
+    }
+}
+
+fn setup_speed_text(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Speed Text"),
+        SpeedText,
+        TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.0),
+                right: Val::Px(20.0),
+                ..default()
+            },
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font: default(),
+                    font_size: SPEED_TEXT_FONT_SIZE,
+                    color: Color::WHITE,
+                },
+            ),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+    ));
+}
+fn setup_speed_slider(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Speed Slider"),
+            SpeedSlider,
+            Interaction::default(),
+            RelativeCursorPosition::default(),
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(SLIDER_TOP),
+                    right: Val::Px(20.0),
+                    width: Val::Px(SLIDER_WIDTH),
+                    height: Val::Px(SLIDER_HEIGHT),
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|slider| {
+            slider.spawn(NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Px(SLIDER_TRACK_HEIGHT),
+                    ..default()
+                },
+                background_color: SLIDER_TRACK_COLOR.into(),
+                border_radius: BorderRadius::MAX,
+                ..default()
+            });
+            slider.spawn((
+                SpeedSliderHandle,
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        left: Val::Percent(slider_position(1.0) * 100.0),
+                        width: Val::Px(SLIDER_HANDLE_SIZE),
+                        height: Val::Px(SLIDER_HANDLE_SIZE),
+                        margin: UiRect::left(Val::Px(-SLIDER_HANDLE_SIZE / 2.0)),
+                        ..default()
+                    },
+                    background_color: SLIDER_HANDLE_COLOR.into(),
+                    border_radius: BorderRadius::MAX,
+                    ..default()
+                },
+            ));
+        });
+}
+/// Where the speed is along the slider, from 0 at the left end to 1 at the right.
+fn slider_position(speed: f32) -> f32 {
+    ((speed / SLIDER_MIN_SPEED).log2() / (SLIDER_MAX_SPEED / SLIDER_MIN_SPEED).log2())
+        .clamp(0.0, 1.0)
+}
+/// The step of the slider nearest to the position, rounded to two decimals so 1x comes out exact.
+fn slider_speed(position: f32) -> f32 {
+    let step = (position.clamp(0.0, 1.0) * SLIDER_STEPS).round() / SLIDER_STEPS;
+    let speed = SLIDER_MIN_SPEED * (SLIDER_MAX_SPEED / SLIDER_MIN_SPEED).powf(step);
+    (speed * 100.0).round() / 100.0
+}
+/// Everything that happens in the game runs on virtual time, so scaling it keeps the timers in
+/// step with each other. Physics steps are capped in length though, so the cap is scaled along
+/// with it, and the step is split up to keep fast bullets from tunneling through tiles.
+fn set_speed(speed: f32, time: &mut Time<Virtual>, rapier_config: &mut RapierConfiguration) {
+    time.set_relative_speed(speed);
+    rapier_config.timestep_mode = TimestepMode::Variable {
+        max_dt: BASE_MAX_DT * speed,
+        time_scale: 1.0,
+        substeps: speed.ceil() as usize,
+    };
+}
+fn show_speed_text(
+    commands: &mut Commands,
+    value: String,
+    text: &mut Query<(&mut Text, &mut Visibility), With<SpeedText>>,
+) {
+    for (mut text, mut visibility) in text {
+        text.sections[0].value.clone_from(&value);
+        *visibility = Visibility::Inherited;
+    }
+    commands.insert_resource(SpeedTextTimer(Timer::from_seconds(
+        SPEED_TEXT_DURATION,
+        TimerMode::Once,
+    )));
+}
+fn apply_initial_speed(
+    speed: Res<InitialSpeed>,
+    mut time: ResMut<Time<Virtual>>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+) {
+    if speed.0 != 1.0 {
+        set_speed(speed.0, &mut time, &mut rapier_config);
+    }
+}
+fn drag_speed_slider(
+    slider: Query<(&Interaction, &RelativeCursorPosition), With<SpeedSlider>>,
+    time: Res<Time<Virtual>>,
+    mut events: EventWriter<SetSpeedEvent>,
+) {
+    for (&interaction, cursor) in &slider {
+        let Some(position) = cursor
+            .normalized
+            .filter(|_| interaction == Interaction::Pressed)
+        else {
+            continue;
+        };
+        let speed = slider_speed(position.x);
+        if speed != time.relative_speed() {
+            events.send(SetSpeedEvent { speed });
+        }
+    }
+}
+/// The slider follows the speed however it was changed.
+fn update_speed_slider(
+    time: Res<Time<Virtual>>,
+    mut handles: Query<&mut Style, With<SpeedSliderHandle>>,
+) {
+    let left = Val::Percent(slider_position(time.relative_speed()) * 100.0);
+    for mut style in &mut handles {
+        if style.left != left {
+            style.left = left;
+        }
+    }
+}
+/// Picking a speed by hand turns the auto-throttle off.
+fn change_speed(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut events: EventReader<SetSpeedEvent>,
+    mut time: ResMut<Time<Virtual>>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+    mut auto_throttle: ResMut<AutoThrottle>,
+    mut text: Query<(&mut Text, &mut Visibility), With<SpeedText>>,
+) {
+    let requested = events.read().last().map(|event| event.speed);
+    let Some(speed) = SPEED_PRESETS
+        .iter()
+        .find(|(key, _)| keys.just_pressed(*key))
+        .map(|&(_, speed)| speed)
+        .or(requested)
+    else {
+        return;
+    };
+    *auto_throttle = AutoThrottle::default();
+    set_speed(speed, &mut time, &mut rapier_config);
+    show_speed_text(&mut commands, format!("{}x", speed), &mut text);
+}
+fn toggle_auto_throttle(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut time: ResMut<Time<Virtual>>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+    mut auto_throttle: ResMut<AutoThrottle>,
+    mut text: Query<(&mut Text, &mut Visibility), With<SpeedText>>,
+) {
+    if !keys.just_pressed(AUTO_THROTTLE_KEY) {
+        return;
+    }
+    let enabled = !auto_throttle.enabled;
+    *auto_throttle = AutoThrottle {
+        enabled,
+        ..default()
+    };
+    set_speed(1.0, &mut time, &mut rapier_config);
+    let value = if enabled { "Auto speed" } else { "1x" };
+    show_speed_text(&mut commands, value.to_string(), &mut text);
+}
+fn deterministic(config: Res<GameConfig>) -> bool {
+    config.deterministic
+}
+fn auto_throttle_enabled(auto_throttle: Res<AutoThrottle>) -> bool {
+    auto_throttle.enabled
+}
+/// Bullets in flight and turrets releasing their charge count as something happening. Multiplies
+/// and penalties don't, since there's hardly ever a second without one.
+fn auto_throttle(
+    real_time: Res<Time<Real>>,
+    mut time: ResMut<Time<Virtual>>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+    mut auto_throttle: ResMut<AutoThrottle>,
+    mut trigger_events: EventReader<TriggerEvent>,
+    bullets: Query<(), With<Bullet>>,
+) {
+    let released = trigger_events.read().any(|event| {
+        matches!(
+            event.trigger_type,
+            TriggerType::BurstShot | TriggerType::ChargedShot
+        )
+    });
+    if released || !bullets.is_empty() {
+        auto_throttle.quiet.reset();
+        if auto_throttle.boosted {
+            auto_throttle.boosted = false;
+            set_speed(1.0, &mut time, &mut rapier_config);
+        }
+    } else if auto_throttle.quiet.tick(real_time.delta()).just_finished() {
+        auto_throttle.boosted = true;
+        set_speed(AUTO_THROTTLE_SPEED, &mut time, &mut rapier_config);
+    }
+}
+fn hide_speed_text(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut timer: ResMut<SpeedTextTimer>,
+    mut text: Query<&mut Visibility, With<SpeedText>>,
+) {
+    if timer.0.tick(time.delta()).finished() {
+        for mut visibility in &mut text {
+            *visibility = Visibility::Hidden;
+        }
+        commands.remove_resource::<SpeedTextTimer>();
+    }
+}
@@ -0,0 +1,78 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::battlefield::{MatchOutcome, RestartEvent};
+
+/// Tracks what the game is doing, and freezes the simulation while it's in a menu. The match goes
+/// over to [`AppState::GameOver`] once its outcome is decided, and back to
+/// [`AppState::Playing`] when the next one starts.
+pub struct AppStatePlugin {
+    pub initial_state: AppState,
+}
+impl Plugin for AppStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_state(self.initial_state)
+            .add_systems(OnEnter(AppState::MainMenu), freeze_simulation)
+            .add_systems(OnExit(AppState::MainMenu), unfreeze_simulation)
+            .add_systems(OnEnter(AppState::HallOfFame), freeze_simulation)
+            .add_systems(OnExit(AppState::HallOfFame), unfreeze_simulation)
+            .add_systems(OnEnter(AppState::Paused), freeze_simulation)
+            .add_systems(OnExit(AppState::Paused), unfreeze_simulation)
+            .add_systems(
+                Update,
+                (
+                    end_match.run_if(
+                        in_state(AppState::Playing).and_then(resource_changed::<MatchOutcome>),
+                    ),
+                    start_match.run_if(on_event::<RestartEvent>()),
+                ),
+            );
+    }
+}
+
+#[derive(States, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum AppState {
+    #[default]
+    MainMenu,
+    /// The past champions, opened from the main menu.
+    HallOfFame,
+    Playing,
+    Paused,
+    /// The match is decided, but the battlefield is still up until the next one starts.
+    GameOver,
+}
+impl AppState {
+    /// What the game goes back to when it's unpaused.
+    pub fn unpaused(outcome: MatchOutcome) -> Self {
+        if outcome == MatchOutcome::Undecided {
+            Self::Playing
+        } else {
+            Self::GameOver
+        }
+    }
+}
+
+/// Pausing the virtual clock stops the timers and the particle effects, but physics has to be
+/// stopped separately.
+fn freeze_simulation(
+    mut time: ResMut<Time<Virtual>>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+) {
+    time.pause();
+    rapier_config.physics_pipeline_active = false;
+}
+fn unfreeze_simulation(
+    mut time: ResMut<Time<Virtual>>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+) {
+    time.unpause();
+    rapier_config.physics_pipeline_active = true;
+}
+fn end_match(outcome: Res<MatchOutcome>, mut next_state: ResMut<NextState<AppState>>) {
+    if *outcome != MatchOutcome::Undecided {
+        next_state.set(AppState::GameOver);
+    }
+}
+fn start_match(mut next_state: ResMut<NextState<AppState>>) {
+    next_state.set(AppState::Playing);
+}
@@ -0,0 +1,109 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    battlefield::{EliminationEvent, MatchOutcome, RestartEvent, TurretDamaged},
+    panel_plugin::{TriggerEvent, TriggerType},
+    state::AppState,
+    utils::{Participant, ParticipantMap},
+};
+
+pub struct MatchStatsPlugin;
+impl Plugin for MatchStatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<MatchFinishedEvent>()
+            .init_resource::<MatchStats>()
+            .add_systems(
+                Update,
+                (
+                    reset_stats.run_if(on_event::<RestartEvent>()),
+                    tick_match_clock.run_if(in_state(AppState::Playing)),
+                    count_trigger_hits.run_if(on_event::<TriggerEvent>()),
+                    record_damage.run_if(on_event::<TurretDamaged>()),
+                    record_eliminations.run_if(on_event::<EliminationEvent>()),
+                    finish_match.run_if(in_state(AppState::GameOver)),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Sent once when a match ends.
+#[derive(Debug, Event, Clone, Copy)]
+pub struct MatchFinishedEvent {
+    /// `None` when the match ended in a draw.
+    pub winner: Option<Participant>,
+}
+/// Running statistics about the current match.
+#[derive(Debug, Resource, Default, Clone, Serialize, Deserialize)]
+pub struct MatchStats {
+    /// Seconds since the start of the match. Stops once the match is over.
+    pub elapsed: f32,
+    pub trigger_hits: ParticipantMap<u64>,
+    /// How many of each participant's trigger hits landed in each kind of zone.
+    #[serde(default)]
+    pub zone_hits: ParticipantMap<ZoneHits>,
+    /// The charge each participant's bullets took off enemy turrets.
+    #[serde(default)]
+    pub damage_dealt: ParticipantMap<u64>,
+    /// The most charge a single one of each participant's bullets took off an enemy turret.
+    #[serde(default)]
+    pub biggest_shot: ParticipantMap<u64>,
+    /// When each participant was eliminated, in seconds since the start of the match.
+    pub eliminated_at: ParticipantMap<Option<f32>>,
+    pub finished: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct ZoneHits {
+    pub multiply: u64,
+    pub burst_shot: u64,
+    pub charged_shot: u64,
+    #[serde(default)]
+    pub penalty: u64,
+}
+
+fn reset_stats(mut stats: ResMut<MatchStats>) {
+    *stats = MatchStats::default();
+}
+fn tick_match_clock(mut stats: ResMut<MatchStats>, time: Res<Time>) {
+    stats.elapsed += time.delta_seconds();
+}
+fn count_trigger_hits(mut events: EventReader<TriggerEvent>, mut stats: ResMut<MatchStats>) {
+    for event in events.read() {
+        stats.trigger_hits[event.participant] += 1;
+        let zone_hits = &mut stats.zone_hits[event.participant];
+        match event.trigger_type {
+            TriggerType::Multiply(_) => zone_hits.multiply += 1,
+            TriggerType::BurstShot => zone_hits.burst_shot += 1,
+            TriggerType::ChargedShot => zone_hits.charged_shot += 1,
+            TriggerType::Penalty(_) => zone_hits.penalty += 1,
+        }
+    }
+}
+fn record_damage(mut events: EventReader<TurretDamaged>, mut stats: ResMut<MatchStats>) {
+    for event in events.read() {
+        stats.damage_dealt[event.attacker] =
+            stats.damage_dealt[event.attacker].saturating_add(event.amount);
+        let biggest_shot = &mut stats.biggest_shot[event.attacker];
+        *biggest_shot = (*biggest_shot).max(event.amount);
+    }
+}
+fn record_eliminations(mut events: EventReader<EliminationEvent>, mut stats: ResMut<MatchStats>) {
+    for event in events.read() {
+        stats.eliminated_at[event.participant] = Some(stats.elapsed);
+    }
+}
+fn finish_match(
+    mut stats: ResMut<MatchStats>,
+    outcome: Res<MatchOutcome>,
+    mut events: EventWriter<MatchFinishedEvent>,
+) {
+    if stats.finished {
+        return;
+    }
+    stats.finished = true;
+    events.send(MatchFinishedEvent {
+        winner: outcome.winner(),
+    });
+}
@@ -0,0 +1,125 @@
+use std::f32::consts::PI;
+
+use bevy::{app::AppExit, prelude::*};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    battlefield::{SpawnBulletEvent, BATTLEFIELD_HALF_WIDTH},
+    utils::Participant,
+};
+
+// Constants {{{
+
+const DEFAULT_DURATION: f32 = 20.0;
+/// Frames left out of the measurements at the start, while everything is still loading.
+const WARMUP_FRAMES: usize = 60;
+/// How close to the walls the bullets can spawn.
+const SPAWN_MARGIN: f32 = 20.0;
+/// The highest charge level the bullets can spawn with.
+const MAX_LEVEL: u32 = 16;
+const MIN_SPEED: f32 = 250.0;
+const MAX_SPEED: f32 = 500.0;
+const USAGE: &str = "Usage: multiply_or_release stress <bullet count> [seconds] [seed]";
+
+// }}}
+
+/// Fills the battlefield with bullets of random charges going every which way on top of a normal
+/// match, then reports how long the frames took once the time is up. Used to find out how much
+/// the physics and the rendering can take.
+pub struct StressPlugin {
+    bullet_count: usize,
+    /// How long to measure for, in seconds of real time.
+    duration: f32,
+    seed: u64,
+}
+impl Plugin for StressPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(StressTest {
+            bullet_count: self.bullet_count,
+            duration: self.duration,
+            seed: self.seed,
+            frame_times: Vec::new(),
+            frames: 0,
+        })
+        .add_systems(Startup, spawn_bullets)
+        .add_systems(Last, measure_frames);
+    }
+}
+
+#[derive(Resource)]
+struct StressTest {
+    bullet_count: usize,
+    duration: f32,
+    seed: u64,
+    /// How long every frame after the warmup took, in seconds.
+    frame_times: Vec<f32>,
+    frames: usize,
+}
+
+/// Runs the `stress` subcommand with the arguments that follow it, and returns the plugin to run
+/// it with.
+pub fn run_cli(args: &[String]) -> StressPlugin {
+    let (Some(bullet_count), Some(duration), Some(seed)) = (
+        args.first().and_then(|arg| arg.parse().ok()),
+        args.get(1)
+            .map_or(Some(DEFAULT_DURATION), |arg| arg.parse().ok()),
+        args.get(2).map_or(Some(0), |arg| arg.parse().ok()),
+    ) else {
+        eprintln!("{}", USAGE);
+        std::process::exit(2);
+    };
+    StressPlugin {
+        bullet_count,
+        duration,
+        seed,
+    }
+}
+/// The value at `fraction` of the way through the sorted values.
+fn percentile(sorted: &[f32], fraction: f32) -> f32 {
+    let index = ((sorted.len() - 1) as f32 * fraction).round() as usize;
+    sorted[index]
+}
+fn spawn_bullets(test: Res<StressTest>, mut events: EventWriter<SpawnBulletEvent>) {
+    let mut rng = StdRng::seed_from_u64(test.seed);
+    let extent = BATTLEFIELD_HALF_WIDTH - SPAWN_MARGIN;
+    events.send_batch((0..test.bullet_count).map(|_| SpawnBulletEvent {
+        owner: Participant::ALL[rng.gen_range(0..Participant::ALL.len())],
+        position: Vec2::new(
+            rng.gen_range(-extent..extent),
+            rng.gen_range(-extent..extent),
+        ),
+        charge: 1 << rng.gen_range(0..MAX_LEVEL),
+        angle: rng.gen_range(0.0..2.0 * PI),
+        speed: rng.gen_range(MIN_SPEED..MAX_SPEED),
+    }));
+}
+fn measure_frames(
+    mut test: ResMut<StressTest>,
+    time: Res<Time<Real>>,
+    mut measured: Local<f32>,
+    mut exit_events: EventWriter<AppExit>,
+) {
+    test.frames += 1;
+    if test.frames <= WARMUP_FRAMES {
+        return;
+    }
+    let delta = time.delta_seconds();
+    test.frame_times.push(delta);
+    *measured += delta;
+    if *measured < test.duration {
+        return;
+    }
+    let mut sorted = std::mem::take(&mut test.frame_times);
+    sorted.sort_by(f32::total_cmp);
+    let millis = |seconds: f32| seconds * 1000.0;
+    println!(
+        "{} bullets, {} frames over {:.1}s",
+        test.bullet_count,
+        sorted.len(),
+        *measured
+    );
+    for (name, fraction) in [("p50", 0.5), ("p90", 0.9), ("p99", 0.99), ("max", 1.0)] {
+        println!("{}: {:.2}ms", name, millis(percentile(&sorted, fraction)));
+    }
+    exit_events.send(AppExit::Success);
+}
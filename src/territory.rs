@@ -0,0 +1,118 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+use crate::{
+    battlefield::{CatchUpBonus, TileCounts},
+    utils::{BallColor, Participant, ParticipantMap, TileColor},
+};
+
+// Constants {{{
+
+const RING_RADIUS: f32 = 40.0;
+const RING_BACKGROUND_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.4);
+const RING_RESOLUTION: usize = 64;
+
+/// How far from the turret, toward the center of the battlefield, the underdog icon goes.
+const UNDERDOG_ICON_OFFSET: f32 = 70.0;
+const UNDERDOG_ICON_FONT_SIZE: f32 = 16.0;
+/// Above the tiles and bullets.
+const UNDERDOG_ICON_Z: f32 = 5.0;
+
+// }}}
+
+/// Draws a ring around each turret that fills up with that participant's share of the tiles, and
+/// marks the participant getting the catch-up bonus.
+pub struct TerritoryRingPlugin;
+impl Plugin for TerritoryRingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_underdog_icon).add_systems(
+            Update,
+            (
+                draw_territory_rings,
+                update_underdog_icon.run_if(resource_changed::<CatchUpBonus>),
+            ),
+        );
+    }
+}
+
+#[derive(Component, Clone, Copy)]
+struct UnderdogIcon;
+
+fn draw_territory_rings(
+    mut gizmos: Gizmos,
+    counts: Res<TileCounts>,
+    turrets: Res<ParticipantMap<Entity>>,
+    transforms: Query<&GlobalTransform>,
+    colors: Res<ParticipantMap<TileColor>>,
+) {
+    let total = Participant::ALL
+        .into_iter()
+        .map(|p| counts.0[p])
+        .sum::<u32>()
+        .max(1) as f32;
+    for participant in Participant::ALL {
+        let Ok(transform) = transforms.get(turrets[participant]) else {
+            continue;
+        };
+        let position = transform.translation().xy();
+        gizmos
+            .circle_2d(position, RING_RADIUS, RING_BACKGROUND_COLOR)
+            .resolution(RING_RESOLUTION);
+        let arc_angle = counts.0[participant] as f32 / total * TAU;
+        // Fill clockwise from the top.
+        gizmos
+            .arc_2d(
+                position,
+                -arc_angle / 2.0,
+                arc_angle,
+                RING_RADIUS,
+                colors[participant].0,
+            )
+            .resolution(RING_RESOLUTION);
+    }
+}
+fn setup_underdog_icon(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Underdog Icon"),
+        UnderdogIcon,
+        Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font: default(),
+                    font_size: UNDERDOG_ICON_FONT_SIZE,
+                    color: Color::WHITE,
+                },
+            ),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+    ));
+}
+fn update_underdog_icon(
+    catch_up_bonus: Res<CatchUpBonus>,
+    turrets: Res<ParticipantMap<Entity>>,
+    colors: Res<ParticipantMap<BallColor>>,
+    turret_transforms: Query<&GlobalTransform>,
+    mut icon: Query<(&mut Text, &mut Transform, &mut Visibility), With<UnderdogIcon>>,
+) {
+    let Ok((mut text, mut transform, mut visibility)) = icon.get_single_mut() else {
+        return;
+    };
+    let Some((underdog, bonus)) = catch_up_bonus.0 else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+    let Ok(turret_transform) = turret_transforms.get(turrets[underdog]) else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+    let turret_position = turret_transform.translation().xy();
+    let position = turret_position - turret_position.normalize_or_zero() * UNDERDOG_ICON_OFFSET;
+    transform.translation = position.extend(UNDERDOG_ICON_Z);
+    let section = &mut text.sections[0];
+    section.value = format!("UNDERDOG\n+{} multiply", bonus);
+    section.style.color = colors[underdog].0;
+    *visibility = Visibility::Inherited;
+}
@@ -0,0 +1,96 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    battlefield::{Bullet, Charge},
+    state::AppState,
+    utils::{BallColor, Participant, ParticipantMap},
+};
+
+// Constants {{{
+
+/// Only bullets that will reach a turret within this many seconds count as threats.
+const THREAT_LOOKAHEAD: f32 = 2.0;
+/// How close a bullet has to pass by a turret to count as heading toward it.
+const THREAT_MISS_DISTANCE: f32 = 40.0;
+/// How many charge levels smaller than the turret a bullet can be and still count as a threat.
+const THREAT_LEVEL_MARGIN: u64 = 2;
+/// The gap between the turret and the start of the arrow.
+const ARROW_OFFSET: f32 = 30.0;
+const ARROW_LENGTH: f32 = 40.0;
+const MIN_ARROW_ALPHA: f32 = 0.3;
+
+// }}}
+
+/// Arrows on the turrets pointing toward the biggest enemy bullet headed their way, so the
+/// collisions that might decide the match can be seen coming.
+pub struct ThreatIndicatorPlugin;
+impl Plugin for ThreatIndicatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            draw_threat_indicators.run_if(in_state(AppState::Playing)),
+        );
+    }
+}
+
+/// The owner and charge level of the biggest bullet that will hit the turret at `position` soon,
+/// and the direction it's coming from.
+fn biggest_threat<'a>(
+    owner: Participant,
+    position: Vec2,
+    bullets: impl Iterator<
+        Item = (
+            &'a Participant,
+            &'a Charge,
+            &'a GlobalTransform,
+            &'a Velocity,
+        ),
+    >,
+) -> Option<(Participant, u64, Vec2)> {
+    bullets
+        .filter(|(participant, ..)| **participant != owner)
+        .filter_map(|(&participant, charge, transform, velocity)| {
+            let offset = position - transform.translation().xy();
+            let speed_squared = velocity.linvel.length_squared();
+            if speed_squared == 0.0 {
+                return None;
+            }
+            let time = offset.dot(velocity.linvel) / speed_squared;
+            let miss = (offset - velocity.linvel * time).length();
+            (time > 0.0 && time < THREAT_LOOKAHEAD && miss < THREAT_MISS_DISTANCE)
+                .then(|| (participant, charge.level(), -offset.normalize_or_zero()))
+        })
+        .max_by_key(|&(_, level, _)| level)
+}
+/// The arrows take the color of the attacker, and are brighter the bigger the threat is compared to
+/// the turret.
+fn draw_threat_indicators(
+    mut gizmos: Gizmos,
+    turret_entities: Res<ParticipantMap<Entity>>,
+    colors: Res<ParticipantMap<BallColor>>,
+    turrets: Query<(&Charge, &GlobalTransform), Without<Bullet>>,
+    bullets: Query<(&Participant, &Charge, &GlobalTransform, &Velocity), With<Bullet>>,
+) {
+    for participant in Participant::ALL {
+        let Ok((charge, transform)) = turrets.get(turret_entities[participant]) else {
+            continue;
+        };
+        let position = transform.translation().xy();
+        let Some((attacker, level, direction)) =
+            biggest_threat(participant, position, bullets.iter())
+        else {
+            continue;
+        };
+        if level + THREAT_LEVEL_MARGIN < charge.level() {
+            continue;
+        }
+        let intensity = (level as f32 / charge.level().max(1) as f32).clamp(MIN_ARROW_ALPHA, 1.0);
+        let start = position + direction * ARROW_OFFSET;
+        gizmos.arrow_2d(
+            start,
+            start + direction * ARROW_LENGTH,
+            colors[attacker].0.with_alpha(intensity),
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+
+use crate::{
+    battlefield::{RestartEvent, TileCounts, BATTLEFIELD_HALF_WIDTH},
+    state::AppState,
+    utils::{BallColor, Participant, ParticipantMap},
+};
+
+// Constants {{{
+
+/// Seconds between samples of the tile counts.
+const SAMPLE_INTERVAL: f32 = 2.0;
+
+const CHART_SIZE: Vec2 = Vec2::new(480.0, 150.0);
+/// The distance from the bottom of the battlefield to the middle of the chart, which keeps it
+/// below the standings on the game over screen.
+const CHART_BOTTOM_INSET: f32 = 110.0;
+const CHART_BACKGROUND_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.6);
+const CHART_GRID_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.15);
+
+// }}}
+
+/// Keeps track of each participant's share of the tiles over the whole match, and charts it once
+/// the match is over, so the ebb and flow of the match can be seen at a glance.
+pub struct TerritoryTimelinePlugin;
+impl Plugin for TerritoryTimelinePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TerritoryTimeline>().add_systems(
+            Update,
+            (
+                clear_timeline.run_if(on_event::<RestartEvent>()),
+                sample_tile_counts.run_if(
+                    in_state(AppState::Playing)
+                        .and_then(on_timer(Duration::from_secs_f32(SAMPLE_INTERVAL))),
+                ),
+                draw_timeline.run_if(in_state(AppState::GameOver)),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Every participant's share of the tiles, oldest first, from the start of the match.
+#[derive(Resource, Default)]
+struct TerritoryTimeline(ParticipantMap<Vec<f32>>);
+
+fn clear_timeline(mut timeline: ResMut<TerritoryTimeline>) {
+    *timeline = TerritoryTimeline::default();
+}
+fn sample_tile_counts(mut timeline: ResMut<TerritoryTimeline>, counts: Res<TileCounts>) {
+    let total = Participant::ALL
+        .into_iter()
+        .map(|p| counts.0[p])
+        .sum::<u32>()
+        .max(1) as f32;
+    for participant in Participant::ALL {
+        timeline.0[participant].push(counts.0[participant] as f32 / total);
+    }
+}
+fn draw_timeline(
+    mut gizmos: Gizmos,
+    timeline: Res<TerritoryTimeline>,
+    colors: Res<ParticipantMap<BallColor>>,
+) {
+    let sample_count = timeline.0.a.len();
+    if sample_count < 2 {
+        return;
+    }
+    let center = Vec2::new(0.0, -BATTLEFIELD_HALF_WIDTH + CHART_BOTTOM_INSET);
+    let origin = center - CHART_SIZE / 2.0;
+    gizmos.rect_2d(center, 0.0, CHART_SIZE, CHART_BACKGROUND_COLOR);
+    for quarter in 1..4 {
+        let y = CHART_SIZE.y * quarter as f32 / 4.0;
+        gizmos.line_2d(
+            origin + Vec2::new(0.0, y),
+            origin + Vec2::new(CHART_SIZE.x, y),
+            CHART_GRID_COLOR,
+        );
+    }
+    // The whole match is squeezed into the width of the chart, however long it went on for.
+    let step = CHART_SIZE.x / (sample_count - 1) as f32;
+    for participant in Participant::ALL {
+        gizmos.linestrip_2d(
+            timeline.0[participant]
+                .iter()
+                .enumerate()
+                .map(|(i, &share)| origin + Vec2::new(i as f32 * step, share * CHART_SIZE.y)),
+            colors[participant].0,
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use bevy::{prelude::*, render::view::RenderLayers};
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    cameras::{LEFT_PANEL_LAYER, RIGHT_PANEL_LAYER},
+    collision_groups::{PANEL_BALLS, PANEL_OBSTACLES},
+    panel_plugin::{WorkerBall, WORKER_BALL_RADIUS, WORKER_BALL_RESTITUTION_COEFFICIENT},
+    utils::{BallColor, Participant, ParticipantMap},
+};
+
+// Constants {{{
+
+const TRAJECTORY_KEY: KeyCode = KeyCode::KeyP;
+/// How far ahead the trajectories are predicted, in seconds.
+const PREDICTION_TIME: f32 = 1.5;
+const PREDICTION_STEP: f32 = 1.0 / 60.0;
+const TRAJECTORY_ALPHA: f32 = 0.35;
+
+// }}}
+
+/// An analyst overlay, toggled with P, drawing where each worker ball is headed over the next
+/// moment. Used to tune the peg layout without waiting for the marbles to get there.
+///
+/// The paths come from stepping each ball forward under gravity and bouncing it off the pegs and
+/// walls it runs into, so they ignore the other balls and drift from what actually happens the
+/// further ahead they go.
+pub struct TrajectoryPreviewPlugin;
+impl Plugin for TrajectoryPreviewPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_gizmo_group::<TrajectoryGizmos>()
+            .add_systems(Startup, setup_trajectory_gizmos)
+            .add_systems(
+                Update,
+                (
+                    toggle_trajectories,
+                    draw_trajectories.run_if(trajectories_enabled),
+                ),
+            );
+    }
+}
+
+#[derive(Default, Reflect, GizmoConfigGroup)]
+struct TrajectoryGizmos;
+
+fn setup_trajectory_gizmos(mut config_store: ResMut<GizmoConfigStore>) {
+    let (config, _) = config_store.config_mut::<TrajectoryGizmos>();
+    config.enabled = false;
+    config.render_layers = RenderLayers::from_layers(&[LEFT_PANEL_LAYER, RIGHT_PANEL_LAYER]);
+}
+fn toggle_trajectories(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut config_store: ResMut<GizmoConfigStore>,
+) {
+    if keys.just_pressed(TRAJECTORY_KEY) {
+        let (config, _) = config_store.config_mut::<TrajectoryGizmos>();
+        config.enabled = !config.enabled;
+    }
+}
+fn trajectories_enabled(config_store: Res<GizmoConfigStore>) -> bool {
+    config_store.config::<TrajectoryGizmos>().0.enabled
+}
+/// The points a ball passes through, starting from where it is now.
+fn predict_trajectory(
+    rapier: &RapierContext,
+    mut position: Vec2,
+    mut velocity: Vec2,
+    gravity: Vec2,
+) -> Vec<Vec2> {
+    let filter = QueryFilter::new()
+        .exclude_sensors()
+        .groups(CollisionGroups::new(PANEL_BALLS, PANEL_OBSTACLES));
+    let steps = (PREDICTION_TIME / PREDICTION_STEP) as usize;
+    let mut points = Vec::with_capacity(steps + 1);
+    points.push(position);
+    for _ in 0..steps {
+        velocity += gravity * PREDICTION_STEP;
+        let step = velocity * PREDICTION_STEP;
+        let length = step.length();
+        if length == 0.0 {
+            continue;
+        }
+        let direction = step / length;
+        // Cast from the center, but stop a radius short of whatever it hits, so the ball bounces
+        // off its edge rather than its center.
+        match rapier.cast_ray_and_get_normal(
+            position,
+            direction,
+            length + WORKER_BALL_RADIUS,
+            true,
+            filter,
+        ) {
+            Some((_, hit)) if hit.normal != Vec2::ZERO => {
+                position += direction * (hit.time_of_impact - WORKER_BALL_RADIUS).max(0.0);
+                velocity = (velocity - 2.0 * velocity.dot(hit.normal) * hit.normal)
+                    * WORKER_BALL_RESTITUTION_COEFFICIENT;
+            }
+            _ => position += step,
+        }
+        points.push(position);
+    }
+    points
+}
+fn draw_trajectories(
+    mut gizmos: Gizmos<TrajectoryGizmos>,
+    rapier: Res<RapierContext>,
+    rapier_config: Res<RapierConfiguration>,
+    colors: Res<ParticipantMap<BallColor>>,
+    balls: Query<(&GlobalTransform, &Velocity, &GravityScale, &Participant), With<WorkerBall>>,
+) {
+    for (transform, velocity, gravity_scale, &participant) in &balls {
+        let points = predict_trajectory(
+            &rapier,
+            transform.translation().xy(),
+            velocity.linvel,
+            rapier_config.gravity * gravity_scale.0,
+        );
+        gizmos.linestrip_2d(points, colors[participant].0.with_alpha(TRAJECTORY_ALPHA));
+    }
+}
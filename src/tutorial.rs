@@ -0,0 +1,234 @@
+#![allow(clippy::too_many_arguments)]
+
+use bevy::prelude::*;
+
+use crate::{
+    battlefield::Bullet,
+    cameras::{BattlefieldCamera, PanelCamera},
+    panel_plugin::TriggerType,
+    utils::{Participant, ParticipantMap},
+};
+
+// Constants {{{
+
+/// How fast the match runs during the tutorial.
+const TUTORIAL_SPEED: f32 = 0.4;
+const ADVANCE_KEY: KeyCode = KeyCode::Space;
+
+const CALLOUT_WIDTH: f32 = 320.0;
+const CALLOUT_FONT_SIZE: f32 = 20.0;
+const CALLOUT_BACKGROUND: Color = Color::srgba(0.05, 0.05, 0.05, 0.9);
+const CALLOUT_BORDER_COLOR: Color = Color::WHITE;
+/// The size of the dot marking what the callout is about.
+const MARKER_SIZE: f32 = 14.0;
+/// How far above the marked point the callout sits.
+const CALLOUT_GAP: f32 = 24.0;
+
+const STEPS: [(CalloutAnchor, &str); 3] = [
+    (
+        CalloutAnchor::MultiplyZone,
+        "Marbles that land in this zone multiply their turret's charge by 4. The zones next to \
+        it multiply by 2, and the outer ones fire the charge off.",
+    ),
+    (
+        CalloutAnchor::Turret,
+        "This is a turret. The number is its charge, which it fires at the other corners when \
+        one of its marbles lands in a release zone. Losing all of it means elimination.",
+    ),
+    (
+        CalloutAnchor::Bullet,
+        "Every enemy tile a shot hits takes one charge from the shot and changes color. Watch \
+        this one eat its way into enemy territory.",
+    ),
+];
+
+// }}}
+
+/// A slowed down match with callouts pointing out the parts of the game one at a time.
+pub struct TutorialPlugin;
+impl Plugin for TutorialPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, start_tutorial).add_systems(
+            Update,
+            (advance_tutorial, position_callout)
+                .chain()
+                .run_if(resource_exists::<Tutorial>),
+        );
+    }
+}
+
+/// What a tutorial step's callout points at.
+#[derive(Clone, Copy)]
+enum CalloutAnchor {
+    /// The center trigger zone of the first panel.
+    MultiplyZone,
+    /// The first participant's turret.
+    Turret,
+    /// The first participant's oldest bullet, waiting for one if there are none yet.
+    Bullet,
+}
+/// Present while the tutorial is running.
+#[derive(Resource)]
+pub struct Tutorial {
+    step: usize,
+}
+#[derive(Component, Clone, Copy)]
+struct Callout;
+#[derive(Component, Clone, Copy)]
+struct CalloutText;
+#[derive(Component, Clone, Copy)]
+struct CalloutMarker;
+
+fn start_tutorial(mut commands: Commands, mut time: ResMut<Time<Virtual>>) {
+    time.set_relative_speed(TUTORIAL_SPEED);
+    commands.insert_resource(Tutorial { step: 0 });
+    commands.spawn((
+        Name::new("Tutorial Marker"),
+        CalloutMarker,
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Px(MARKER_SIZE),
+                height: Val::Px(MARKER_SIZE),
+                border: UiRect::all(Val::Px(3.0)),
+                ..default()
+            },
+            border_color: CALLOUT_BORDER_COLOR.into(),
+            border_radius: BorderRadius::MAX,
+            visibility: Visibility::Hidden,
+            z_index: ZIndex::Global(1),
+            ..default()
+        },
+    ));
+    commands
+        .spawn((
+            Name::new("Tutorial Callout"),
+            Callout,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Px(CALLOUT_WIDTH),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(8.0),
+                    padding: UiRect::all(Val::Px(12.0)),
+                    border: UiRect::all(Val::Px(2.0)),
+                    ..default()
+                },
+                background_color: CALLOUT_BACKGROUND.into(),
+                border_color: CALLOUT_BORDER_COLOR.into(),
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Global(1),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                CalloutText,
+                TextBundle::from_section(
+                    STEPS[0].1,
+                    TextStyle {
+                        font: default(),
+                        font_size: CALLOUT_FONT_SIZE,
+                        color: Color::WHITE,
+                    },
+                ),
+            ));
+            parent.spawn(TextBundle::from_section(
+                "Press Space to continue",
+                TextStyle {
+                    font: default(),
+                    font_size: CALLOUT_FONT_SIZE * 0.8,
+                    color: Color::srgb(0.7, 0.7, 0.7),
+                },
+            ));
+        });
+}
+fn advance_tutorial(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut tutorial: ResMut<Tutorial>,
+    mut time: ResMut<Time<Virtual>>,
+    mut text: Query<&mut Text, With<CalloutText>>,
+    nodes: Query<Entity, Or<(With<Callout>, With<CalloutMarker>)>>,
+) {
+    if !keys.just_pressed(ADVANCE_KEY) {
+        return;
+    }
+    tutorial.step += 1;
+    let Some(&(_, step_text)) = STEPS.get(tutorial.step) else {
+        // That was the last step, so the match carries on at the normal speed.
+        time.set_relative_speed(1.0);
+        commands.remove_resource::<Tutorial>();
+        for entity in &nodes {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    };
+    for mut text in &mut text {
+        text.sections[0].value = step_text.to_string();
+    }
+}
+/// Keeps the callout next to what it's pointing at, which may be moving, and hides it while
+/// there's nothing to point at.
+fn position_callout(
+    tutorial: Res<Tutorial>,
+    ui_scale: Res<UiScale>,
+    turrets: Res<ParticipantMap<Entity>>,
+    zones: Query<(&TriggerType, &GlobalTransform)>,
+    bullets: Query<(Entity, &Participant, &GlobalTransform), With<Bullet>>,
+    transforms: Query<&GlobalTransform>,
+    battlefield_camera: Query<(&Camera, &GlobalTransform), With<BattlefieldCamera>>,
+    panel_cameras: Query<(&Camera, &GlobalTransform), With<PanelCamera>>,
+    mut callout: Query<(&mut Style, &mut Visibility, &Node), With<Callout>>,
+    mut marker: Query<(&mut Style, &mut Visibility), (With<CalloutMarker>, Without<Callout>)>,
+) {
+    let (anchor, _) = STEPS[tutorial.step];
+    let target = match anchor {
+        CalloutAnchor::MultiplyZone => zones
+            .iter()
+            .filter_map(|(&trigger_type, transform)| match trigger_type {
+                TriggerType::Multiply(factor) => Some((factor, transform)),
+                _ => None,
+            })
+            .max_by_key(|&(factor, _)| factor)
+            .map(|(_, transform)| (transform.translation(), true)),
+        CalloutAnchor::Turret => transforms
+            .get(turrets[Participant::A])
+            .ok()
+            .map(|transform| (transform.translation(), false)),
+        CalloutAnchor::Bullet => bullets
+            .iter()
+            .filter(|(_, &owner, _)| owner == Participant::A)
+            .min_by_key(|(entity, _, _)| *entity)
+            .map(|(_, _, transform)| (transform.translation(), false)),
+    };
+    let position = target.and_then(|(translation, in_panel)| {
+        let (camera, camera_transform) = if in_panel {
+            panel_cameras.iter().next()?
+        } else {
+            battlefield_camera.get_single().ok()?
+        };
+        camera.world_to_viewport(camera_transform, translation)
+    });
+    let (
+        Ok((mut callout_style, mut callout_visibility, callout_node)),
+        Ok((mut marker_style, mut marker_visibility)),
+    ) = (callout.get_single_mut(), marker.get_single_mut())
+    else {
+        return;
+    };
+    let Some(position) = position else {
+        *callout_visibility = Visibility::Hidden;
+        *marker_visibility = Visibility::Hidden;
+        return;
+    };
+    // `Val::Px` gets multiplied by the UI scale, so it has to be divided out here.
+    let position = position / ui_scale.0;
+    let callout_size = callout_node.size() / ui_scale.0;
+    *callout_visibility = Visibility::Inherited;
+    *marker_visibility = Visibility::Inherited;
+    marker_style.left = Val::Px(position.x - MARKER_SIZE / 2.0);
+    marker_style.top = Val::Px(position.y - MARKER_SIZE / 2.0);
+    callout_style.left = Val::Px((position.x - callout_size.x / 2.0).max(0.0));
+    callout_style.top = Val::Px((position.y - CALLOUT_GAP - callout_size.y).max(0.0));
+}
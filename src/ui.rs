@@ -1,32 +1,67 @@
 #![allow(clippy::type_complexity, clippy::too_many_arguments)]
 
 use crate::{
-    battlefield::{game_is_going, EliminationEvent, RestartEvent},
-    utils::{BallColor, ParticipantMap},
+    battlefield::{EliminationEvent, MatchOutcome, Placements, RestartEvent},
+    config::{DisplayConfig, GameConfig, MatchSeed, ScreenLayout},
+    focus::Focusable,
+    match_code,
+    restart::RestartRequestEvent,
+    state::AppState,
+    stats::MatchStats,
+    utils::{BallColor, Participant, ParticipantMap},
 };
-use bevy::prelude::*;
+use bevy::{input::InputSystem, prelude::*, ui::UiSystem, window::PrimaryWindow};
+use std::collections::VecDeque;
 
 pub struct UIPlugin;
 impl Plugin for UIPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, setup).add_systems(
-            Update,
-            (
-                button_system.run_if(not(game_is_going)),
-                restart.run_if(on_event::<RestartEvent>()),
-                add_elimination_text.run_if(on_event::<EliminationEvent>()),
-                remove_elimination_text.run_if(any_with_component::<EliminationTextTimer>),
-                add_game_over_text.run_if(not(game_is_going)),
-            ),
-        );
+        app.init_resource::<NotificationQueue>()
+            .add_systems(Startup, setup)
+            .add_systems(
+                PreUpdate,
+                type_seed
+                    .run_if(resource_exists::<SeedEntry>)
+                    .after(InputSystem)
+                    .before(UiSystem::Focus),
+            )
+            .add_systems(
+                Update,
+                (
+                    (button_system, update_seed_button).run_if(in_state(AppState::GameOver)),
+                    restart.run_if(on_event::<RestartEvent>()),
+                    (
+                        queue_elimination_text.run_if(on_event::<EliminationEvent>()),
+                        show_notifications,
+                        animate_notifications.run_if(any_with_component::<Notification>),
+                    )
+                        .chain(),
+                    add_game_over_text.run_if(in_state(AppState::GameOver)),
+                    (update_ui_scale, layout_play_area).chain(),
+                ),
+            );
     }
 }
 
 // CONSTANTS {{{
 
+/// How long an elimination is shown for, counting sliding in and out.
 const ELIMINATION_TEXT_DURATION: f32 = 4.0;
+/// The most eliminations shown at once. Any more wait for room.
+const MAX_NOTIFICATIONS: usize = 2;
+/// How long an elimination stays once it's all the way in before making room for one waiting.
+const MIN_NOTIFICATION_HOLD: f32 = 1.0;
+const NOTIFICATION_SLIDE_DURATION: f32 = 0.3;
+const NOTIFICATION_SLIDE_DISTANCE: f32 = 160.0;
+/// Far enough up to clear the bullet pressure row.
+const NOTIFICATION_STACK_BOTTOM: f32 = 48.0;
 const ELIMINATION_TEXT_FONT_SIZE: f32 = 48.0;
 const GAME_OVER_TEXT_FONT_SIZE: f32 = 72.0;
+const MATCH_CODE_FONT_SIZE: f32 = 28.0;
+const STANDINGS_FONT_SIZE: f32 = 32.0;
+const ZONE_HITS_FONT_SIZE: f32 = 24.0;
+
+const SEED_BUTTON_LABEL: &str = "Restart with seed";
 
 const NORMAL_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
 const HOVERED_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
@@ -36,30 +71,65 @@ const HOVERED_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
 
 #[derive(Clone, Copy, Component)]
 struct UIRoot;
+/// Marker for root UI nodes that should cover the play area rather than the whole window, so that
+/// what's in them stays next to the battlefield on ultrawide and portrait windows.
+#[derive(Clone, Copy, Component)]
+pub struct PlayAreaNode;
+/// The row holding the buttons shown once the match is over.
+#[derive(Clone, Copy, Component)]
+struct GameOverButtons;
 #[derive(Clone, Copy, Component)]
 struct RestartButton;
+/// Restarts with the same seed, to see whether the outcome was down to luck.
+#[derive(Clone, Copy, Component)]
+struct RematchButton;
+/// Opens the [`SeedEntry`] to restart with a seed typed in.
+#[derive(Clone, Copy, Component)]
+struct SeedButton;
+/// The digits of the seed being typed in. It takes the keyboard while it's there.
+#[derive(Resource, Default)]
+struct SeedEntry(String);
+/// The column the eliminations are shown in, the newest at the bottom, apart from the game over
+/// text so they don't push each other around.
+#[derive(Clone, Copy, Component)]
+struct NotificationStack;
 #[derive(Component)]
-struct EliminationTextTimer(Timer);
+struct Notification {
+    /// How long it's been shown for, in seconds.
+    age: f32,
+    /// How long it's shown for in all. Cut short when others are waiting for room.
+    duration: f32,
+}
+impl Notification {
+    fn leaving(&self) -> bool {
+        self.age >= self.duration - NOTIFICATION_SLIDE_DURATION
+    }
+}
+/// Eliminations waiting for room in the stack, in the order they happened.
+#[derive(Resource, Default)]
+struct NotificationQueue(VecDeque<Participant>);
 #[derive(Bundle)]
 struct EliminationTextBundle {
     text_bundle: TextBundle,
-    timer: EliminationTextTimer,
+    notification: Notification,
 }
 impl EliminationTextBundle {
     fn new(participant: impl std::fmt::Display, color: Color) -> Self {
+        let mut text_bundle = TextBundle::from_section(
+            format!("{} Eliminated", participant),
+            TextStyle {
+                font: default(),
+                font_size: ELIMINATION_TEXT_FONT_SIZE,
+                color: color.with_alpha(0.0),
+            },
+        );
+        text_bundle.style.left = Val::Px(NOTIFICATION_SLIDE_DISTANCE);
         EliminationTextBundle {
-            text_bundle: TextBundle::from_section(
-                format!("{} Eliminated", participant),
-                TextStyle {
-                    font: default(),
-                    font_size: ELIMINATION_TEXT_FONT_SIZE,
-                    color,
-                },
-            ),
-            timer: EliminationTextTimer(Timer::from_seconds(
-                ELIMINATION_TEXT_DURATION,
-                TimerMode::Once,
-            )),
+            text_bundle,
+            notification: Notification {
+                age: 0.0,
+                duration: ELIMINATION_TEXT_DURATION,
+            },
         }
     }
 }
@@ -67,8 +137,10 @@ impl EliminationTextBundle {
 fn setup(mut commands: Commands) {
     commands.spawn((
         UIRoot,
+        PlayAreaNode,
         NodeBundle {
             style: Style {
+                position_type: PositionType::Absolute,
                 width: Val::Percent(100.),
                 height: Val::Percent(100.),
                 flex_direction: FlexDirection::Column,
@@ -80,33 +152,80 @@ fn setup(mut commands: Commands) {
             ..default()
         },
     ));
+    commands.spawn((
+        Name::new("Notification Stack"),
+        NotificationStack,
+        PlayAreaNode,
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::End,
+                align_items: AlignItems::Center,
+                padding: UiRect::bottom(Val::Px(NOTIFICATION_STACK_BOTTOM)),
+                ..default()
+            },
+            ..default()
+        },
+    ));
+    let buttons = commands
+        .spawn((
+            GameOverButtons,
+            NodeBundle {
+                style: Style {
+                    justify_self: JustifySelf::Center,
+                    align_self: AlignSelf::Center,
+                    column_gap: Val::Px(20.0),
+                    ..default()
+                },
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+        ))
+        .id();
+    game_over_button(&mut commands, buttons, RestartButton, "Restart", 200.0);
+    game_over_button(
+        &mut commands,
+        buttons,
+        RematchButton,
+        "Rematch (same seed)",
+        380.0,
+    );
+    game_over_button(&mut commands, buttons, SeedButton, SEED_BUTTON_LABEL, 460.0);
+}
+fn game_over_button(
+    commands: &mut Commands,
+    parent: Entity,
+    marker: impl Component,
+    label: &str,
+    width: f32,
+) {
     let button = commands
         .spawn((
-            RestartButton,
+            marker,
+            Focusable,
             ButtonBundle {
                 style: Style {
-                    width: Val::Px(200.0),
+                    width: Val::Px(width),
                     height: Val::Px(65.0),
                     border: UiRect::all(Val::Px(5.0)),
-                    justify_self: JustifySelf::Center,
-                    align_self: AlignSelf::Center,
                     // horizontally center child text
                     justify_content: JustifyContent::Center,
                     // vertically center child text
                     align_items: AlignItems::Center,
                     ..default()
                 },
-                visibility: Visibility::Hidden,
                 border_color: BorderColor(Color::BLACK),
                 border_radius: BorderRadius::MAX,
                 background_color: NORMAL_BUTTON.into(),
                 ..default()
             },
         ))
+        .set_parent(parent)
         .id();
     commands
         .spawn(TextBundle::from_section(
-            "Restart",
+            label,
             TextStyle {
                 font: default(),
                 font_size: 40.0,
@@ -117,15 +236,29 @@ fn setup(mut commands: Commands) {
 }
 fn button_system(
     mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor, &mut BorderColor),
-        (Changed<Interaction>, With<Button>),
+        (
+            &Interaction,
+            &mut BackgroundColor,
+            &mut BorderColor,
+            Has<RematchButton>,
+            Has<SeedButton>,
+        ),
+        (
+            Changed<Interaction>,
+            Or<(With<RestartButton>, With<RematchButton>, With<SeedButton>)>,
+        ),
     >,
-    mut events: EventWriter<RestartEvent>,
+    mut commands: Commands,
+    mut events: EventWriter<RestartRequestEvent>,
 ) {
-    for (interaction, mut color, mut border_color) in &mut interaction_query {
+    for (interaction, mut color, mut border_color, rematch, seed) in &mut interaction_query {
         match *interaction {
+            Interaction::Pressed if seed => commands.init_resource::<SeedEntry>(),
             Interaction::Pressed => {
-                events.send_default();
+                events.send(RestartRequestEvent {
+                    rematch,
+                    seed: None,
+                });
                 // *color = PRESSED_BUTTON.into();
                 // border_color.0 = RED.into();
             }
@@ -140,45 +273,159 @@ fn button_system(
         }
     }
 }
-fn add_elimination_text(
+fn typed_digit(key: KeyCode) -> Option<char> {
+    let digit = match key {
+        KeyCode::Digit0 | KeyCode::Numpad0 => '0',
+        KeyCode::Digit1 | KeyCode::Numpad1 => '1',
+        KeyCode::Digit2 | KeyCode::Numpad2 => '2',
+        KeyCode::Digit3 | KeyCode::Numpad3 => '3',
+        KeyCode::Digit4 | KeyCode::Numpad4 => '4',
+        KeyCode::Digit5 | KeyCode::Numpad5 => '5',
+        KeyCode::Digit6 | KeyCode::Numpad6 => '6',
+        KeyCode::Digit7 | KeyCode::Numpad7 => '7',
+        KeyCode::Digit8 | KeyCode::Numpad8 => '8',
+        KeyCode::Digit9 | KeyCode::Numpad9 => '9',
+        _ => return None,
+    };
+    Some(digit)
+}
+/// Runs before anything else reads the keyboard, and clears it afterwards, so the digits don't
+/// change the speed and `Enter` and `Esc` don't press buttons or open the pause menu as well.
+fn type_seed(
     mut commands: Commands,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut entry: ResMut<SeedEntry>,
+    mut events: EventWriter<RestartRequestEvent>,
+) {
+    for digit in keys.get_just_pressed().filter_map(|&key| typed_digit(key)) {
+        let digits = format!("{}{}", entry.0, digit);
+        // Anything that wouldn't fit in a seed isn't let in.
+        if digits.parse::<u64>().is_ok() {
+            entry.0 = digits;
+        }
+    }
+    if keys.just_pressed(KeyCode::Backspace) {
+        entry.0.pop();
+    }
+    if keys.just_pressed(KeyCode::Enter) {
+        if let Ok(seed) = entry.0.parse() {
+            events.send(RestartRequestEvent {
+                rematch: false,
+                seed: Some(seed),
+            });
+        }
+        commands.remove_resource::<SeedEntry>();
+    } else if keys.just_pressed(KeyCode::Escape) {
+        commands.remove_resource::<SeedEntry>();
+    }
+    keys.reset_all();
+}
+fn update_seed_button(
+    entry: Option<Res<SeedEntry>>,
+    buttons: Query<&Children, With<SeedButton>>,
+    mut texts: Query<&mut Text>,
+) {
+    let label = entry.map_or_else(
+        || SEED_BUTTON_LABEL.to_string(),
+        |entry| format!("Seed: {}_", entry.0),
+    );
+    for &child in buttons.iter().flatten() {
+        let Ok(mut text) = texts.get_mut(child) else {
+            continue;
+        };
+        if text.sections[0].value != label {
+            text.sections[0].value = label.clone();
+        }
+    }
+}
+fn queue_elimination_text(
     mut events: EventReader<EliminationEvent>,
+    mut queue: ResMut<NotificationQueue>,
+) {
+    queue.0.extend(events.read().map(|event| event.participant));
+}
+/// Moves eliminations from the queue into the stack as there's room, and hurries the oldest one
+/// out when there isn't.
+fn show_notifications(
+    mut commands: Commands,
+    mut queue: ResMut<NotificationQueue>,
+    mut notifications: Query<&mut Notification>,
+    stack: Query<Entity, With<NotificationStack>>,
     colors: Res<ParticipantMap<BallColor>>,
-    ui_root: Query<Entity, With<UIRoot>>,
 ) {
-    for event in events.read() {
+    if queue.0.is_empty() {
+        return;
+    }
+    let mut shown = notifications
+        .iter()
+        .filter(|notification| !notification.leaving())
+        .count();
+    while shown < MAX_NOTIFICATIONS {
+        let Some(participant) = queue.0.pop_front() else {
+            return;
+        };
         commands
             .spawn(EliminationTextBundle::new(
-                event.participant,
-                colors.get(event.participant).0,
+                participant,
+                colors[participant].0,
             ))
-            .set_parent(ui_root.single());
+            .set_parent(stack.single());
+        shown += 1;
+    }
+    let oldest = notifications
+        .iter_mut()
+        .filter(|notification| !notification.leaving())
+        .max_by(|a, b| a.age.total_cmp(&b.age));
+    if let Some(mut oldest) = oldest {
+        let leave_at = oldest
+            .age
+            .max(NOTIFICATION_SLIDE_DURATION + MIN_NOTIFICATION_HOLD);
+        oldest.duration = oldest.duration.min(leave_at + NOTIFICATION_SLIDE_DURATION);
     }
 }
-fn remove_elimination_text(
+/// Slides the eliminations in from the side and fades them in, then the other way out.
+fn animate_notifications(
     mut commands: Commands,
-    mut query: Query<(Entity, &mut EliminationTextTimer)>,
+    mut notifications: Query<(Entity, &mut Notification, &mut Style, &mut Text)>,
     time: Res<Time>,
 ) {
-    for (text_id, mut timer) in &mut query {
-        timer.0.tick(time.delta());
-        if timer.0.just_finished() {
-            commands.entity(text_id).despawn_recursive();
+    for (entity, mut notification, mut style, mut text) in &mut notifications {
+        notification.age += time.delta_seconds();
+        if notification.age >= notification.duration {
+            commands.entity(entity).despawn_recursive();
+            continue;
         }
+        let shown = (notification
+            .age
+            .min(notification.duration - notification.age)
+            / NOTIFICATION_SLIDE_DURATION)
+            .min(1.0);
+        let eased = 1.0 - (1.0 - shown).powi(2);
+        style.left = Val::Px(NOTIFICATION_SLIDE_DISTANCE * (1.0 - eased));
+        text.sections[0].style.color.set_alpha(eased);
     }
 }
 fn add_game_over_text(
     mut commands: Commands,
     ui_root: Query<Entity, With<UIRoot>>,
-    mut restart_button: Query<&mut Visibility, With<RestartButton>>,
+    mut buttons: Query<&mut Visibility, With<GameOverButtons>>,
+    outcome: Res<MatchOutcome>,
+    config: Res<GameConfig>,
+    seed: Res<MatchSeed>,
+    placements: Res<Placements>,
+    stats: Res<MatchStats>,
+    colors: Res<ParticipantMap<BallColor>>,
 ) {
-    if restart_button.single() == Visibility::Visible {
+    if buttons.single() == Visibility::Visible {
         return;
     }
-    *restart_button.single_mut() = Visibility::Visible;
+    *buttons.single_mut() = Visibility::Visible;
     let text_id = commands
         .spawn(TextBundle::from_section(
-            "Game Over",
+            match *outcome {
+                MatchOutcome::Draw => "Draw",
+                _ => "Game Over",
+            },
             TextStyle {
                 font: default(),
                 font_size: GAME_OVER_TEXT_FONT_SIZE,
@@ -186,17 +433,180 @@ fn add_game_over_text(
             },
         ))
         .id();
+    let code_id = commands
+        .spawn(TextBundle::from_section(
+            format!(
+                "Seed: {}   Match code: {}",
+                seed.0,
+                match_code::encode(&config, seed.0)
+            ),
+            TextStyle {
+                font: default(),
+                font_size: MATCH_CODE_FONT_SIZE,
+                color: Color::BLACK,
+            },
+        ))
+        .id();
+    let mut standings = Participant::ALL
+        .into_iter()
+        .filter_map(|p| placements.0[p].map(|placement| (p, placement)))
+        .collect::<Vec<_>>();
+    standings.sort_by_key(|(_, placement)| placement.place);
+    let standings_id = commands
+        .spawn(TextBundle::from_sections(
+            standings
+                .into_iter()
+                .enumerate()
+                .map(|(i, (participant, placement))| {
+                    let seconds = placement.time as u32;
+                    TextSection::new(
+                        format!(
+                            "{}{} {}  {:02}:{:02}",
+                            if i == 0 { "" } else { "\n" },
+                            ordinal(placement.place),
+                            participant,
+                            seconds / 60,
+                            seconds % 60
+                        ),
+                        TextStyle {
+                            font: default(),
+                            font_size: STANDINGS_FONT_SIZE,
+                            color: colors[participant].0,
+                        },
+                    )
+                }),
+        ))
+        .id();
+    let zone_hits_id = zone_hits_table(&mut commands, &stats, &colors, &config.participants);
     commands
         .entity(ui_root.single())
-        .insert_children(0, &[text_id]);
+        .insert_children(0, &[text_id, code_id, standings_id, zone_hits_id]);
+}
+/// How many times each participant's marbles landed in each kind of trigger zone, to show who got
+/// luckier.
+fn zone_hits_table(
+    commands: &mut Commands,
+    stats: &MatchStats,
+    colors: &ParticipantMap<BallColor>,
+    seated: &ParticipantMap<bool>,
+) -> Entity {
+    let cell = |value: String, color: Color| {
+        TextBundle::from_section(
+            value,
+            TextStyle {
+                font: default(),
+                font_size: ZONE_HITS_FONT_SIZE,
+                color,
+            },
+        )
+    };
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                display: Display::Grid,
+                grid_template_columns: RepeatedGridTrack::auto(5),
+                column_gap: Val::Px(24.0),
+                margin: UiRect::top(Val::Px(12.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|table| {
+            for heading in ["", "Multiply", "Burst", "Charged", "Penalty"] {
+                table.spawn(cell(heading.to_string(), Color::BLACK));
+            }
+            for participant in Participant::ALL.into_iter().filter(|&p| seated[p]) {
+                let hits = stats.zone_hits[participant];
+                let color = colors[participant].0;
+                table.spawn(cell(participant.to_string(), color));
+                for count in [
+                    hits.multiply,
+                    hits.burst_shot,
+                    hits.charged_shot,
+                    hits.penalty,
+                ] {
+                    table.spawn(cell(count.to_string(), color));
+                }
+            }
+        })
+        .id()
+}
+fn ordinal(place: u8) -> String {
+    let suffix = match place {
+        1 => "st",
+        2 => "nd",
+        3 => "rd",
+        _ => "th",
+    };
+    format!("{}{}", place, suffix)
 }
 fn restart(
     mut commands: Commands,
-    query: Query<&Children, With<UIRoot>>,
-    mut button_visibility: Query<&mut Visibility, With<RestartButton>>,
+    query: Query<&Children, Or<(With<UIRoot>, With<NotificationStack>)>>,
+    mut button_visibility: Query<&mut Visibility, With<GameOverButtons>>,
+    mut queue: ResMut<NotificationQueue>,
 ) {
-    for &child in query.single().iter() {
+    for &child in query.iter().flatten() {
         commands.entity(child).despawn_recursive();
-        *button_visibility.single_mut() = Visibility::Hidden;
+    }
+    *button_visibility.single_mut() = Visibility::Hidden;
+    queue.0.clear();
+    commands.remove_resource::<SeedEntry>();
+}
+/// The size of the part of the world the camera always shows, whatever the shape of the window.
+pub fn play_area_size(layout: ScreenLayout) -> Vec2 {
+    match layout {
+        ScreenLayout::Landscape => Vec2::new(1280.0, 720.0),
+        ScreenLayout::Portrait => Vec2::new(760.0, 1480.0),
+    }
+}
+/// Where the camera looks when nothing moves it around.
+pub fn camera_center(layout: ScreenLayout) -> Vec2 {
+    match layout {
+        ScreenLayout::Landscape => Vec2::ZERO,
+        ScreenLayout::Portrait => Vec2::new(0.0, -370.0),
+    }
+}
+/// The size and offset of the play area within the window, in logical pixels.
+fn play_area(window: &Window, layout: ScreenLayout) -> (Vec2, Vec2) {
+    let window_size = window.size();
+    let play_area_size = play_area_size(layout);
+    let scale = (window_size / play_area_size).min_element();
+    let size = play_area_size * scale;
+    (size, (window_size - size) / 2.0)
+}
+fn update_ui_scale(
+    window: Query<&Window, With<PrimaryWindow>>,
+    config: Res<DisplayConfig>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    let Ok(window) = window.get_single() else {
+        return;
+    };
+    let (size, _) = play_area(window, config.layout);
+    let scale = size.y / play_area_size(config.layout).y * config.ui_scale;
+    if scale > 0.0 && ui_scale.0 != scale {
+        ui_scale.0 = scale;
+    }
+}
+fn layout_play_area(
+    window: Query<&Window, With<PrimaryWindow>>,
+    ui_scale: Res<UiScale>,
+    config: Res<DisplayConfig>,
+    mut nodes: Query<&mut Style, With<PlayAreaNode>>,
+) {
+    let Ok(window) = window.get_single() else {
+        return;
+    };
+    // `Val::Px` gets multiplied by the UI scale, so it has to be divided out here.
+    let (size, offset) = play_area(window, config.layout);
+    let (size, offset) = (size / ui_scale.0, offset / ui_scale.0);
+    for mut style in &mut nodes {
+        let mut new_style = style.clone();
+        new_style.left = Val::Px(offset.x);
+        new_style.top = Val::Px(offset.y);
+        new_style.width = Val::Px(size.x);
+        new_style.height = Val::Px(size.y);
+        style.set_if_neq(new_style);
     }
 }
@@ -2,8 +2,14 @@ use std::ops::{Index, IndexMut};
 
 use bevy::{color::palettes::css, prelude::*};
 use bevy_hanabi::prelude::*;
+use rand::{thread_rng, Rng};
+use serde::{Deserialize, Serialize};
 
-use crate::panel_plugin::{WORKER_BALL_COUNT_MAX, WORKER_BALL_RADIUS};
+use crate::{
+    battlefield::RestartEvent,
+    config::GameConfig,
+    panel_plugin::{WORKER_BALL_COUNT_MAX, WORKER_BALL_RADIUS},
+};
 
 // Constants {{{
 
@@ -26,18 +32,33 @@ pub const SPAWN_COLOR_PROPERTY: &str = "spawn_color";
 const POSITION_PROPERTY: &str = "position";
 const BULLET_VEL_PROPERTY: &str = "bullet_vel";
 
+const LOW_SPEC_MAX_BULLETS: usize = 200;
+
 // }}}
 
 pub struct UtilsPlugin;
 impl Plugin for UtilsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            PreStartup,
-            (
-                setup_participant_maps,
-                (setup_tile_hit_effect, setup_trail_effect).after(setup_participant_maps),
-            ),
-        );
+        app.init_resource::<ScreenShake>()
+            .add_systems(
+                PreStartup,
+                (
+                    setup_participant_maps,
+                    setup_entity_budget,
+                    (setup_tile_hit_effect, setup_trail_effect).after(setup_participant_maps),
+                ),
+            )
+            .add_systems(
+                PreUpdate,
+                (
+                    clear_screen_shake,
+                    setup_entity_budget.run_if(on_event::<RestartEvent>()),
+                ),
+            )
+            .add_systems(
+                PostUpdate,
+                apply_screen_shake.before(TransformSystem::TransformPropagate),
+            );
     }
 }
 
@@ -47,7 +68,7 @@ pub struct TileColor(pub Color);
 pub struct BallColor(pub Color);
 
 /// A struct that maps a value to each participant.
-#[derive(Debug, Clone, Copy, Default, Resource)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Resource, Serialize, Deserialize)]
 pub struct ParticipantMap<T> {
     // {{{
     pub a: T,
@@ -112,7 +133,7 @@ impl<T: Copy> ParticipantMap<T> {
     }
 }
 
-#[derive(Debug, Component, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Component, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 /// A game participant. It's not called player since the game is not interactive.
 pub enum Participant {
     #[default]
@@ -123,6 +144,15 @@ pub enum Participant {
 }
 impl Participant {
     pub const ALL: [Self; 4] = [Self::A, Self::B, Self::C, Self::D];
+    /// The first letter of the name, to mark things as theirs without relying on color.
+    pub fn initial(self) -> char {
+        match self {
+            Participant::A => 'R',
+            Participant::B => 'G',
+            Participant::C => 'V',
+            Participant::D => 'Y',
+        }
+    }
 }
 impl std::fmt::Display for Participant {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -150,6 +180,51 @@ impl Default for EffectLifetimeTimer {
     }
 }
 
+/// Shakes the camera for a while. The offset is only applied for rendering and is taken back out
+/// at the start of every frame, so other systems can move the camera as usual.
+#[derive(Debug, Clone, Copy, Default, Resource)]
+pub struct ScreenShake {
+    remaining: f32,
+    duration: f32,
+    intensity: f32,
+    offset: Vec2,
+}
+impl ScreenShake {
+    /// Starts a shake, or extends the current one if it's weaker or ends sooner.
+    pub fn start(&mut self, duration: f32, intensity: f32) {
+        if self.remaining <= 0.0 {
+            self.intensity = 0.0;
+        }
+        self.remaining = self.remaining.max(duration);
+        self.duration = self.remaining;
+        self.intensity = self.intensity.max(intensity);
+    }
+}
+
+/// Limits on what a match may spawn, taken from the config when the match starts.
+#[derive(Debug, Clone, Copy, Resource)]
+pub struct EntityBudget {
+    /// The most bullets that can be on the battlefield at once. Turrets hold their shots while the
+    /// battlefield is full.
+    pub max_bullets: Option<usize>,
+    /// Whether worker balls leave trails.
+    pub trails: bool,
+    /// Whether captured tiles burst into particles rather than just flash.
+    pub particles: bool,
+}
+impl EntityBudget {
+    fn new(config: &GameConfig) -> Self {
+        Self {
+            max_bullets: config.low_spec.then_some(LOW_SPEC_MAX_BULLETS),
+            trails: !config.low_spec,
+            particles: !config.low_spec,
+        }
+    }
+}
+
+fn setup_entity_budget(mut commands: Commands, config: Res<GameConfig>) {
+    commands.insert_resource(EntityBudget::new(&config));
+}
 fn setup_participant_maps(mut commands: Commands, mut materials: ResMut<Assets<ColorMaterial>>) {
     commands.insert_resource(ParticipantMap::splat(true));
     commands.insert_resource(PARTICIPANT_COLORS.map(Color::Srgba).map(TileColor));
@@ -158,7 +233,11 @@ fn setup_participant_maps(mut commands: Commands, mut materials: ResMut<Assets<C
         BALL_COLORS.map(|srgba| materials.add(ColorMaterial::from(Color::from(srgba)))),
     );
 }
-fn setup_tile_hit_effect(mut commands: Commands, mut effects: ResMut<Assets<EffectAsset>>) {
+// The particle effects are left out when Hanabi isn't loaded, like in headless runs.
+fn setup_tile_hit_effect(mut commands: Commands, effects: Option<ResMut<Assets<EffectAsset>>>) {
+    let Some(mut effects) = effects else {
+        return;
+    };
     // Set `spawn_immediately` to false to spawn on command with Spawner::reset()
     let spawner = Spawner::once(HIT_PARTICLE_COUNT.into(), true);
 
@@ -232,7 +311,10 @@ fn setup_tile_hit_effect(mut commands: Commands, mut effects: ResMut<Assets<Effe
 
     commands.insert_resource(TileHitEffect(effect));
 }
-fn setup_trail_effect(mut commands: Commands, mut effects: ResMut<Assets<EffectAsset>>) {
+fn setup_trail_effect(mut commands: Commands, effects: Option<ResMut<Assets<EffectAsset>>>) {
+    let Some(mut effects) = effects else {
+        return;
+    };
     let writer = ExprWriter::default();
 
     let pos = writer.add_property(POSITION_PROPERTY, Vec3::ZERO.into());
@@ -370,3 +452,31 @@ impl EffectPropertiesExt for EffectProperties {
         self.set("position", position.into());
     }
 }
+fn clear_screen_shake(
+    mut shake: ResMut<ScreenShake>,
+    mut camera: Query<&mut Transform, With<Camera2d>>,
+) {
+    if shake.offset == Vec2::ZERO {
+        return;
+    }
+    for mut transform in &mut camera {
+        transform.translation -= shake.offset.extend(0.0);
+    }
+    shake.offset = Vec2::ZERO;
+}
+fn apply_screen_shake(
+    mut shake: ResMut<ScreenShake>,
+    mut camera: Query<&mut Transform, With<Camera2d>>,
+    time: Res<Time>,
+) {
+    if shake.remaining <= 0.0 {
+        return;
+    }
+    shake.remaining -= time.delta_seconds();
+    let strength = shake.intensity * (shake.remaining / shake.duration).max(0.0);
+    let mut rng = thread_rng();
+    shake.offset = Vec2::new(rng.gen_range(-1.0..=1.0), rng.gen_range(-1.0..=1.0)) * strength;
+    for mut transform in &mut camera {
+        transform.translation += shake.offset.extend(0.0);
+    }
+}